@@ -0,0 +1,185 @@
+//! Thickens a sound by mixing it with slightly delayed and
+//! detuned copies of itself.
+
+use kira::{
+	clock::ClockId,
+	manager::resources::Parameters,
+	track::Effect,
+	value::{cached::CachedValue, Smoothing, Value},
+	Frame,
+};
+
+use crate::{
+	modulation::{ModulatedDelayLine, ModulationLfo},
+	tail::feedback_tail_length,
+};
+
+/// The delay time (in seconds) the chorus modulates around.
+const BASE_DELAY_TIME: f64 = 0.02;
+/// How far (in seconds) the delay time moves from the base
+/// delay time at full depth.
+const MAX_DELAY_TIME_SWING: f64 = 0.01;
+
+/// Settings for a [`Chorus`].
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(
+	feature = "serde_support",
+	derive(serde::Serialize, serde::Deserialize)
+)]
+#[cfg_attr(feature = "serde_support", serde(default))]
+pub struct ChorusSettings {
+	/// How many times the delay time oscillates per second,
+	/// or per tick if the chorus is synced to a clock.
+	pub rate: Value,
+	/// How much the delay time oscillates, from 0.0 to 1.0.
+	pub depth: Value,
+	/// How much of the output is fed back into the chorus.
+	pub feedback: Value,
+	/// The balance between dry (unaffected) signal and wet
+	/// (affected) signal to output. 0.0 is fully dry,
+	/// 1.0 is fully wet.
+	pub mix: Value,
+	/// The clock the oscillation should be synced to, if any.
+	#[cfg_attr(feature = "serde_support", serde(skip))]
+	pub clock: Option<ClockId>,
+	/// How the settings move to new values after they're changed.
+	///
+	/// If `None`, the default smoothing of the audio manager
+	/// will be used.
+	pub smoothing: Option<Smoothing>,
+}
+
+impl ChorusSettings {
+	/// Creates a new `ChorusSettings` with the default settings.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Sets how many times the delay time oscillates per second,
+	/// or per tick if the chorus is synced to a clock.
+	pub fn rate(self, rate: impl Into<Value>) -> Self {
+		Self {
+			rate: rate.into(),
+			..self
+		}
+	}
+
+	/// Sets how much the delay time oscillates, from 0.0 to 1.0.
+	pub fn depth(self, depth: impl Into<Value>) -> Self {
+		Self {
+			depth: depth.into(),
+			..self
+		}
+	}
+
+	/// Sets how much of the output is fed back into the chorus.
+	pub fn feedback(self, feedback: impl Into<Value>) -> Self {
+		Self {
+			feedback: feedback.into(),
+			..self
+		}
+	}
+
+	/// Sets the balance between dry (unaffected) signal and wet
+	/// (affected) signal to output. 0.0 is fully dry, 1.0 is fully wet.
+	pub fn mix(self, mix: impl Into<Value>) -> Self {
+		Self {
+			mix: mix.into(),
+			..self
+		}
+	}
+
+	/// Sets the clock the oscillation should be synced to, if any.
+	pub fn clock(self, clock: impl Into<Option<ClockId>>) -> Self {
+		Self {
+			clock: clock.into(),
+			..self
+		}
+	}
+
+	/// Sets how the settings move to new values after they're changed.
+	pub fn smoothing(self, smoothing: impl Into<Option<Smoothing>>) -> Self {
+		Self {
+			smoothing: smoothing.into(),
+			..self
+		}
+	}
+}
+
+impl Default for ChorusSettings {
+	fn default() -> Self {
+		Self {
+			rate: Value::Fixed(0.5),
+			depth: Value::Fixed(0.5),
+			feedback: Value::Fixed(0.0),
+			mix: Value::Fixed(0.5),
+			clock: None,
+			smoothing: None,
+		}
+	}
+}
+
+/// An effect that mixes input audio with copies of itself
+/// whose delay time slowly oscillates.
+///
+/// The left and right channels are modulated out of phase
+/// to widen the sound.
+pub struct Chorus {
+	rate: CachedValue,
+	depth: CachedValue,
+	feedback: CachedValue,
+	mix: CachedValue,
+	lfo: ModulationLfo,
+	delay_line: Option<ModulatedDelayLine>,
+}
+
+impl Chorus {
+	/// Creates a new chorus effect.
+	pub fn new(settings: ChorusSettings) -> Self {
+		Self {
+			rate: CachedValue::new(0.0.., settings.rate, 0.5).with_smoothing(settings.smoothing),
+			depth: CachedValue::new(0.0..=1.0, settings.depth, 0.5)
+				.with_smoothing(settings.smoothing),
+			feedback: CachedValue::new(-1.0..=1.0, settings.feedback, 0.0)
+				.with_smoothing(settings.smoothing),
+			mix: CachedValue::new(0.0..=1.0, settings.mix, 0.5).with_smoothing(settings.smoothing),
+			lfo: ModulationLfo::new(settings.clock),
+			delay_line: None,
+		}
+	}
+}
+
+impl Effect for Chorus {
+	fn init(&mut self, sample_rate: u32) {
+		self.delay_line = Some(ModulatedDelayLine::new(
+			BASE_DELAY_TIME + MAX_DELAY_TIME_SWING,
+			sample_rate,
+		));
+	}
+
+	fn tail_length(&self) -> Option<f64> {
+		feedback_tail_length(BASE_DELAY_TIME + MAX_DELAY_TIME_SWING, self.feedback.get())
+	}
+
+	fn process(&mut self, input: Frame, dt: f64, parameters: &Parameters) -> Frame {
+		self.rate.update(parameters);
+		self.depth.update(parameters);
+		self.feedback.update(parameters);
+		self.mix.update(parameters);
+		self.lfo.update(self.rate.get(), dt, parameters);
+		let delay_line = self
+			.delay_line
+			.as_mut()
+			.expect("The chorus should be initialized before the first process call");
+		let swing = MAX_DELAY_TIME_SWING * self.depth.get();
+		let left_delay = (BASE_DELAY_TIME + swing * self.lfo.value(0.0)) / dt;
+		let right_delay = (BASE_DELAY_TIME + swing * self.lfo.value(0.25)) / dt;
+		let output = Frame::new(
+			delay_line.read(left_delay).left,
+			delay_line.read(right_delay).right,
+		);
+		delay_line.write(input + output * self.feedback.get() as f32);
+		let mix = self.mix.get() as f32;
+		output * mix + input * (1.0 - mix)
+	}
+}
@@ -0,0 +1,247 @@
+//! Reduces the dynamic range of a sound.
+//!
+//! A compressor can also be keyed from another mixer track
+//! by setting a sidechain track. For example, a compressor on
+//! a music track with a dialog track as its sidechain will
+//! turn the music down whenever someone is talking.
+
+use kira::{
+	manager::resources::Parameters,
+	track::{Effect, TrackId},
+	value::{cached::CachedValue, Smoothing, Value},
+	Frame,
+};
+
+use crate::tail::one_pole_tail_length;
+
+/// Settings for a [`Compressor`].
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(
+	feature = "serde_support",
+	derive(serde::Serialize, serde::Deserialize)
+)]
+#[cfg_attr(feature = "serde_support", serde(default))]
+pub struct CompressorSettings {
+	/// The volume (in decibels) above which the signal
+	/// will be turned down.
+	pub threshold: Value,
+	/// How much the signal above the threshold will be
+	/// turned down. A ratio of 4.0 means that for every 4 decibels
+	/// the signal goes above the threshold, the output will only
+	/// go 1 decibel above the threshold.
+	pub ratio: Value,
+	/// The width (in decibels) of the range around the threshold
+	/// where the compression is gradually applied.
+	pub knee: Value,
+	/// How quickly the compressor responds to the signal
+	/// getting louder (in seconds).
+	pub attack: Value,
+	/// How quickly the compressor responds to the signal
+	/// getting quieter (in seconds).
+	pub release: Value,
+	/// The amount to turn up the output (in decibels) after
+	/// compression.
+	pub makeup_gain: Value,
+	/// A mixer track whose output should be used to decide
+	/// how much to compress the signal. If `None`, the
+	/// input signal will be used.
+	#[cfg_attr(feature = "serde_support", serde(skip))]
+	pub sidechain: Option<TrackId>,
+	/// How the settings move to new values after they're changed.
+	///
+	/// If `None`, the default smoothing of the audio manager
+	/// will be used.
+	pub smoothing: Option<Smoothing>,
+}
+
+impl CompressorSettings {
+	/// Creates a new `CompressorSettings` with the default settings.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Sets the volume (in decibels) above which the signal
+	/// will be turned down.
+	pub fn threshold(self, threshold: impl Into<Value>) -> Self {
+		Self {
+			threshold: threshold.into(),
+			..self
+		}
+	}
+
+	/// Sets how much the signal above the threshold will be
+	/// turned down.
+	pub fn ratio(self, ratio: impl Into<Value>) -> Self {
+		Self {
+			ratio: ratio.into(),
+			..self
+		}
+	}
+
+	/// Sets the width (in decibels) of the range around the
+	/// threshold where the compression is gradually applied.
+	pub fn knee(self, knee: impl Into<Value>) -> Self {
+		Self {
+			knee: knee.into(),
+			..self
+		}
+	}
+
+	/// Sets how quickly the compressor responds to the signal
+	/// getting louder (in seconds).
+	pub fn attack(self, attack: impl Into<Value>) -> Self {
+		Self {
+			attack: attack.into(),
+			..self
+		}
+	}
+
+	/// Sets how quickly the compressor responds to the signal
+	/// getting quieter (in seconds).
+	pub fn release(self, release: impl Into<Value>) -> Self {
+		Self {
+			release: release.into(),
+			..self
+		}
+	}
+
+	/// Sets the amount to turn up the output (in decibels)
+	/// after compression.
+	pub fn makeup_gain(self, makeup_gain: impl Into<Value>) -> Self {
+		Self {
+			makeup_gain: makeup_gain.into(),
+			..self
+		}
+	}
+
+	/// Sets the mixer track whose output should be used to decide
+	/// how much to compress the signal.
+	pub fn sidechain(self, track: impl Into<TrackId>) -> Self {
+		Self {
+			sidechain: Some(track.into()),
+			..self
+		}
+	}
+
+	/// Sets how the settings move to new values after they're changed.
+	pub fn smoothing(self, smoothing: impl Into<Option<Smoothing>>) -> Self {
+		Self {
+			smoothing: smoothing.into(),
+			..self
+		}
+	}
+}
+
+impl Default for CompressorSettings {
+	fn default() -> Self {
+		Self {
+			threshold: Value::Fixed(-24.0),
+			ratio: Value::Fixed(4.0),
+			knee: Value::Fixed(6.0),
+			attack: Value::Fixed(0.01),
+			release: Value::Fixed(0.1),
+			makeup_gain: Value::Fixed(0.0),
+			sidechain: None,
+			smoothing: None,
+		}
+	}
+}
+
+/// An effect that turns down loud parts of the input audio.
+pub struct Compressor {
+	threshold: CachedValue,
+	ratio: CachedValue,
+	knee: CachedValue,
+	attack: CachedValue,
+	release: CachedValue,
+	makeup_gain: CachedValue,
+	sidechain: Option<TrackId>,
+	sidechain_input: Frame,
+	gain_reduction: f64,
+}
+
+impl Compressor {
+	/// Creates a new compressor.
+	pub fn new(settings: CompressorSettings) -> Self {
+		Self {
+			threshold: CachedValue::new(.., settings.threshold, -24.0)
+				.with_smoothing(settings.smoothing),
+			ratio: CachedValue::new(1.0.., settings.ratio, 4.0).with_smoothing(settings.smoothing),
+			knee: CachedValue::new(0.0.., settings.knee, 6.0).with_smoothing(settings.smoothing),
+			attack: CachedValue::new(0.0.., settings.attack, 0.01)
+				.with_smoothing(settings.smoothing),
+			release: CachedValue::new(0.0.., settings.release, 0.1)
+				.with_smoothing(settings.smoothing),
+			makeup_gain: CachedValue::new(.., settings.makeup_gain, 0.0)
+				.with_smoothing(settings.smoothing),
+			sidechain: settings.sidechain,
+			sidechain_input: Frame::ZERO,
+			gain_reduction: 0.0,
+		}
+	}
+
+	/// Returns the amount (in decibels) that a signal at
+	/// the given level should be turned down by.
+	fn target_gain_reduction(&self, level: f64) -> f64 {
+		let threshold = self.threshold.get();
+		let ratio = self.ratio.get();
+		let knee = self.knee.get();
+		let overshoot = level - threshold;
+		if knee > 0.0 && (2.0 * overshoot).abs() <= knee {
+			(1.0 / ratio - 1.0) * (overshoot + knee / 2.0).powi(2) / (2.0 * knee)
+		} else if overshoot > 0.0 {
+			overshoot / ratio - overshoot
+		} else {
+			0.0
+		}
+	}
+}
+
+impl Effect for Compressor {
+	fn sidechain(&self) -> Option<TrackId> {
+		self.sidechain
+	}
+
+	fn receive_sidechain_input(&mut self, input: Frame) {
+		self.sidechain_input = input;
+	}
+
+	fn tail_length(&self) -> Option<f64> {
+		Some(one_pole_tail_length(self.release.get()))
+	}
+
+	fn process(&mut self, input: Frame, dt: f64, parameters: &Parameters) -> Frame {
+		self.threshold.update(parameters);
+		self.ratio.update(parameters);
+		self.knee.update(parameters);
+		self.attack.update(parameters);
+		self.release.update(parameters);
+		self.makeup_gain.update(parameters);
+		let key = if self.sidechain.is_some() {
+			self.sidechain_input
+		} else {
+			input
+		};
+		let level = amplitude_to_decibels(key.left.abs().max(key.right.abs()) as f64);
+		let target = self.target_gain_reduction(level);
+		let response_time = if target < self.gain_reduction {
+			self.attack.get()
+		} else {
+			self.release.get()
+		};
+		self.gain_reduction = if response_time > 0.0 {
+			target + (self.gain_reduction - target) * (-dt / response_time).exp()
+		} else {
+			target
+		};
+		input * decibels_to_amplitude(self.gain_reduction + self.makeup_gain.get()) as f32
+	}
+}
+
+pub(crate) fn amplitude_to_decibels(amplitude: f64) -> f64 {
+	20.0 * amplitude.log10()
+}
+
+pub(crate) fn decibels_to_amplitude(decibels: f64) -> f64 {
+	10.0f64.powf(decibels / 20.0)
+}
@@ -0,0 +1,319 @@
+//! Adds the reverberations of a recorded space to a sound.
+
+use std::{sync::Arc, time::Duration};
+
+use kira::{
+	manager::resources::Parameters,
+	sound::{static_sound::StaticSound, Sound},
+	track::Effect,
+	value::{cached::CachedValue, Smoothing, Value},
+	Frame,
+};
+use rustfft::{num_complex::Complex, Fft, FftPlanner};
+
+/// The number of frames processed at a time. The wet signal
+/// is delayed by this many frames.
+const BLOCK_SIZE: usize = 256;
+const FFT_SIZE: usize = BLOCK_SIZE * 2;
+
+/// Settings for a [`ConvolutionReverb`].
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(
+	feature = "serde_support",
+	derive(serde::Serialize, serde::Deserialize)
+)]
+#[cfg_attr(feature = "serde_support", serde(default))]
+pub struct ConvolutionReverbSettings {
+	/// The amount of time before the reverberations start.
+	///
+	/// The reverberations are always delayed by at least
+	/// 256 frames.
+	pub pre_delay: Duration,
+	/// The balance between dry (unaffected) signal and wet
+	/// (affected) signal to output. 0.0 is fully dry,
+	/// 1.0 is fully wet.
+	pub mix: Value,
+	/// How the settings move to new values after they're changed.
+	///
+	/// If `None`, the default smoothing of the audio manager
+	/// will be used.
+	pub smoothing: Option<Smoothing>,
+}
+
+impl ConvolutionReverbSettings {
+	/// Creates a new `ConvolutionReverbSettings` with the default settings.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Sets the amount of time before the reverberations start.
+	pub fn pre_delay(self, pre_delay: Duration) -> Self {
+		Self { pre_delay, ..self }
+	}
+
+	/// Sets the balance between dry (unaffected) signal and wet
+	/// (affected) signal to output. 0.0 is fully dry, 1.0 is fully wet.
+	pub fn mix(self, mix: impl Into<Value>) -> Self {
+		Self {
+			mix: mix.into(),
+			..self
+		}
+	}
+
+	/// Sets how the settings move to new values after they're changed.
+	pub fn smoothing(self, smoothing: impl Into<Option<Smoothing>>) -> Self {
+		Self {
+			smoothing: smoothing.into(),
+			..self
+		}
+	}
+}
+
+impl Default for ConvolutionReverbSettings {
+	fn default() -> Self {
+		Self {
+			pre_delay: Duration::ZERO,
+			mix: Value::Fixed(0.5),
+			smoothing: None,
+		}
+	}
+}
+
+/// Convolves one channel of audio with one channel of an
+/// impulse response using uniformly partitioned overlap-save
+/// convolution.
+struct Convolver {
+	/// The spectrum of each block of the impulse response.
+	partitions: Vec<Vec<Complex<f32>>>,
+	/// The spectra of the most recent blocks of input,
+	/// used as a ring buffer.
+	input_spectra: Vec<Vec<Complex<f32>>>,
+	/// The position in `input_spectra` of the newest spectrum.
+	input_spectra_position: usize,
+	/// The last two blocks of input.
+	input: Vec<f32>,
+	accumulator: Vec<Complex<f32>>,
+	output: Vec<f32>,
+}
+
+impl Convolver {
+	fn new(impulse_response: &[f32], fft: &dyn Fft<f32>, scratch: &mut [Complex<f32>]) -> Self {
+		let partitions: Vec<Vec<Complex<f32>>> = impulse_response
+			.chunks(BLOCK_SIZE)
+			.map(|chunk| {
+				let mut partition = vec![Complex::new(0.0, 0.0); FFT_SIZE];
+				for (i, sample) in chunk.iter().enumerate() {
+					// scale down now so the inverse FFT
+					// doesn't need to be normalized
+					partition[i] = Complex::new(sample / FFT_SIZE as f32, 0.0);
+				}
+				fft.process_with_scratch(&mut partition, scratch);
+				partition
+			})
+			.collect();
+		let num_partitions = partitions.len();
+		Self {
+			partitions,
+			input_spectra: vec![vec![Complex::new(0.0, 0.0); FFT_SIZE]; num_partitions],
+			input_spectra_position: 0,
+			input: vec![0.0; FFT_SIZE],
+			accumulator: vec![Complex::new(0.0, 0.0); FFT_SIZE],
+			output: vec![0.0; BLOCK_SIZE],
+		}
+	}
+
+	/// Adds a sample to the current block of input.
+	fn push_input(&mut self, index: usize, sample: f32) {
+		self.input[BLOCK_SIZE + index] = sample;
+	}
+
+	/// Gets a sample from the most recent block of output.
+	fn output(&self, index: usize) -> f32 {
+		self.output[index]
+	}
+
+	/// Convolves the most recent block of input with the
+	/// impulse response.
+	fn process_block(
+		&mut self,
+		fft: &dyn Fft<f32>,
+		inverse_fft: &dyn Fft<f32>,
+		scratch: &mut [Complex<f32>],
+	) {
+		if self.partitions.is_empty() {
+			return;
+		}
+		self.input_spectra_position = (self.input_spectra_position + 1) % self.partitions.len();
+		let spectrum = &mut self.input_spectra[self.input_spectra_position];
+		for (bin, sample) in spectrum.iter_mut().zip(self.input.iter()) {
+			*bin = Complex::new(*sample, 0.0);
+		}
+		fft.process_with_scratch(spectrum, scratch);
+		// the newest input spectrum is multiplied with the first
+		// partition, the one before it with the second partition,
+		// and so on
+		for bin in &mut self.accumulator {
+			*bin = Complex::new(0.0, 0.0);
+		}
+		for (i, partition) in self.partitions.iter().enumerate() {
+			let spectrum_index =
+				(self.input_spectra_position + self.partitions.len() - i) % self.partitions.len();
+			let spectrum = &self.input_spectra[spectrum_index];
+			for ((bin, input), impulse) in self
+				.accumulator
+				.iter_mut()
+				.zip(spectrum.iter())
+				.zip(partition.iter())
+			{
+				*bin += input * impulse;
+			}
+		}
+		inverse_fft.process_with_scratch(&mut self.accumulator, scratch);
+		// the first half of the result is affected by circular
+		// convolution and gets thrown away
+		for (output, bin) in self
+			.output
+			.iter_mut()
+			.zip(self.accumulator[BLOCK_SIZE..].iter())
+		{
+			*output = bin.re;
+		}
+		self.input.copy_within(BLOCK_SIZE.., 0);
+	}
+}
+
+enum ConvolutionReverbState {
+	Uninitialized {
+		impulse_response: StaticSound,
+	},
+	Initialized {
+		fft: Arc<dyn Fft<f32>>,
+		inverse_fft: Arc<dyn Fft<f32>>,
+		scratch: Vec<Complex<f32>>,
+		convolvers: (Convolver, Convolver),
+		pre_delay_buffer: Vec<Frame>,
+		pre_delay_position: usize,
+		block_position: usize,
+	},
+}
+
+/// A reverb effect that convolves the input audio with an
+/// impulse response recorded in a real (or simulated) space.
+pub struct ConvolutionReverb {
+	pre_delay: Duration,
+	tail_length: f64,
+	mix: CachedValue,
+	state: ConvolutionReverbState,
+}
+
+impl ConvolutionReverb {
+	/// Creates a new `ConvolutionReverb` effect.
+	///
+	/// The left and right channels of the impulse response
+	/// are applied to the left and right channels of the input.
+	/// The impulse response will be resampled to the sample
+	/// rate of the audio output if needed.
+	pub fn new(impulse_response: StaticSound, settings: ConvolutionReverbSettings) -> Self {
+		Self {
+			pre_delay: settings.pre_delay,
+			tail_length: 0.0,
+			mix: CachedValue::new(0.0..=1.0, settings.mix, 0.5).with_smoothing(settings.smoothing),
+			state: ConvolutionReverbState::Uninitialized { impulse_response },
+		}
+	}
+}
+
+impl Effect for ConvolutionReverb {
+	fn init(&mut self, sample_rate: u32) {
+		if let ConvolutionReverbState::Uninitialized { impulse_response } = &mut self.state {
+			let num_frames =
+				(impulse_response.duration.as_secs_f64() * sample_rate as f64).round() as usize;
+			// resampling the impulse response changes how many frames
+			// of it are summed together, so the volume needs to be
+			// adjusted to make up for that
+			let volume = impulse_response.sample_rate as f32 / sample_rate as f32;
+			let (left, right): (Vec<f32>, Vec<f32>) = (0..num_frames)
+				.map(|i| {
+					let frame = impulse_response
+						.frame_at_position(i as f64 / sample_rate as f64)
+						.unwrap_or(Frame::ZERO)
+						* volume;
+					(frame.left, frame.right)
+				})
+				.unzip();
+			let mut planner = FftPlanner::new();
+			let fft = planner.plan_fft_forward(FFT_SIZE);
+			let inverse_fft = planner.plan_fft_inverse(FFT_SIZE);
+			let mut scratch = vec![
+				Complex::new(0.0, 0.0);
+				fft.get_inplace_scratch_len()
+					.max(inverse_fft.get_inplace_scratch_len())
+			];
+			let convolvers = (
+				Convolver::new(&left, fft.as_ref(), &mut scratch),
+				Convolver::new(&right, fft.as_ref(), &mut scratch),
+			);
+			// the block processing already delays the wet signal,
+			// so the pre-delay buffer only needs to make up the difference
+			self.tail_length = (num_frames + BLOCK_SIZE) as f64 / sample_rate as f64
+				+ self.pre_delay.as_secs_f64();
+			let pre_delay_frames = ((self.pre_delay.as_secs_f64() * sample_rate as f64) as usize)
+				.saturating_sub(BLOCK_SIZE);
+			self.state = ConvolutionReverbState::Initialized {
+				fft,
+				inverse_fft,
+				scratch,
+				convolvers,
+				pre_delay_buffer: vec![Frame::ZERO; pre_delay_frames + 1],
+				pre_delay_position: 0,
+				block_position: 0,
+			};
+		} else {
+			panic!("ConvolutionReverb should be in the uninitialized state before init");
+		}
+	}
+
+	fn tail_length(&self) -> Option<f64> {
+		Some(self.tail_length)
+	}
+
+	fn process(&mut self, input: Frame, _dt: f64, parameters: &Parameters) -> Frame {
+		self.mix.update(parameters);
+		if let ConvolutionReverbState::Initialized {
+			fft,
+			inverse_fft,
+			scratch,
+			convolvers,
+			pre_delay_buffer,
+			pre_delay_position,
+			block_position,
+		} = &mut self.state
+		{
+			pre_delay_buffer[*pre_delay_position] = input;
+			*pre_delay_position = (*pre_delay_position + 1) % pre_delay_buffer.len();
+			let delayed_input = pre_delay_buffer[*pre_delay_position];
+			convolvers.0.push_input(*block_position, delayed_input.left);
+			convolvers
+				.1
+				.push_input(*block_position, delayed_input.right);
+			let output = Frame::new(
+				convolvers.0.output(*block_position),
+				convolvers.1.output(*block_position),
+			);
+			*block_position += 1;
+			if *block_position >= BLOCK_SIZE {
+				*block_position = 0;
+				convolvers
+					.0
+					.process_block(fft.as_ref(), inverse_fft.as_ref(), scratch);
+				convolvers
+					.1
+					.process_block(fft.as_ref(), inverse_fft.as_ref(), scratch);
+			}
+			let mix = self.mix.get() as f32;
+			output * mix + input * (1.0 - mix)
+		} else {
+			panic!("ConvolutionReverb should be initialized before the first process call")
+		}
+	}
+}
@@ -1,28 +1,57 @@
 //! Adds echoes to a sound.
 
 use kira::{
+	clock::ClockId,
 	manager::resources::Parameters,
 	track::Effect,
-	util,
-	value::{cached::CachedValue, Value},
+	value::{cached::CachedValue, Smoothing, Value},
 	Frame,
 };
 
 use super::filter::{Filter, FilterSettings};
+use crate::{
+	modulation::ModulatedDelayLine,
+	tail::{feedback_tail_length, FILTER_TAIL_LENGTH},
+};
+
+/// How quickly the ducking responds to input getting louder (in seconds).
+const DUCKING_ATTACK: f64 = 0.01;
+/// How quickly the ducking responds to input getting quieter (in seconds).
+const DUCKING_RELEASE: f64 = 0.25;
 
 /// Settings for a [`Delay`] effect.
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(
+	feature = "serde_support",
+	derive(serde::Serialize, serde::Deserialize)
+)]
+#[cfg_attr(feature = "serde_support", serde(default))]
 pub struct DelaySettings {
-	/// The delay time (in seconds).
-	delay_time: Value,
+	/// The delay time of the left channel (in seconds, or in ticks
+	/// if the delay is synced to a clock).
+	left_delay_time: Value,
+	/// The delay time of the right channel (in seconds, or in ticks
+	/// if the delay is synced to a clock).
+	right_delay_time: Value,
+	/// The clock the delay times should be measured in, if any.
+	#[cfg_attr(feature = "serde_support", serde(skip))]
+	clock: Option<ClockId>,
 	/// The amount of feedback.
 	feedback: Value,
+	/// Whether the echoes should alternate between the left
+	/// and right channels.
+	ping_pong: bool,
+	/// How much the echoes are turned down while there's input
+	/// audio, from 0.0 (not at all) to 1.0 (fully).
+	ducking: Value,
 	/// The amount of audio the delay can store.
 	/// This affects the maximum delay time.
 	buffer_length: f64,
 	/// Whether a filter should be added to the feedback loop,
 	/// and if so, the settings to use for the filter.
 	filter_settings: Option<FilterSettings>,
+	/// How the settings move to new values after they're changed.
+	smoothing: Option<Smoothing>,
 }
 
 impl DelaySettings {
@@ -31,10 +60,42 @@ impl DelaySettings {
 		Self::default()
 	}
 
-	/// Sets the delay time (in seconds).
+	/// Sets the delay time of both channels (in seconds, or in
+	/// ticks if the delay is synced to a clock).
 	pub fn delay_time(self, delay_time: impl Into<Value>) -> Self {
+		let delay_time = delay_time.into();
+		Self {
+			left_delay_time: delay_time,
+			right_delay_time: delay_time,
+			..self
+		}
+	}
+
+	/// Sets the delay time of the left channel (in seconds, or in
+	/// ticks if the delay is synced to a clock).
+	pub fn left_delay_time(self, left_delay_time: impl Into<Value>) -> Self {
+		Self {
+			left_delay_time: left_delay_time.into(),
+			..self
+		}
+	}
+
+	/// Sets the delay time of the right channel (in seconds, or in
+	/// ticks if the delay is synced to a clock).
+	pub fn right_delay_time(self, right_delay_time: impl Into<Value>) -> Self {
+		Self {
+			right_delay_time: right_delay_time.into(),
+			..self
+		}
+	}
+
+	/// Sets the clock the delay times should be measured in, if any.
+	///
+	/// If set, the delay times are in ticks of the clock, so the
+	/// echoes follow changes to the clock's interval.
+	pub fn clock(self, clock: impl Into<Option<ClockId>>) -> Self {
 		Self {
-			delay_time: delay_time.into(),
+			clock: clock.into(),
 			..self
 		}
 	}
@@ -47,6 +108,24 @@ impl DelaySettings {
 		}
 	}
 
+	/// Sets whether the echoes should alternate between the left
+	/// and right channels.
+	///
+	/// In ping-pong mode, the left and right channels of the input
+	/// are mixed together and sent to the left channel first.
+	pub fn ping_pong(self, ping_pong: bool) -> Self {
+		Self { ping_pong, ..self }
+	}
+
+	/// Sets how much the echoes are turned down while there's
+	/// input audio, from 0.0 (not at all) to 1.0 (fully).
+	pub fn ducking(self, ducking: impl Into<Value>) -> Self {
+		Self {
+			ducking: ducking.into(),
+			..self
+		}
+	}
+
 	/// Sets the amount of audio the delay can store.
 	pub fn buffer_length(self, buffer_length: f64) -> Self {
 		Self {
@@ -63,35 +142,53 @@ impl DelaySettings {
 			..self
 		}
 	}
+
+	/// Sets how the settings move to new values after they're changed.
+	///
+	/// If `None`, the default smoothing of the audio manager
+	/// will be used.
+	pub fn smoothing(self, smoothing: impl Into<Option<Smoothing>>) -> Self {
+		Self {
+			smoothing: smoothing.into(),
+			..self
+		}
+	}
 }
 
 impl Default for DelaySettings {
 	fn default() -> Self {
 		Self {
-			delay_time: Value::Fixed(0.5),
+			left_delay_time: Value::Fixed(0.5),
+			right_delay_time: Value::Fixed(0.5),
+			clock: None,
 			feedback: Value::Fixed(0.5),
+			ping_pong: false,
+			ducking: Value::Fixed(0.0),
 			buffer_length: 10.0,
 			filter_settings: None,
+			smoothing: None,
 		}
 	}
 }
 
-#[derive(Debug, Clone)]
 enum DelayState {
-	Uninitialized {
-		buffer_length: f64,
-	},
-	Initialized {
-		buffer: Vec<Frame>,
-		write_position: usize,
-	},
+	Uninitialized { buffer_length: f64 },
+	Initialized { delay_line: ModulatedDelayLine },
 }
 
 /// An effect that repeats audio after a certain delay. Useful
 /// for creating echo effects.
 pub struct Delay {
-	delay_time: CachedValue,
+	left_delay_time: CachedValue,
+	right_delay_time: CachedValue,
+	clock: Option<ClockId>,
+	/// The most recent interval of the clock the delay is synced to,
+	/// which is used if the clock is removed.
+	last_clock_interval: f64,
 	feedback: CachedValue,
+	ping_pong: bool,
+	ducking: CachedValue,
+	ducking_envelope: f64,
 	state: DelayState,
 	filter: Option<Filter>,
 }
@@ -100,72 +197,121 @@ impl Delay {
 	/// Creates a new delay effect.
 	pub fn new(settings: DelaySettings) -> Self {
 		Self {
-			delay_time: CachedValue::new(0.0.., settings.delay_time, 0.5),
-			feedback: CachedValue::new(-1.0..=1.0, settings.feedback, 0.5),
+			left_delay_time: CachedValue::new(0.0.., settings.left_delay_time, 0.5)
+				.with_smoothing(settings.smoothing),
+			right_delay_time: CachedValue::new(0.0.., settings.right_delay_time, 0.5)
+				.with_smoothing(settings.smoothing),
+			clock: settings.clock,
+			last_clock_interval: 1.0,
+			feedback: CachedValue::new(-1.0..=1.0, settings.feedback, 0.5)
+				.with_smoothing(settings.smoothing),
+			ping_pong: settings.ping_pong,
+			ducking: CachedValue::new(0.0..=1.0, settings.ducking, 0.0)
+				.with_smoothing(settings.smoothing),
+			ducking_envelope: 0.0,
 			state: DelayState::Uninitialized {
 				buffer_length: settings.buffer_length,
 			},
 			filter: settings.filter_settings.map(Filter::new),
 		}
 	}
+
+	/// Returns the length of one unit of delay time (in seconds).
+	fn delay_time_unit(&mut self, parameters: &Parameters) -> f64 {
+		match self.clock {
+			Some(clock) => {
+				if let Some(interval) = parameters.clock_interval(clock) {
+					self.last_clock_interval = interval;
+				}
+				self.last_clock_interval
+			}
+			None => 1.0,
+		}
+	}
+
+	/// Follows the loudness of the input and returns how much
+	/// the echoes should be turned down.
+	fn ducking_gain(&mut self, input: Frame, dt: f64) -> f64 {
+		let peak = input.left.abs().max(input.right.abs()) as f64;
+		let time = if peak > self.ducking_envelope {
+			DUCKING_ATTACK
+		} else {
+			DUCKING_RELEASE
+		};
+		let coefficient = (-dt / time).exp();
+		self.ducking_envelope = peak + (self.ducking_envelope - peak) * coefficient;
+		1.0 - self.ducking.get() * self.ducking_envelope.min(1.0)
+	}
 }
 
 impl Effect for Delay {
 	fn init(&mut self, sample_rate: u32) {
 		if let DelayState::Uninitialized { buffer_length } = &self.state {
 			self.state = DelayState::Initialized {
-				buffer: vec![Frame::ZERO; (buffer_length * sample_rate as f64) as usize],
-				write_position: 0,
+				delay_line: ModulatedDelayLine::new(*buffer_length, sample_rate),
 			}
 		} else {
 			panic!("The delay should be in the uninitialized state before init")
 		}
 	}
 
+	fn tail_length(&self) -> Option<f64> {
+		let delay_time_unit = match self.clock {
+			Some(_) => self.last_clock_interval,
+			None => 1.0,
+		};
+		let left_delay_time = self.left_delay_time.get() * delay_time_unit;
+		let right_delay_time = self.right_delay_time.get() * delay_time_unit;
+		// in ping-pong mode, echoes go through both channels
+		// before coming back around
+		let loop_time = if self.ping_pong {
+			left_delay_time + right_delay_time
+		} else {
+			left_delay_time.max(right_delay_time)
+		};
+		let filter_tail_length = if self.filter.is_some() {
+			FILTER_TAIL_LENGTH
+		} else {
+			0.0
+		};
+		Some(feedback_tail_length(loop_time, self.feedback.get())? + filter_tail_length)
+	}
+
 	fn process(&mut self, input: Frame, dt: f64, parameters: &Parameters) -> Frame {
-		if let DelayState::Initialized {
-			buffer,
-			write_position,
-		} = &mut self.state
-		{
-			// update cached values
-			self.delay_time.update(parameters);
-			self.feedback.update(parameters);
-
-			// get the read position (in samples)
-			let mut read_position = *write_position as f32 - (self.delay_time.get() / dt) as f32;
-			while read_position < 0.0 {
-				read_position += buffer.len() as f32;
-			}
+		// update cached values
+		self.left_delay_time.update(parameters);
+		self.right_delay_time.update(parameters);
+		self.feedback.update(parameters);
+		self.ducking.update(parameters);
+		let delay_time_unit = self.delay_time_unit(parameters);
+		let ducking_gain = self.ducking_gain(input, dt);
 
-			// read an interpolated sample
-			let current_sample_index = read_position as usize;
-			let previous_sample_index = if current_sample_index == 0 {
-				buffer.len() - 2
-			} else {
-				current_sample_index - 1
-			};
-			let next_sample_index = (current_sample_index + 1) % buffer.len();
-			let next_sample_index_2 = (current_sample_index + 2) % buffer.len();
-			let fraction = read_position % 1.0;
-			let output = util::interpolate_frame(
-				buffer[previous_sample_index],
-				buffer[current_sample_index],
-				buffer[next_sample_index],
-				buffer[next_sample_index_2],
-				fraction,
+		if let DelayState::Initialized { delay_line } = &mut self.state {
+			// read interpolated samples (the delay times are in frames)
+			let left_delay = self.left_delay_time.get() * delay_time_unit / dt;
+			let right_delay = self.right_delay_time.get() * delay_time_unit / dt;
+			let output = Frame::new(
+				delay_line.read(left_delay).left,
+				delay_line.read(right_delay).right,
 			);
 
 			// write input audio to the buffer
-			*write_position += 1;
-			*write_position %= buffer.len();
 			let filtered_output = match &mut self.filter {
 				Some(filter) => filter.process(output, dt, parameters),
 				None => output,
 			};
-			buffer[*write_position] = input + filtered_output * self.feedback.get() as f32;
+			let feedback = filtered_output * self.feedback.get() as f32;
+			delay_line.write(if self.ping_pong {
+				// send echoes from each channel to the other one
+				Frame::new(
+					(input.left + input.right) / 2.0 + feedback.right,
+					feedback.left,
+				)
+			} else {
+				input + feedback
+			});
 
-			filtered_output
+			filtered_output * ducking_gain as f32
 		} else {
 			panic!("The delay should be initialized by the first process call")
 		}
@@ -1,14 +1,40 @@
 //! Makes a sound harsher and noisier.
 
+mod oversampler;
+
+use std::f64::consts::TAU;
+
 use kira::{
 	manager::resources::Parameters,
 	track::Effect,
-	value::{cached::CachedValue, Value},
+	value::{cached::CachedValue, Smoothing, Value},
 	Frame,
 };
 
+use oversampler::Oversampler;
+
+use crate::{
+	compressor::decibels_to_amplitude,
+	filter::{Filter, FilterSettings},
+	tail::FILTER_TAIL_LENGTH,
+};
+
+/// How much the input is offset before [`DistortionKind::Tube`]
+/// saturation is applied, which makes the saturation asymmetric.
+const TUBE_BIAS: f32 = 0.25;
+/// The cutoff frequency (in hertz) of the filter that removes
+/// the DC offset added by [`DistortionKind::Tube`] saturation.
+const DC_BLOCKER_CUTOFF: f64 = 10.0;
+/// The lowest drive the distortion uses. The signal is divided
+/// by the drive after it's distorted, so it can't be 0.
+const MIN_DRIVE: f64 = 1.0e-6;
+
 /// Different types of distortion effect.
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(
+	feature = "serde_support",
+	derive(serde::Serialize, serde::Deserialize)
+)]
 pub enum DistortionKind {
 	/// The signal will be clamped to the -1.0 to 1.0 range.
 	///
@@ -22,6 +48,25 @@ pub enum DistortionKind {
 	/// This creates a smoother distortion that gradually
 	/// becomes more prominent as the signal becomes louder.
 	SoftClip,
+	/// The signal will be saturated more when it's positive
+	/// than when it's negative.
+	///
+	/// This creates a warm distortion similar to an
+	/// overdriven tube amplifier.
+	Tube,
+	/// Parts of the signal outside of the -1.0 to 1.0 range
+	/// will be folded back into the range.
+	///
+	/// This creates a metallic distortion with many
+	/// high overtones.
+	Foldback,
+	/// The signal will be rounded to a limited number of
+	/// levels, set by [`DistortionSettings::bit_depth`].
+	BitCrush,
+	/// The signal will be held at the same value for
+	/// multiple frames, as if it were played back at
+	/// [`DistortionSettings::reduced_sample_rate`].
+	SampleRateReduction,
 }
 
 impl Default for DistortionKind {
@@ -30,14 +75,78 @@ impl Default for DistortionKind {
 	}
 }
 
+/// How many times the sample rate is multiplied while
+/// applying distortion.
+///
+/// Distortion adds high frequencies to the signal, which
+/// can fold back into the audible range as unpleasant
+/// noise (aliasing). Oversampling reduces this at the cost
+/// of more processing.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde_support",
+	derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum Oversampling {
+	/// The distortion is applied at the normal sample rate.
+	None,
+	/// The distortion is applied at twice the sample rate.
+	X2,
+	/// The distortion is applied at four times the sample rate.
+	X4,
+}
+
+impl Oversampling {
+	fn factor(self) -> usize {
+		match self {
+			Oversampling::None => 1,
+			Oversampling::X2 => 2,
+			Oversampling::X4 => 4,
+		}
+	}
+}
+
+impl Default for Oversampling {
+	fn default() -> Self {
+		Self::None
+	}
+}
+
 /// Settings for a [`Distortion`] effect.
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(
+	feature = "serde_support",
+	derive(serde::Serialize, serde::Deserialize)
+)]
+#[cfg_attr(feature = "serde_support", serde(default))]
 pub struct DistortionSettings {
 	/// The kind of distortion to use.
 	pub kind: DistortionKind,
 	/// The factor to multiply the signal by before applying
 	/// the distortion.
 	pub drive: Value,
+	/// The number of bits to round the signal to when using
+	/// [`DistortionKind::BitCrush`].
+	pub bit_depth: Value,
+	/// The sample rate to hold the signal at when using
+	/// [`DistortionKind::SampleRateReduction`].
+	pub reduced_sample_rate: Value,
+	/// How many times the sample rate is multiplied while
+	/// applying distortion.
+	pub oversampling: Oversampling,
+	/// The settings of a filter to apply to the signal before
+	/// the distortion, if any.
+	pub pre_filter: Option<FilterSettings>,
+	/// The settings of a filter to apply to the signal after
+	/// the distortion, if any.
+	pub post_filter: Option<FilterSettings>,
+	/// The volume adjustment to apply to the output (in decibels).
+	pub output_gain: Value,
+	/// How the settings move to new values after they're changed.
+	///
+	/// If `None`, the default smoothing of the audio manager
+	/// will be used.
+	pub smoothing: Option<Smoothing>,
 }
 
 impl DistortionSettings {
@@ -59,6 +168,67 @@ impl DistortionSettings {
 			..self
 		}
 	}
+
+	/// Sets the number of bits to round the signal to when using
+	/// [`DistortionKind::BitCrush`].
+	pub fn bit_depth(self, bit_depth: impl Into<Value>) -> Self {
+		Self {
+			bit_depth: bit_depth.into(),
+			..self
+		}
+	}
+
+	/// Sets the sample rate to hold the signal at when using
+	/// [`DistortionKind::SampleRateReduction`].
+	pub fn reduced_sample_rate(self, reduced_sample_rate: impl Into<Value>) -> Self {
+		Self {
+			reduced_sample_rate: reduced_sample_rate.into(),
+			..self
+		}
+	}
+
+	/// Sets how many times the sample rate is multiplied while
+	/// applying distortion.
+	pub fn oversampling(self, oversampling: Oversampling) -> Self {
+		Self {
+			oversampling,
+			..self
+		}
+	}
+
+	/// Sets the settings of a filter to apply to the signal before
+	/// the distortion, if any.
+	pub fn pre_filter(self, pre_filter: impl Into<Option<FilterSettings>>) -> Self {
+		Self {
+			pre_filter: pre_filter.into(),
+			..self
+		}
+	}
+
+	/// Sets the settings of a filter to apply to the signal after
+	/// the distortion, if any.
+	pub fn post_filter(self, post_filter: impl Into<Option<FilterSettings>>) -> Self {
+		Self {
+			post_filter: post_filter.into(),
+			..self
+		}
+	}
+
+	/// Sets the volume adjustment to apply to the output (in decibels).
+	pub fn output_gain(self, output_gain: impl Into<Value>) -> Self {
+		Self {
+			output_gain: output_gain.into(),
+			..self
+		}
+	}
+
+	/// Sets how the settings move to new values after they're changed.
+	pub fn smoothing(self, smoothing: impl Into<Option<Smoothing>>) -> Self {
+		Self {
+			smoothing: smoothing.into(),
+			..self
+		}
+	}
 }
 
 impl Default for DistortionSettings {
@@ -66,43 +236,175 @@ impl Default for DistortionSettings {
 		Self {
 			kind: Default::default(),
 			drive: Value::Fixed(1.0),
+			bit_depth: Value::Fixed(8.0),
+			reduced_sample_rate: Value::Fixed(8000.0),
+			oversampling: Oversampling::default(),
+			pre_filter: None,
+			post_filter: None,
+			output_gain: Value::Fixed(0.0),
+			smoothing: None,
 		}
 	}
 }
 
+/// Applies the waveshaping of a [`Distortion`] effect.
+struct Shaper {
+	kind: DistortionKind,
+	/// The frame being held by sample rate reduction.
+	held_frame: Frame,
+	/// The progress towards the next frame to hold.
+	hold_phase: f64,
+	/// The previous input and output of the DC blocker.
+	dc_blocker_state: (Frame, Frame),
+}
+
+impl Shaper {
+	fn new(kind: DistortionKind) -> Self {
+		Self {
+			kind,
+			held_frame: Frame::ZERO,
+			hold_phase: 1.0,
+			dc_blocker_state: (Frame::ZERO, Frame::ZERO),
+		}
+	}
+
+	/// Applies the distortion to a frame of (possibly oversampled)
+	/// audio. `dt` is the time between oversampled frames.
+	fn process(
+		&mut self,
+		input: Frame,
+		dt: f64,
+		bit_depth: f64,
+		reduced_sample_rate: f64,
+	) -> Frame {
+		match self.kind {
+			DistortionKind::HardClip => Frame::new(
+				input.left.max(-1.0).min(1.0),
+				input.right.max(-1.0).min(1.0),
+			),
+			DistortionKind::SoftClip => Frame::new(
+				input.left / (1.0 + input.left.abs()),
+				input.right / (1.0 + input.right.abs()),
+			),
+			DistortionKind::Tube => {
+				let saturated = Frame::new(tube(input.left), tube(input.right));
+				self.block_dc(saturated, dt)
+			}
+			DistortionKind::Foldback => Frame::new(foldback(input.left), foldback(input.right)),
+			DistortionKind::BitCrush => {
+				let levels = 2.0f32.powf(bit_depth as f32 - 1.0);
+				Frame::new(
+					(input.left * levels).round() / levels,
+					(input.right * levels).round() / levels,
+				)
+			}
+			DistortionKind::SampleRateReduction => {
+				self.hold_phase += reduced_sample_rate * dt;
+				if self.hold_phase >= 1.0 {
+					self.hold_phase %= 1.0;
+					self.held_frame = input;
+				}
+				self.held_frame
+			}
+		}
+	}
+
+	fn block_dc(&mut self, input: Frame, dt: f64) -> Frame {
+		let coefficient = (-TAU * DC_BLOCKER_CUTOFF * dt).exp() as f32;
+		let (previous_input, previous_output) = self.dc_blocker_state;
+		let output = input - previous_input + previous_output * coefficient;
+		self.dc_blocker_state = (input, output);
+		output
+	}
+}
+
 /// An effect that modifies an input signal to make it more
 /// distorted and noisy.
 pub struct Distortion {
-	kind: DistortionKind,
 	drive: CachedValue,
+	bit_depth: CachedValue,
+	reduced_sample_rate: CachedValue,
+	output_gain: CachedValue,
+	shaper: Shaper,
+	oversampler: Option<Oversampler>,
+	pre_filter: Option<Filter>,
+	post_filter: Option<Filter>,
 }
 
 impl Distortion {
 	/// Creates a new distortion effect.
 	pub fn new(settings: DistortionSettings) -> Self {
 		Self {
-			kind: settings.kind,
-			drive: CachedValue::new(.., settings.drive, 1.0),
+			drive: CachedValue::new(MIN_DRIVE.., settings.drive, 1.0)
+				.with_smoothing(settings.smoothing),
+			bit_depth: CachedValue::new(1.0..=32.0, settings.bit_depth, 8.0)
+				.with_smoothing(settings.smoothing),
+			reduced_sample_rate: CachedValue::new(1.0.., settings.reduced_sample_rate, 8000.0)
+				.with_smoothing(settings.smoothing),
+			output_gain: CachedValue::new(.., settings.output_gain, 0.0)
+				.with_smoothing(settings.smoothing),
+			shaper: Shaper::new(settings.kind),
+			oversampler: match settings.oversampling.factor() {
+				1 => None,
+				factor => Some(Oversampler::new(factor)),
+			},
+			pre_filter: settings.pre_filter.map(Filter::new),
+			post_filter: settings.post_filter.map(Filter::new),
 		}
 	}
 }
 
+fn tube(x: f32) -> f32 {
+	(x + TUBE_BIAS).tanh() - TUBE_BIAS.tanh()
+}
+
+fn foldback(x: f32) -> f32 {
+	let phase = (x + 1.0).rem_euclid(4.0);
+	if phase < 2.0 {
+		phase - 1.0
+	} else {
+		3.0 - phase
+	}
+}
+
 impl Effect for Distortion {
-	fn process(&mut self, mut input: Frame, _dt: f64, parameters: &Parameters) -> Frame {
+	fn tail_length(&self) -> Option<f64> {
+		Some(FILTER_TAIL_LENGTH)
+	}
+
+	fn latency(&self) -> usize {
+		self.oversampler
+			.as_ref()
+			.map(|oversampler| oversampler.latency())
+			.unwrap_or(0)
+	}
+
+	fn process(&mut self, mut input: Frame, dt: f64, parameters: &Parameters) -> Frame {
 		self.drive.update(parameters);
+		self.bit_depth.update(parameters);
+		self.reduced_sample_rate.update(parameters);
+		self.output_gain.update(parameters);
+		if let Some(filter) = &mut self.pre_filter {
+			input = filter.process(input, dt, parameters);
+		}
 		let drive = self.drive.get() as f32;
 		input *= drive;
-		input = match self.kind {
-			DistortionKind::HardClip => Frame::new(
-				input.left.max(-1.0).min(1.0),
-				input.right.max(-1.0).min(1.0),
-			),
-			DistortionKind::SoftClip => Frame::new(
-				input.left / (1.0 + input.left.abs()),
-				input.right / (1.0 + input.right.abs()),
-			),
+		let bit_depth = self.bit_depth.get();
+		let reduced_sample_rate = self.reduced_sample_rate.get();
+		let shaper = &mut self.shaper;
+		input = match &mut self.oversampler {
+			Some(oversampler) => {
+				let oversampled_dt = dt / oversampler.factor() as f64;
+				oversampler.process(input, |frame| {
+					shaper.process(frame, oversampled_dt, bit_depth, reduced_sample_rate)
+				})
+			}
+			None => shaper.process(input, dt, bit_depth, reduced_sample_rate),
 		};
 		input /= drive;
-		input
+		if let Some(filter) = &mut self.post_filter {
+			input = filter.process(input, dt, parameters);
+		}
+		input * decibels_to_amplitude(self.output_gain.get()) as f32
 	}
 }
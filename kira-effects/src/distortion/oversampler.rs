@@ -0,0 +1,113 @@
+use std::f64::consts::PI;
+
+use kira::Frame;
+
+/// The number of filter taps per oversampled frame.
+const TAPS_PER_FACTOR: usize = 16;
+
+/// A windowed sinc low-pass filter.
+struct FirFilter {
+	history: Vec<Frame>,
+	position: usize,
+}
+
+impl FirFilter {
+	fn new(num_taps: usize) -> Self {
+		Self {
+			history: vec![Frame::ZERO; num_taps],
+			position: 0,
+		}
+	}
+
+	fn process(&mut self, input: Frame, coefficients: &[f32]) -> Frame {
+		self.position = (self.position + 1) % self.history.len();
+		self.history[self.position] = input;
+		let mut output = Frame::ZERO;
+		for (i, coefficient) in coefficients.iter().enumerate() {
+			let index = (self.position + self.history.len() - i) % self.history.len();
+			output += self.history[index] * *coefficient;
+		}
+		output
+	}
+}
+
+/// Runs a process at a multiple of the sample rate, filtering
+/// out frequencies above the original nyquist frequency
+/// before and after to prevent aliasing.
+pub(super) struct Oversampler {
+	factor: usize,
+	coefficients: Vec<f32>,
+	upsample_filter: FirFilter,
+	downsample_filter: FirFilter,
+}
+
+impl Oversampler {
+	pub fn new(factor: usize) -> Self {
+		let num_taps = TAPS_PER_FACTOR * factor + 1;
+		// leave some room below the original nyquist frequency
+		// for the filter to roll off
+		let cutoff = 0.45 / factor as f64;
+		let center = (num_taps / 2) as f64;
+		let mut coefficients: Vec<f32> = (0..num_taps)
+			.map(|i| {
+				let x = i as f64 - center;
+				let sinc = if x == 0.0 {
+					2.0 * cutoff
+				} else {
+					(2.0 * PI * cutoff * x).sin() / (PI * x)
+				};
+				let window = 0.42 - 0.5 * (2.0 * PI * i as f64 / (num_taps - 1) as f64).cos()
+					+ 0.08 * (4.0 * PI * i as f64 / (num_taps - 1) as f64).cos();
+				(sinc * window) as f32
+			})
+			.collect();
+		// normalize the filter so it has unity gain at 0 Hz
+		let sum: f32 = coefficients.iter().sum();
+		for coefficient in &mut coefficients {
+			*coefficient /= sum;
+		}
+		Self {
+			factor,
+			coefficients,
+			upsample_filter: FirFilter::new(num_taps),
+			downsample_filter: FirFilter::new(num_taps),
+		}
+	}
+
+	pub fn factor(&self) -> usize {
+		self.factor
+	}
+
+	/// Returns how many frames (at the original sample rate)
+	/// the filters delay the signal by.
+	pub fn latency(&self) -> usize {
+		// each filter delays the signal by half its length
+		// at the oversampled rate
+		(self.coefficients.len() - 1) / self.factor
+	}
+
+	/// Upsamples the input, runs `process` on each oversampled
+	/// frame, and downsamples the result.
+	pub fn process(&mut self, input: Frame, mut process: impl FnMut(Frame) -> Frame) -> Frame {
+		let mut output = Frame::ZERO;
+		for i in 0..self.factor {
+			// insert zeros between the input frames, making up for
+			// the lost volume by scaling up the input frames
+			let stuffed = if i == 0 {
+				input * self.factor as f32
+			} else {
+				Frame::ZERO
+			};
+			let upsampled = self.upsample_filter.process(stuffed, &self.coefficients);
+			let downsampled = self
+				.downsample_filter
+				.process(process(upsampled), &self.coefficients);
+			// keep the frame that lines up with the input frame
+			// so the latency is a whole number of frames
+			if i == 0 {
+				output = downsampled;
+			}
+		}
+		output
+	}
+}
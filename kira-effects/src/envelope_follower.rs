@@ -0,0 +1,149 @@
+//! Measures the loudness of a sound so other settings
+//! can react to it.
+//!
+//! The envelope follower publishes its output to an effect
+//! output, which can be created with
+//! [`AudioManager::add_effect_output`](kira::manager::AudioManager::add_effect_output)
+//! and linked to any setting that accepts a [`Value`].
+//! For example, linking a filter's cutoff to an envelope
+//! follower on a drum track will open the filter on
+//! every hit.
+
+use kira::{
+	manager::resources::Parameters,
+	modulator::ModulatorId,
+	track::Effect,
+	value::{cached::CachedValue, Smoothing, Value},
+	Frame,
+};
+
+use crate::tail::one_pole_tail_length;
+
+/// Settings for an [`EnvelopeFollower`].
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(
+	feature = "serde_support",
+	derive(serde::Serialize, serde::Deserialize)
+)]
+#[cfg_attr(feature = "serde_support", serde(default))]
+pub struct EnvelopeFollowerSettings {
+	/// How quickly the output rises when the signal gets
+	/// louder (in seconds).
+	pub attack: Value,
+	/// How quickly the output falls when the signal gets
+	/// quieter (in seconds).
+	pub release: Value,
+	/// The factor to multiply the output by.
+	pub gain: Value,
+	/// How the settings move to new values after they're changed.
+	///
+	/// If `None`, the default smoothing of the audio manager
+	/// will be used.
+	pub smoothing: Option<Smoothing>,
+}
+
+impl EnvelopeFollowerSettings {
+	/// Creates a new `EnvelopeFollowerSettings` with the default settings.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Sets how quickly the output rises when the signal
+	/// gets louder (in seconds).
+	pub fn attack(self, attack: impl Into<Value>) -> Self {
+		Self {
+			attack: attack.into(),
+			..self
+		}
+	}
+
+	/// Sets how quickly the output falls when the signal
+	/// gets quieter (in seconds).
+	pub fn release(self, release: impl Into<Value>) -> Self {
+		Self {
+			release: release.into(),
+			..self
+		}
+	}
+
+	/// Sets the factor to multiply the output by.
+	pub fn gain(self, gain: impl Into<Value>) -> Self {
+		Self {
+			gain: gain.into(),
+			..self
+		}
+	}
+
+	/// Sets how the settings move to new values after they're changed.
+	pub fn smoothing(self, smoothing: impl Into<Option<Smoothing>>) -> Self {
+		Self {
+			smoothing: smoothing.into(),
+			..self
+		}
+	}
+}
+
+impl Default for EnvelopeFollowerSettings {
+	fn default() -> Self {
+		Self {
+			attack: Value::Fixed(0.01),
+			release: Value::Fixed(0.1),
+			gain: Value::Fixed(1.0),
+			smoothing: None,
+		}
+	}
+}
+
+/// An effect that follows the amplitude of the input audio
+/// and publishes it to an effect output.
+///
+/// The input audio passes through unchanged.
+pub struct EnvelopeFollower {
+	output: ModulatorId,
+	attack: CachedValue,
+	release: CachedValue,
+	gain: CachedValue,
+	envelope: f64,
+}
+
+impl EnvelopeFollower {
+	/// Creates a new envelope follower that publishes
+	/// to the given effect output.
+	pub fn new(output: impl Into<ModulatorId>, settings: EnvelopeFollowerSettings) -> Self {
+		Self {
+			output: output.into(),
+			attack: CachedValue::new(0.0.., settings.attack, 0.01)
+				.with_smoothing(settings.smoothing),
+			release: CachedValue::new(0.0.., settings.release, 0.1)
+				.with_smoothing(settings.smoothing),
+			gain: CachedValue::new(.., settings.gain, 1.0).with_smoothing(settings.smoothing),
+			envelope: 0.0,
+		}
+	}
+}
+
+impl Effect for EnvelopeFollower {
+	fn tail_length(&self) -> Option<f64> {
+		// keep publishing until the output has fallen to silence
+		Some(one_pole_tail_length(self.release.get()))
+	}
+
+	fn process(&mut self, input: Frame, dt: f64, parameters: &Parameters) -> Frame {
+		self.attack.update(parameters);
+		self.release.update(parameters);
+		self.gain.update(parameters);
+		let level = input.left.abs().max(input.right.abs()) as f64;
+		let response_time = if level > self.envelope {
+			self.attack.get()
+		} else {
+			self.release.get()
+		};
+		self.envelope = if response_time > 0.0 {
+			level + (self.envelope - level) * (-dt / response_time).exp()
+		} else {
+			level
+		};
+		parameters.publish(self.output, self.envelope * self.gain.get());
+		input
+	}
+}
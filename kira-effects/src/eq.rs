@@ -0,0 +1,203 @@
+//! Boosts or cuts ranges of frequencies.
+
+use std::f64::consts::PI;
+
+use kira::{
+	manager::resources::Parameters,
+	track::Effect,
+	value::{cached::CachedValue, Smoothing, Value},
+	Frame,
+};
+
+use crate::{
+	svf::{gain_to_a, Svf, SvfCoefficients},
+	tail::FILTER_TAIL_LENGTH,
+};
+
+/// How an [`EqBand`] affects frequencies.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde_support",
+	derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum EqBandKind {
+	/// Boosts or cuts frequencies around the band's frequency.
+	Peaking,
+	/// Boosts or cuts frequencies below the band's frequency.
+	LowShelf,
+	/// Boosts or cuts frequencies above the band's frequency.
+	HighShelf,
+	/// Removes frequencies below the band's frequency.
+	///
+	/// The gain of the band is ignored.
+	LowCut,
+	/// Removes frequencies above the band's frequency.
+	///
+	/// The gain of the band is ignored.
+	HighCut,
+}
+
+/// Settings for a single band of a [`ParametricEq`].
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(
+	feature = "serde_support",
+	derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct EqBand {
+	/// How the band affects frequencies.
+	pub kind: EqBandKind,
+	/// The center or corner frequency of the band (in hertz).
+	pub frequency: Value,
+	/// How much to boost or cut the frequencies (in decibels).
+	pub gain: Value,
+	/// How narrow the range of affected frequencies is.
+	pub q: Value,
+}
+
+/// Settings for a [`ParametricEq`].
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(
+	feature = "serde_support",
+	derive(serde::Serialize, serde::Deserialize)
+)]
+#[cfg_attr(feature = "serde_support", serde(default))]
+pub struct ParametricEqSettings {
+	/// The bands of the EQ, which are applied in order.
+	pub bands: Vec<EqBand>,
+	/// How the settings move to new values after they're changed.
+	///
+	/// If `None`, the default smoothing of the audio manager
+	/// will be used.
+	pub smoothing: Option<Smoothing>,
+}
+
+impl ParametricEqSettings {
+	/// Creates a new `ParametricEqSettings` with no bands.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Adds a band to the EQ.
+	pub fn with_band(
+		mut self,
+		kind: EqBandKind,
+		frequency: impl Into<Value>,
+		gain: impl Into<Value>,
+		q: impl Into<Value>,
+	) -> Self {
+		self.bands.push(EqBand {
+			kind,
+			frequency: frequency.into(),
+			gain: gain.into(),
+			q: q.into(),
+		});
+		self
+	}
+
+	/// Sets how the settings move to new values after they're changed.
+	pub fn smoothing(self, smoothing: impl Into<Option<Smoothing>>) -> Self {
+		Self {
+			smoothing: smoothing.into(),
+			..self
+		}
+	}
+}
+
+fn coefficients(
+	kind: EqBandKind,
+	frequency: f64,
+	gain: f64,
+	q: f64,
+	sample_rate: f64,
+) -> SvfCoefficients {
+	let g = (PI * (frequency / sample_rate)).tan();
+	let k = 1.0 / q;
+	let a = gain_to_a(gain);
+	match kind {
+		EqBandKind::Peaking => SvfCoefficients::peak(g, k, a),
+		EqBandKind::LowShelf => SvfCoefficients::low_shelf(g, k, a),
+		EqBandKind::HighShelf => SvfCoefficients::high_shelf(g, k, a),
+		EqBandKind::LowCut => SvfCoefficients::high_pass(g, k),
+		EqBandKind::HighCut => SvfCoefficients::low_pass(g, k),
+	}
+}
+
+struct Band {
+	kind: EqBandKind,
+	frequency: CachedValue,
+	gain: CachedValue,
+	q: CachedValue,
+	svf: Svf,
+	/// The frequency, gain, Q, and sample rate the coefficients
+	/// were last calculated with.
+	coefficient_inputs: [f64; 4],
+	coefficients: SvfCoefficients,
+}
+
+impl Band {
+	fn new(band: EqBand, smoothing: Option<Smoothing>) -> Self {
+		Self {
+			kind: band.kind,
+			frequency: CachedValue::new(20.0..=20000.0, band.frequency, 1000.0)
+				.with_smoothing(smoothing),
+			gain: CachedValue::new(.., band.gain, 0.0).with_smoothing(smoothing),
+			q: CachedValue::new(0.01.., band.q, 0.707).with_smoothing(smoothing),
+			svf: Svf::new(),
+			coefficient_inputs: [f64::NAN; 4],
+			coefficients: coefficients(band.kind, 1000.0, 0.0, 0.707, 44100.0),
+		}
+	}
+
+	fn process(&mut self, input: Frame, dt: f64, parameters: &Parameters) -> Frame {
+		self.frequency.update(parameters);
+		self.gain.update(parameters);
+		self.q.update(parameters);
+		// recalculating the coefficients is the most expensive part
+		// of processing a band, so only do it when the settings change
+		let coefficient_inputs = [
+			self.frequency.get(),
+			self.gain.get(),
+			self.q.get(),
+			1.0 / dt,
+		];
+		if coefficient_inputs != self.coefficient_inputs {
+			let [frequency, gain, q, sample_rate] = coefficient_inputs;
+			self.coefficients = coefficients(self.kind, frequency, gain, q, sample_rate);
+			self.coefficient_inputs = coefficient_inputs;
+		}
+		self.svf.process(input, self.coefficients)
+	}
+}
+
+/// An effect that boosts or cuts ranges of frequencies using
+/// any number of bands.
+pub struct ParametricEq {
+	bands: Vec<Band>,
+}
+
+impl ParametricEq {
+	/// Creates a new parametric EQ.
+	pub fn new(settings: ParametricEqSettings) -> Self {
+		let smoothing = settings.smoothing;
+		Self {
+			bands: settings
+				.bands
+				.into_iter()
+				.map(|band| Band::new(band, smoothing))
+				.collect(),
+		}
+	}
+}
+
+impl Effect for ParametricEq {
+	fn tail_length(&self) -> Option<f64> {
+		Some(FILTER_TAIL_LENGTH)
+	}
+
+	fn process(&mut self, mut input: Frame, dt: f64, parameters: &Parameters) -> Frame {
+		for band in &mut self.bands {
+			input = band.process(input, dt, parameters);
+		}
+		input
+	}
+}
@@ -5,15 +5,21 @@ use std::f64::consts::PI;
 use kira::{
 	manager::resources::Parameters,
 	track::Effect,
-	value::{cached::CachedValue, Value},
+	value::{cached::CachedValue, Smoothing, Value},
 	Frame,
 };
 
-// This filter code is based on the filter code from baseplug:
-// https://github.com/wrl/baseplug/blob/trunk/examples/svf/svf_simper.rs
+use crate::{
+	svf::{gain_to_a, Svf, SvfCoefficients},
+	tail::FILTER_TAIL_LENGTH,
+};
 
-/// The frequencies that the filter will remove.
+/// How the filter affects frequencies.
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(
+	feature = "serde_support",
+	derive(serde::Serialize, serde::Deserialize)
+)]
 pub enum FilterMode {
 	/// Removes frequencies above the cutoff frequency.
 	LowPass,
@@ -23,12 +29,80 @@ pub enum FilterMode {
 	HighPass,
 	/// Removes frequencies around the cutoff frequency.
 	Notch,
+	/// Boosts or cuts frequencies below the cutoff frequency
+	/// by the filter's gain.
+	LowShelf,
+	/// Boosts or cuts frequencies above the cutoff frequency
+	/// by the filter's gain.
+	HighShelf,
+	/// Boosts or cuts frequencies around the cutoff frequency
+	/// by the filter's gain.
+	Peak,
+	/// Keeps all frequencies, but shifts the phase of
+	/// frequencies around the cutoff frequency.
+	AllPass,
+}
+
+/// How steeply the filter removes frequencies.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde_support",
+	derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum FilterSlope {
+	/// 12 dB per octave.
+	Db12,
+	/// 24 dB per octave.
+	Db24,
+	/// 48 dB per octave.
+	Db48,
+}
+
+impl FilterSlope {
+	fn num_stages(self) -> usize {
+		match self {
+			FilterSlope::Db12 => 1,
+			FilterSlope::Db24 => 2,
+			FilterSlope::Db48 => 4,
+		}
+	}
+}
+
+/// Returns the damping factor (the inverse of the Q) of each stage
+/// of a filter with the given slope when it has no resonance.
+///
+/// A single stage has a damping of 2. Cascaded stages use the
+/// damping factors of a Butterworth filter, so the combined filter
+/// is 3 dB down at the cutoff frequency. The stages are ordered
+/// from the lowest Q to the highest, so the resonance can be
+/// applied to the last one.
+fn stage_damping(slope: FilterSlope) -> [f64; 4] {
+	let num_stages = slope.num_stages();
+	let mut damping = [2.0; 4];
+	if num_stages > 1 {
+		for (stage, damping) in damping[..num_stages].iter_mut().enumerate() {
+			let pole_pair = num_stages - stage;
+			*damping = 2.0 * (PI * (2 * pole_pair - 1) as f64 / (4 * num_stages) as f64).sin();
+		}
+	}
+	damping
+}
+
+impl Default for FilterSlope {
+	fn default() -> Self {
+		Self::Db12
+	}
 }
 
 /// Settings for a [`Filter`].
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(
+	feature = "serde_support",
+	derive(serde::Serialize, serde::Deserialize)
+)]
+#[cfg_attr(feature = "serde_support", serde(default))]
 pub struct FilterSettings {
-	/// The frequencies that the filter will remove.
+	/// How the filter affects frequencies.
 	pub mode: FilterMode,
 	/// The cutoff frequency of the filter (in hertz).
 	pub cutoff: Value,
@@ -37,6 +111,18 @@ pub struct FilterSettings {
 	/// The resonance is a feedback effect that produces
 	/// a distinctive "ringing" sound.
 	pub resonance: Value,
+	/// How steeply the filter removes frequencies.
+	pub slope: FilterSlope,
+	/// How much to boost or cut frequencies (in decibels) when
+	/// using the [`LowShelf`](FilterMode::LowShelf),
+	/// [`HighShelf`](FilterMode::HighShelf) or [`Peak`](FilterMode::Peak)
+	/// modes.
+	pub gain: Value,
+	/// How the settings move to new values after they're changed.
+	///
+	/// If `None`, the default smoothing of the audio manager
+	/// will be used.
+	pub smoothing: Option<Smoothing>,
 }
 
 impl FilterSettings {
@@ -45,7 +131,7 @@ impl FilterSettings {
 		Self::default()
 	}
 
-	/// Sets the frequencies that the filter will remove.
+	/// Sets how the filter affects frequencies.
 	pub fn mode(self, mode: FilterMode) -> Self {
 		Self { mode, ..self }
 	}
@@ -65,6 +151,28 @@ impl FilterSettings {
 			..self
 		}
 	}
+
+	/// Sets how steeply the filter removes frequencies.
+	pub fn slope(self, slope: FilterSlope) -> Self {
+		Self { slope, ..self }
+	}
+
+	/// Sets how much to boost or cut frequencies (in decibels)
+	/// when using a shelf or peak mode.
+	pub fn gain(self, gain: impl Into<Value>) -> Self {
+		Self {
+			gain: gain.into(),
+			..self
+		}
+	}
+
+	/// Sets how the settings move to new values after they're changed.
+	pub fn smoothing(self, smoothing: impl Into<Option<Smoothing>>) -> Self {
+		Self {
+			smoothing: smoothing.into(),
+			..self
+		}
+	}
 }
 
 impl Default for FilterSettings {
@@ -73,6 +181,9 @@ impl Default for FilterSettings {
 			mode: FilterMode::LowPass,
 			cutoff: 10000.0.into(),
 			resonance: 0.0.into(),
+			slope: FilterSlope::default(),
+			gain: 0.0.into(),
+			smoothing: None,
 		}
 	}
 }
@@ -82,8 +193,10 @@ pub struct Filter {
 	mode: FilterMode,
 	cutoff: CachedValue,
 	resonance: CachedValue,
-	ic1eq: Frame,
-	ic2eq: Frame,
+	gain: CachedValue,
+	slope: FilterSlope,
+	stage_damping: [f64; 4],
+	stages: [Svf; 4],
 }
 
 impl Filter {
@@ -91,34 +204,52 @@ impl Filter {
 	pub fn new(settings: FilterSettings) -> Self {
 		Self {
 			mode: settings.mode,
-			cutoff: CachedValue::new(20.0..=20000.0, settings.cutoff, 10000.0),
-			resonance: CachedValue::new(0.0..=1.0, settings.resonance, 0.0),
-			ic1eq: Frame::ZERO,
-			ic2eq: Frame::ZERO,
+			cutoff: CachedValue::new(20.0..=20000.0, settings.cutoff, 10000.0)
+				.with_smoothing(settings.smoothing),
+			resonance: CachedValue::new(0.0..=1.0, settings.resonance, 0.0)
+				.with_smoothing(settings.smoothing),
+			gain: CachedValue::new(.., settings.gain, 0.0).with_smoothing(settings.smoothing),
+			slope: settings.slope,
+			stage_damping: stage_damping(settings.slope),
+			stages: [Svf::new(), Svf::new(), Svf::new(), Svf::new()],
 		}
 	}
 }
 
 impl Effect for Filter {
-	fn process(&mut self, input: Frame, dt: f64, parameters: &Parameters) -> Frame {
+	fn tail_length(&self) -> Option<f64> {
+		Some(FILTER_TAIL_LENGTH)
+	}
+
+	fn process(&mut self, mut input: Frame, dt: f64, parameters: &Parameters) -> Frame {
 		self.cutoff.update(parameters);
 		self.resonance.update(parameters);
+		self.gain.update(parameters);
 		let sample_rate = 1.0 / dt;
 		let g = (PI * (self.cutoff.get() / sample_rate)).tan();
-		let k = 2.0 - (1.9 * self.resonance.get().min(1.0).max(0.0));
-		let a1 = 1.0 / (1.0 + (g * (g + k)));
-		let a2 = g * a1;
-		let a3 = g * a2;
-		let v3 = input - self.ic2eq;
-		let v1 = (self.ic1eq * (a1 as f32)) + (v3 * (a2 as f32));
-		let v2 = self.ic2eq + (self.ic1eq * (a2 as f32)) + (v3 * (a3 as f32));
-		self.ic1eq = (v1 * 2.0) - self.ic1eq;
-		self.ic2eq = (v2 * 2.0) - self.ic2eq;
-		match self.mode {
-			FilterMode::LowPass => v2,
-			FilterMode::BandPass => v1,
-			FilterMode::HighPass => input - v1 * (k as f32) - v2,
-			FilterMode::Notch => input - v1 * (k as f32),
+		let num_stages = self.slope.num_stages();
+		// split the gain between the stages so the total boost
+		// or cut doesn't depend on the slope
+		let a = gain_to_a(self.gain.get() / num_stages as f64);
+		for (i, stage) in self.stages[..num_stages].iter_mut().enumerate() {
+			let mut k = self.stage_damping[i];
+			// only the last stage resonates so the resonance
+			// doesn't pile up across stages
+			if i == num_stages - 1 {
+				k *= 1.0 - 0.95 * self.resonance.get();
+			}
+			let coefficients = match self.mode {
+				FilterMode::LowPass => SvfCoefficients::low_pass(g, k),
+				FilterMode::BandPass => SvfCoefficients::band_pass(g, k),
+				FilterMode::HighPass => SvfCoefficients::high_pass(g, k),
+				FilterMode::Notch => SvfCoefficients::notch(g, k),
+				FilterMode::LowShelf => SvfCoefficients::low_shelf(g, k, a),
+				FilterMode::HighShelf => SvfCoefficients::high_shelf(g, k, a),
+				FilterMode::Peak => SvfCoefficients::peak(g, k, a),
+				FilterMode::AllPass => SvfCoefficients::all_pass(g, k),
+			};
+			input = stage.process(input, coefficients);
 		}
+		input
 	}
 }
@@ -0,0 +1,179 @@
+//! Creates a sweeping, "jet plane" sound by mixing a sound
+//! with a very short, oscillating delay of itself.
+
+use kira::{
+	clock::ClockId,
+	manager::resources::Parameters,
+	track::Effect,
+	value::{cached::CachedValue, Smoothing, Value},
+	Frame,
+};
+
+use crate::{
+	modulation::{ModulatedDelayLine, ModulationLfo},
+	tail::feedback_tail_length,
+};
+
+/// The shortest delay time (in seconds) of the flanger.
+const MIN_DELAY_TIME: f64 = 0.001;
+/// How far (in seconds) the delay time moves from the minimum
+/// delay time at full depth.
+const MAX_DELAY_TIME_SWING: f64 = 0.004;
+
+/// Settings for a [`Flanger`].
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(
+	feature = "serde_support",
+	derive(serde::Serialize, serde::Deserialize)
+)]
+#[cfg_attr(feature = "serde_support", serde(default))]
+pub struct FlangerSettings {
+	/// How many times the delay time oscillates per second,
+	/// or per tick if the flanger is synced to a clock.
+	pub rate: Value,
+	/// How much the delay time oscillates, from 0.0 to 1.0.
+	pub depth: Value,
+	/// How much of the output is fed back into the flanger.
+	pub feedback: Value,
+	/// The balance between dry (unaffected) signal and wet
+	/// (affected) signal to output. 0.0 is fully dry,
+	/// 1.0 is fully wet.
+	pub mix: Value,
+	/// The clock the oscillation should be synced to, if any.
+	#[cfg_attr(feature = "serde_support", serde(skip))]
+	pub clock: Option<ClockId>,
+	/// How the settings move to new values after they're changed.
+	///
+	/// If `None`, the default smoothing of the audio manager
+	/// will be used.
+	pub smoothing: Option<Smoothing>,
+}
+
+impl FlangerSettings {
+	/// Creates a new `FlangerSettings` with the default settings.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Sets how many times the delay time oscillates per second,
+	/// or per tick if the flanger is synced to a clock.
+	pub fn rate(self, rate: impl Into<Value>) -> Self {
+		Self {
+			rate: rate.into(),
+			..self
+		}
+	}
+
+	/// Sets how much the delay time oscillates, from 0.0 to 1.0.
+	pub fn depth(self, depth: impl Into<Value>) -> Self {
+		Self {
+			depth: depth.into(),
+			..self
+		}
+	}
+
+	/// Sets how much of the output is fed back into the flanger.
+	pub fn feedback(self, feedback: impl Into<Value>) -> Self {
+		Self {
+			feedback: feedback.into(),
+			..self
+		}
+	}
+
+	/// Sets the balance between dry (unaffected) signal and wet
+	/// (affected) signal to output. 0.0 is fully dry, 1.0 is fully wet.
+	pub fn mix(self, mix: impl Into<Value>) -> Self {
+		Self {
+			mix: mix.into(),
+			..self
+		}
+	}
+
+	/// Sets the clock the oscillation should be synced to, if any.
+	pub fn clock(self, clock: impl Into<Option<ClockId>>) -> Self {
+		Self {
+			clock: clock.into(),
+			..self
+		}
+	}
+
+	/// Sets how the settings move to new values after they're changed.
+	pub fn smoothing(self, smoothing: impl Into<Option<Smoothing>>) -> Self {
+		Self {
+			smoothing: smoothing.into(),
+			..self
+		}
+	}
+}
+
+impl Default for FlangerSettings {
+	fn default() -> Self {
+		Self {
+			rate: Value::Fixed(0.25),
+			depth: Value::Fixed(0.5),
+			feedback: Value::Fixed(0.5),
+			mix: Value::Fixed(0.5),
+			clock: None,
+			smoothing: None,
+		}
+	}
+}
+
+/// An effect that mixes input audio with a copy of itself
+/// whose delay time oscillates between 1 and 5 milliseconds.
+pub struct Flanger {
+	rate: CachedValue,
+	depth: CachedValue,
+	feedback: CachedValue,
+	mix: CachedValue,
+	lfo: ModulationLfo,
+	delay_line: Option<ModulatedDelayLine>,
+}
+
+impl Flanger {
+	/// Creates a new flanger effect.
+	pub fn new(settings: FlangerSettings) -> Self {
+		Self {
+			rate: CachedValue::new(0.0.., settings.rate, 0.25).with_smoothing(settings.smoothing),
+			depth: CachedValue::new(0.0..=1.0, settings.depth, 0.5)
+				.with_smoothing(settings.smoothing),
+			feedback: CachedValue::new(-1.0..=1.0, settings.feedback, 0.5)
+				.with_smoothing(settings.smoothing),
+			mix: CachedValue::new(0.0..=1.0, settings.mix, 0.5).with_smoothing(settings.smoothing),
+			lfo: ModulationLfo::new(settings.clock),
+			delay_line: None,
+		}
+	}
+}
+
+impl Effect for Flanger {
+	fn init(&mut self, sample_rate: u32) {
+		self.delay_line = Some(ModulatedDelayLine::new(
+			MIN_DELAY_TIME + MAX_DELAY_TIME_SWING,
+			sample_rate,
+		));
+	}
+
+	fn tail_length(&self) -> Option<f64> {
+		feedback_tail_length(MIN_DELAY_TIME + MAX_DELAY_TIME_SWING, self.feedback.get())
+	}
+
+	fn process(&mut self, input: Frame, dt: f64, parameters: &Parameters) -> Frame {
+		self.rate.update(parameters);
+		self.depth.update(parameters);
+		self.feedback.update(parameters);
+		self.mix.update(parameters);
+		self.lfo.update(self.rate.get(), dt, parameters);
+		let delay_line = self
+			.delay_line
+			.as_mut()
+			.expect("The flanger should be initialized before the first process call");
+		// map the oscillator output from -1..1 to 0..1
+		let sweep = (self.lfo.value(0.0) + 1.0) / 2.0;
+		let delay = (MIN_DELAY_TIME + MAX_DELAY_TIME_SWING * self.depth.get() * sweep) / dt;
+		let output = delay_line.read(delay);
+		delay_line.write(input + output * self.feedback.get() as f32);
+		let mix = self.mix.get() as f32;
+		output * mix + input * (1.0 - mix)
+	}
+}
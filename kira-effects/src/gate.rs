@@ -0,0 +1,212 @@
+//! Turns down quiet parts of a sound.
+//!
+//! A gate silences background noise between louder sounds,
+//! like breathing between lines of dialog. With a low ratio,
+//! it acts as an expander, which gently turns quiet sounds
+//! down further instead of silencing them.
+
+use kira::{
+	manager::resources::Parameters,
+	track::Effect,
+	value::{cached::CachedValue, Smoothing, Value},
+	Frame,
+};
+
+use crate::{
+	compressor::{amplitude_to_decibels, decibels_to_amplitude},
+	tail::one_pole_tail_length,
+};
+
+/// Settings for a [`Gate`].
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(
+	feature = "serde_support",
+	derive(serde::Serialize, serde::Deserialize)
+)]
+#[cfg_attr(feature = "serde_support", serde(default))]
+pub struct GateSettings {
+	/// The volume (in decibels) below which the signal
+	/// will be turned down.
+	pub threshold: Value,
+	/// How much the signal below the threshold will be
+	/// turned down. A ratio of 2.0 means that for every decibel
+	/// the signal goes below the threshold, the output will
+	/// go 2 decibels below the threshold.
+	///
+	/// High ratios make the effect act as a gate, and low
+	/// ratios make it act as an expander.
+	pub ratio: Value,
+	/// The most the signal can be turned down (in decibels).
+	pub range: Value,
+	/// How quickly the gate opens when the signal rises above
+	/// the threshold (in seconds).
+	pub attack: Value,
+	/// How long the gate stays open after the signal falls
+	/// below the threshold (in seconds).
+	pub hold: Value,
+	/// How quickly the gate closes after the hold time
+	/// is over (in seconds).
+	pub release: Value,
+	/// How the settings move to new values after they're changed.
+	///
+	/// If `None`, the default smoothing of the audio manager
+	/// will be used.
+	pub smoothing: Option<Smoothing>,
+}
+
+impl GateSettings {
+	/// Creates a new `GateSettings` with the default settings.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Sets the volume (in decibels) below which the signal
+	/// will be turned down.
+	pub fn threshold(self, threshold: impl Into<Value>) -> Self {
+		Self {
+			threshold: threshold.into(),
+			..self
+		}
+	}
+
+	/// Sets how much the signal below the threshold will be
+	/// turned down.
+	pub fn ratio(self, ratio: impl Into<Value>) -> Self {
+		Self {
+			ratio: ratio.into(),
+			..self
+		}
+	}
+
+	/// Sets the most the signal can be turned down (in decibels).
+	pub fn range(self, range: impl Into<Value>) -> Self {
+		Self {
+			range: range.into(),
+			..self
+		}
+	}
+
+	/// Sets how quickly the gate opens when the signal rises
+	/// above the threshold (in seconds).
+	pub fn attack(self, attack: impl Into<Value>) -> Self {
+		Self {
+			attack: attack.into(),
+			..self
+		}
+	}
+
+	/// Sets how long the gate stays open after the signal falls
+	/// below the threshold (in seconds).
+	pub fn hold(self, hold: impl Into<Value>) -> Self {
+		Self {
+			hold: hold.into(),
+			..self
+		}
+	}
+
+	/// Sets how quickly the gate closes after the hold time
+	/// is over (in seconds).
+	pub fn release(self, release: impl Into<Value>) -> Self {
+		Self {
+			release: release.into(),
+			..self
+		}
+	}
+
+	/// Sets how the settings move to new values after they're changed.
+	pub fn smoothing(self, smoothing: impl Into<Option<Smoothing>>) -> Self {
+		Self {
+			smoothing: smoothing.into(),
+			..self
+		}
+	}
+}
+
+impl Default for GateSettings {
+	fn default() -> Self {
+		Self {
+			threshold: Value::Fixed(-40.0),
+			ratio: Value::Fixed(100.0),
+			range: Value::Fixed(-80.0),
+			attack: Value::Fixed(0.001),
+			hold: Value::Fixed(0.05),
+			release: Value::Fixed(0.1),
+			smoothing: None,
+		}
+	}
+}
+
+/// An effect that turns down parts of the input audio
+/// that are below a threshold.
+pub struct Gate {
+	threshold: CachedValue,
+	ratio: CachedValue,
+	range: CachedValue,
+	attack: CachedValue,
+	hold: CachedValue,
+	release: CachedValue,
+	/// How long (in seconds) since the signal was last
+	/// above the threshold.
+	time_below_threshold: f64,
+	gain_reduction: f64,
+}
+
+impl Gate {
+	/// Creates a new gate.
+	pub fn new(settings: GateSettings) -> Self {
+		Self {
+			threshold: CachedValue::new(.., settings.threshold, -40.0)
+				.with_smoothing(settings.smoothing),
+			ratio: CachedValue::new(1.0.., settings.ratio, 100.0)
+				.with_smoothing(settings.smoothing),
+			range: CachedValue::new(..=0.0, settings.range, -80.0)
+				.with_smoothing(settings.smoothing),
+			attack: CachedValue::new(0.0.., settings.attack, 0.001)
+				.with_smoothing(settings.smoothing),
+			hold: CachedValue::new(0.0.., settings.hold, 0.05).with_smoothing(settings.smoothing),
+			release: CachedValue::new(0.0.., settings.release, 0.1)
+				.with_smoothing(settings.smoothing),
+			time_below_threshold: f64::INFINITY,
+			gain_reduction: 0.0,
+		}
+	}
+}
+
+impl Effect for Gate {
+	fn tail_length(&self) -> Option<f64> {
+		Some(self.hold.get() + one_pole_tail_length(self.release.get()))
+	}
+
+	fn process(&mut self, input: Frame, dt: f64, parameters: &Parameters) -> Frame {
+		self.threshold.update(parameters);
+		self.ratio.update(parameters);
+		self.range.update(parameters);
+		self.attack.update(parameters);
+		self.hold.update(parameters);
+		self.release.update(parameters);
+		let level = amplitude_to_decibels(input.left.abs().max(input.right.abs()) as f64);
+		let undershoot = level - self.threshold.get();
+		let target = if undershoot >= 0.0 {
+			self.time_below_threshold = 0.0;
+			0.0
+		} else {
+			self.time_below_threshold += dt;
+			if self.time_below_threshold <= self.hold.get() {
+				0.0
+			} else {
+				(undershoot * (self.ratio.get() - 1.0)).max(self.range.get())
+			}
+		};
+		let response_time = if target > self.gain_reduction {
+			self.attack.get()
+		} else {
+			self.release.get()
+		};
+		self.gain_reduction = if response_time > 0.0 {
+			target + (self.gain_reduction - target) * (-dt / response_time).exp()
+		} else {
+			target
+		};
+		input * decibels_to_amplitude(self.gain_reduction) as f32
+	}
+}
@@ -1,6 +1,20 @@
 #![allow(clippy::large_enum_variant)]
 
+pub mod chorus;
+pub mod compressor;
+pub mod convolution_reverb;
 pub mod delay;
 pub mod distortion;
+pub mod envelope_follower;
+pub mod eq;
 pub mod filter;
+pub mod flanger;
+pub mod gate;
+pub mod limiter;
+mod modulation;
+pub mod phaser;
+pub mod preset;
 pub mod reverb;
+pub mod stereo_imager;
+mod svf;
+mod tail;
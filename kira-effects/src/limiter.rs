@@ -0,0 +1,150 @@
+//! Keeps a sound from getting louder than a certain volume.
+
+use kira::{
+	manager::resources::Parameters,
+	track::{Effect, TrackId},
+	value::{cached::CachedValue, Smoothing, Value},
+	Frame,
+};
+
+use crate::{compressor::decibels_to_amplitude, tail::one_pole_tail_length};
+
+/// Settings for a [`Limiter`].
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(
+	feature = "serde_support",
+	derive(serde::Serialize, serde::Deserialize)
+)]
+#[cfg_attr(feature = "serde_support", serde(default))]
+pub struct LimiterSettings {
+	/// The maximum volume (in decibels) of the output.
+	pub threshold: Value,
+	/// How quickly the limiter stops turning down the signal
+	/// after it gets quieter (in seconds).
+	pub release: Value,
+	/// A mixer track whose output should be used to decide
+	/// how much to turn down the signal. If `None`, the
+	/// input signal will be used.
+	#[cfg_attr(feature = "serde_support", serde(skip))]
+	pub sidechain: Option<TrackId>,
+	/// How the settings move to new values after they're changed.
+	///
+	/// If `None`, the default smoothing of the audio manager
+	/// will be used.
+	pub smoothing: Option<Smoothing>,
+}
+
+impl LimiterSettings {
+	/// Creates a new `LimiterSettings` with the default settings.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Sets the maximum volume (in decibels) of the output.
+	pub fn threshold(self, threshold: impl Into<Value>) -> Self {
+		Self {
+			threshold: threshold.into(),
+			..self
+		}
+	}
+
+	/// Sets how quickly the limiter stops turning down the signal
+	/// after it gets quieter (in seconds).
+	pub fn release(self, release: impl Into<Value>) -> Self {
+		Self {
+			release: release.into(),
+			..self
+		}
+	}
+
+	/// Sets the mixer track whose output should be used to decide
+	/// how much to turn down the signal.
+	pub fn sidechain(self, track: impl Into<TrackId>) -> Self {
+		Self {
+			sidechain: Some(track.into()),
+			..self
+		}
+	}
+
+	/// Sets how the settings move to new values after they're changed.
+	pub fn smoothing(self, smoothing: impl Into<Option<Smoothing>>) -> Self {
+		Self {
+			smoothing: smoothing.into(),
+			..self
+		}
+	}
+}
+
+impl Default for LimiterSettings {
+	fn default() -> Self {
+		Self {
+			threshold: Value::Fixed(0.0),
+			release: Value::Fixed(0.1),
+			sidechain: None,
+			smoothing: None,
+		}
+	}
+}
+
+/// An effect that instantly turns down any part of the input
+/// audio that's louder than the threshold.
+pub struct Limiter {
+	threshold: CachedValue,
+	release: CachedValue,
+	sidechain: Option<TrackId>,
+	sidechain_input: Frame,
+	gain: f64,
+}
+
+impl Limiter {
+	/// Creates a new limiter.
+	pub fn new(settings: LimiterSettings) -> Self {
+		Self {
+			threshold: CachedValue::new(.., settings.threshold, 0.0)
+				.with_smoothing(settings.smoothing),
+			release: CachedValue::new(0.0.., settings.release, 0.1)
+				.with_smoothing(settings.smoothing),
+			sidechain: settings.sidechain,
+			sidechain_input: Frame::ZERO,
+			gain: 1.0,
+		}
+	}
+}
+
+impl Effect for Limiter {
+	fn sidechain(&self) -> Option<TrackId> {
+		self.sidechain
+	}
+
+	fn receive_sidechain_input(&mut self, input: Frame) {
+		self.sidechain_input = input;
+	}
+
+	fn tail_length(&self) -> Option<f64> {
+		Some(one_pole_tail_length(self.release.get()))
+	}
+
+	fn process(&mut self, input: Frame, dt: f64, parameters: &Parameters) -> Frame {
+		self.threshold.update(parameters);
+		self.release.update(parameters);
+		let key = if self.sidechain.is_some() {
+			self.sidechain_input
+		} else {
+			input
+		};
+		let peak = key.left.abs().max(key.right.abs()) as f64;
+		let threshold = decibels_to_amplitude(self.threshold.get());
+		let target_gain = if peak > threshold {
+			threshold / peak
+		} else {
+			1.0
+		};
+		let release = self.release.get();
+		self.gain = if target_gain < self.gain || release <= 0.0 {
+			target_gain
+		} else {
+			target_gain + (self.gain - target_gain) * (-dt / release).exp()
+		};
+		input * self.gain as f32
+	}
+}
@@ -0,0 +1,89 @@
+//! Building blocks shared by the delay, chorus, flanger, and phaser.
+
+use std::f64::consts::TAU;
+
+use kira::{clock::ClockId, manager::resources::Parameters, util, Frame};
+
+#[cfg(test)]
+mod test;
+
+/// A buffer of recent audio that can be read at fractional
+/// delay times.
+pub(crate) struct ModulatedDelayLine {
+	buffer: Vec<Frame>,
+	write_position: usize,
+}
+
+impl ModulatedDelayLine {
+	pub fn new(max_delay_time: f64, sample_rate: u32) -> Self {
+		// the extra frames are needed to interpolate
+		// at the maximum delay time
+		let length = (max_delay_time * sample_rate as f64).ceil() as usize + 4;
+		Self {
+			buffer: vec![Frame::ZERO; length],
+			write_position: 0,
+		}
+	}
+
+	/// Reads an interpolated frame from `delay` frames before
+	/// the frame that will be written next.
+	///
+	/// The delay is at least 2 frames, since the interpolation
+	/// needs the frame after the one being read.
+	pub fn read(&self, delay: f64) -> Frame {
+		let delay = delay.max(2.0).min((self.buffer.len() - 2) as f64);
+		let read_position =
+			(self.write_position as f64 + 1.0 - delay).rem_euclid(self.buffer.len() as f64);
+		let current_index = read_position as usize;
+		let previous_index = (current_index + self.buffer.len() - 1) % self.buffer.len();
+		let next_index = (current_index + 1) % self.buffer.len();
+		let next_index_2 = (current_index + 2) % self.buffer.len();
+		util::interpolate_frame(
+			self.buffer[previous_index],
+			self.buffer[current_index],
+			self.buffer[next_index],
+			self.buffer[next_index_2],
+			(read_position % 1.0) as f32,
+		)
+	}
+
+	pub fn write(&mut self, frame: Frame) {
+		self.write_position = (self.write_position + 1) % self.buffer.len();
+		self.buffer[self.write_position] = frame;
+	}
+}
+
+/// A sine oscillator that can optionally follow the time
+/// of a clock.
+pub(crate) struct ModulationLfo {
+	clock: Option<ClockId>,
+	phase: f64,
+}
+
+impl ModulationLfo {
+	pub fn new(clock: Option<ClockId>) -> Self {
+		Self { clock, phase: 0.0 }
+	}
+
+	/// Moves the oscillator forward by `rate` cycles per second,
+	/// or per tick if the oscillator is synced to a clock.
+	///
+	/// A synced oscillator holds its phase while the clock
+	/// isn't ticking.
+	pub fn update(&mut self, rate: f64, dt: f64, parameters: &Parameters) {
+		match self.clock {
+			Some(clock) => {
+				if let Some(position) = parameters.clock_position(clock) {
+					self.phase = (position * rate).rem_euclid(1.0);
+				}
+			}
+			None => self.phase = (self.phase + rate * dt).rem_euclid(1.0),
+		}
+	}
+
+	/// Returns the output of the oscillator (between -1 and 1)
+	/// with the phase shifted by `offset` cycles.
+	pub fn value(&self, offset: f64) -> f64 {
+		(TAU * (self.phase + offset)).sin()
+	}
+}
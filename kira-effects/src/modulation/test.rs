@@ -0,0 +1,45 @@
+use kira::Frame;
+
+use super::ModulatedDelayLine;
+
+fn assert_approximate_eq(a: f32, b: f32) {
+	const ERROR_THRESHOLD: f32 = 1.0e-6;
+	if (a - b).abs() > ERROR_THRESHOLD {
+		assert_eq!(a, b);
+	}
+}
+
+/// Creates a delay line that's had the frames 0.0, 1.0, 2.0...
+/// up to 9.0 written to it.
+fn ramp_delay_line() -> ModulatedDelayLine {
+	let mut delay_line = ModulatedDelayLine::new(1.0, 20);
+	for i in 0..10 {
+		delay_line.write(Frame::from_mono(i as f32));
+	}
+	delay_line
+}
+
+#[test]
+fn reads_whole_frame_delays_exactly() {
+	let delay_line = ramp_delay_line();
+	// the next frame to be written is frame 10
+	for delay in 2..=10 {
+		assert_approximate_eq(delay_line.read(delay as f64).left, 10.0 - delay as f32);
+	}
+}
+
+#[test]
+fn interpolates_fractional_delays() {
+	let delay_line = ramp_delay_line();
+	// the interpolation reproduces a straight line exactly
+	for delay in [2.5, 3.25, 6.75, 8.1] {
+		assert_approximate_eq(delay_line.read(delay).left, 10.0 - delay as f32);
+	}
+}
+
+#[test]
+fn clamps_delays_to_the_readable_range() {
+	let delay_line = ramp_delay_line();
+	assert_approximate_eq(delay_line.read(0.5).left, delay_line.read(2.0).left);
+	assert_approximate_eq(delay_line.read(1000.0).left, delay_line.read(22.0).left);
+}
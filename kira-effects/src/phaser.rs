@@ -0,0 +1,240 @@
+//! Creates a sweeping sound by moving notches through
+//! the frequency spectrum.
+
+use std::f64::consts::PI;
+
+use kira::{
+	clock::ClockId,
+	manager::resources::Parameters,
+	track::Effect,
+	value::{cached::CachedValue, Smoothing, Value},
+	Frame,
+};
+
+use crate::{modulation::ModulationLfo, tail::FILTER_TAIL_LENGTH};
+
+/// The number of all-pass filters each channel goes through.
+const NUM_STAGES: usize = 6;
+/// The lowest frequency (in hertz) the all-pass filters are tuned to.
+const MIN_FREQUENCY: f64 = 200.0;
+/// How many octaves above the minimum frequency the all-pass
+/// filters sweep at full depth.
+const MAX_OCTAVES: f64 = 5.0;
+
+/// Settings for a [`Phaser`].
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(
+	feature = "serde_support",
+	derive(serde::Serialize, serde::Deserialize)
+)]
+#[cfg_attr(feature = "serde_support", serde(default))]
+pub struct PhaserSettings {
+	/// How many times the filters sweep up and down per second,
+	/// or per tick if the phaser is synced to a clock.
+	pub rate: Value,
+	/// How far the filters sweep, from 0.0 to 1.0.
+	pub depth: Value,
+	/// How much of the output is fed back into the phaser.
+	///
+	/// Values are limited to between -0.95 and 0.95 to keep
+	/// the filters stable.
+	pub feedback: Value,
+	/// The balance between dry (unaffected) signal and wet
+	/// (affected) signal to output. 0.0 is fully dry,
+	/// 1.0 is fully wet.
+	pub mix: Value,
+	/// The clock the oscillation should be synced to, if any.
+	#[cfg_attr(feature = "serde_support", serde(skip))]
+	pub clock: Option<ClockId>,
+	/// How the settings move to new values after they're changed.
+	///
+	/// If `None`, the default smoothing of the audio manager
+	/// will be used.
+	pub smoothing: Option<Smoothing>,
+}
+
+impl PhaserSettings {
+	/// Creates a new `PhaserSettings` with the default settings.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Sets how many times the filters sweep up and down per second,
+	/// or per tick if the phaser is synced to a clock.
+	pub fn rate(self, rate: impl Into<Value>) -> Self {
+		Self {
+			rate: rate.into(),
+			..self
+		}
+	}
+
+	/// Sets how far the filters sweep, from 0.0 to 1.0.
+	pub fn depth(self, depth: impl Into<Value>) -> Self {
+		Self {
+			depth: depth.into(),
+			..self
+		}
+	}
+
+	/// Sets how much of the output is fed back into the phaser.
+	pub fn feedback(self, feedback: impl Into<Value>) -> Self {
+		Self {
+			feedback: feedback.into(),
+			..self
+		}
+	}
+
+	/// Sets the balance between dry (unaffected) signal and wet
+	/// (affected) signal to output. 0.0 is fully dry, 1.0 is fully wet.
+	pub fn mix(self, mix: impl Into<Value>) -> Self {
+		Self {
+			mix: mix.into(),
+			..self
+		}
+	}
+
+	/// Sets the clock the oscillation should be synced to, if any.
+	pub fn clock(self, clock: impl Into<Option<ClockId>>) -> Self {
+		Self {
+			clock: clock.into(),
+			..self
+		}
+	}
+
+	/// Sets how the settings move to new values after they're changed.
+	pub fn smoothing(self, smoothing: impl Into<Option<Smoothing>>) -> Self {
+		Self {
+			smoothing: smoothing.into(),
+			..self
+		}
+	}
+}
+
+impl Default for PhaserSettings {
+	fn default() -> Self {
+		Self {
+			rate: Value::Fixed(0.5),
+			depth: Value::Fixed(0.5),
+			feedback: Value::Fixed(0.0),
+			mix: Value::Fixed(0.5),
+			clock: None,
+			smoothing: None,
+		}
+	}
+}
+
+/// A first-order all-pass filter, which shifts the phase of
+/// frequencies around its tuning frequency without changing
+/// their volume.
+#[derive(Debug, Clone, Copy)]
+struct AllPassStage {
+	state: f64,
+}
+
+impl AllPassStage {
+	fn new() -> Self {
+		Self { state: 0.0 }
+	}
+
+	fn process(&mut self, input: f64, coefficient: f64) -> f64 {
+		let output = coefficient * input + self.state;
+		self.state = input - coefficient * output;
+		output
+	}
+}
+
+/// Processes one channel of audio through a chain of
+/// all-pass filters.
+struct PhaserChannel {
+	stages: [AllPassStage; NUM_STAGES],
+	previous_output: f64,
+}
+
+impl PhaserChannel {
+	fn new() -> Self {
+		Self {
+			stages: [AllPassStage::new(); NUM_STAGES],
+			previous_output: 0.0,
+		}
+	}
+
+	fn process(&mut self, input: f64, coefficient: f64, feedback: f64) -> f64 {
+		let mut output = input + self.previous_output * feedback;
+		for stage in &mut self.stages {
+			output = stage.process(output, coefficient);
+		}
+		self.previous_output = output;
+		output
+	}
+}
+
+/// An effect that mixes input audio with a copy of itself
+/// that has been passed through a chain of all-pass filters
+/// whose frequency slowly oscillates. The phase differences
+/// between the two copies cancel out some frequencies.
+///
+/// The left and right channels are modulated out of phase
+/// to widen the sound.
+pub struct Phaser {
+	rate: CachedValue,
+	depth: CachedValue,
+	feedback: CachedValue,
+	mix: CachedValue,
+	lfo: ModulationLfo,
+	channels: (PhaserChannel, PhaserChannel),
+}
+
+impl Phaser {
+	/// Creates a new phaser effect.
+	pub fn new(settings: PhaserSettings) -> Self {
+		Self {
+			rate: CachedValue::new(0.0.., settings.rate, 0.5).with_smoothing(settings.smoothing),
+			depth: CachedValue::new(0.0..=1.0, settings.depth, 0.5)
+				.with_smoothing(settings.smoothing),
+			feedback: CachedValue::new(-0.95..=0.95, settings.feedback, 0.0)
+				.with_smoothing(settings.smoothing),
+			mix: CachedValue::new(0.0..=1.0, settings.mix, 0.5).with_smoothing(settings.smoothing),
+			lfo: ModulationLfo::new(settings.clock),
+			channels: (PhaserChannel::new(), PhaserChannel::new()),
+		}
+	}
+}
+
+impl Phaser {
+	/// Returns the all-pass coefficient for a point in the sweep
+	/// (between -1 and 1).
+	fn coefficient(&self, sweep: f64, dt: f64) -> f64 {
+		let octaves = MAX_OCTAVES * self.depth.get() * (sweep + 1.0) / 2.0;
+		let frequency = MIN_FREQUENCY * 2.0f64.powf(octaves);
+		// keep the frequency under the nyquist frequency
+		let tan = (PI * (frequency * dt).min(0.49)).tan();
+		(tan - 1.0) / (tan + 1.0)
+	}
+}
+
+impl Effect for Phaser {
+	fn tail_length(&self) -> Option<f64> {
+		Some(FILTER_TAIL_LENGTH)
+	}
+
+	fn process(&mut self, input: Frame, dt: f64, parameters: &Parameters) -> Frame {
+		self.rate.update(parameters);
+		self.depth.update(parameters);
+		self.feedback.update(parameters);
+		self.mix.update(parameters);
+		self.lfo.update(self.rate.get(), dt, parameters);
+		let left_coefficient = self.coefficient(self.lfo.value(0.0), dt);
+		let right_coefficient = self.coefficient(self.lfo.value(0.25), dt);
+		let feedback = self.feedback.get();
+		let output = Frame::new(
+			self.channels
+				.0
+				.process(input.left as f64, left_coefficient, feedback) as f32,
+			self.channels
+				.1
+				.process(input.right as f64, right_coefficient, feedback) as f32,
+		);
+		let mix = self.mix.get() as f32;
+		output * mix + input * (1.0 - mix)
+	}
+}
@@ -0,0 +1,222 @@
+//! Describes mixer tracks and their effects as plain data.
+//!
+//! A [`TrackPreset`] holds the settings for a track and each
+//! of its effects, so it can be stored in a file (with the
+//! `serde_support` feature enabled) and turned into
+//! [`TrackSettings`] when the game runs.
+//!
+//! Effects are identified by the variants of [`EffectSettings`].
+//! [`ConvolutionReverb`](crate::convolution_reverb::ConvolutionReverb)
+//! and [`EnvelopeFollower`](crate::envelope_follower::EnvelopeFollower)
+//! need resources that only exist at runtime, so they can't be
+//! described by a preset.
+
+use kira::{
+	track::{Effect, TrackSettings},
+	value::{Smoothing, Value},
+};
+
+use crate::{
+	chorus::{Chorus, ChorusSettings},
+	compressor::{Compressor, CompressorSettings},
+	delay::{Delay, DelaySettings},
+	distortion::{Distortion, DistortionSettings},
+	eq::{ParametricEq, ParametricEqSettings},
+	filter::{Filter, FilterSettings},
+	flanger::{Flanger, FlangerSettings},
+	gate::{Gate, GateSettings},
+	limiter::{Limiter, LimiterSettings},
+	phaser::{Phaser, PhaserSettings},
+	reverb::{Reverb, ReverbSettings},
+	stereo_imager::{StereoImager, StereoImagerSettings},
+};
+
+/// The kind of an effect along with its settings.
+#[derive(Debug, Clone)]
+#[cfg_attr(
+	feature = "serde_support",
+	derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum EffectSettings {
+	/// A [`Chorus`] effect.
+	Chorus(ChorusSettings),
+	/// A [`Compressor`] effect.
+	Compressor(CompressorSettings),
+	/// A [`Delay`] effect.
+	Delay(DelaySettings),
+	/// A [`Distortion`] effect.
+	Distortion(DistortionSettings),
+	/// A [`Filter`] effect.
+	Filter(FilterSettings),
+	/// A [`Flanger`] effect.
+	Flanger(FlangerSettings),
+	/// A [`Gate`] effect.
+	Gate(GateSettings),
+	/// A [`Limiter`] effect.
+	Limiter(LimiterSettings),
+	/// A [`ParametricEq`] effect.
+	ParametricEq(ParametricEqSettings),
+	/// A [`Phaser`] effect.
+	Phaser(PhaserSettings),
+	/// A [`Reverb`] effect.
+	Reverb(ReverbSettings),
+	/// A [`StereoImager`] effect.
+	StereoImager(StereoImagerSettings),
+}
+
+impl EffectSettings {
+	/// Creates the effect these settings describe.
+	pub fn into_effect(self) -> Box<dyn Effect> {
+		match self {
+			Self::Chorus(settings) => Box::new(Chorus::new(settings)),
+			Self::Compressor(settings) => Box::new(Compressor::new(settings)),
+			Self::Delay(settings) => Box::new(Delay::new(settings)),
+			Self::Distortion(settings) => Box::new(Distortion::new(settings)),
+			Self::Filter(settings) => Box::new(Filter::new(settings)),
+			Self::Flanger(settings) => Box::new(Flanger::new(settings)),
+			Self::Gate(settings) => Box::new(Gate::new(settings)),
+			Self::Limiter(settings) => Box::new(Limiter::new(settings)),
+			Self::ParametricEq(settings) => Box::new(ParametricEq::new(settings)),
+			Self::Phaser(settings) => Box::new(Phaser::new(settings)),
+			Self::Reverb(settings) => Box::new(Reverb::new(settings)),
+			Self::StereoImager(settings) => Box::new(StereoImager::new(settings)),
+		}
+	}
+}
+
+macro_rules! impl_from_settings {
+	($($variant:ident($settings:ty)),* $(,)?) => {
+		$(
+			impl From<$settings> for EffectSettings {
+				fn from(settings: $settings) -> Self {
+					Self::$variant(settings)
+				}
+			}
+		)*
+	};
+}
+
+impl_from_settings!(
+	Chorus(ChorusSettings),
+	Compressor(CompressorSettings),
+	Delay(DelaySettings),
+	Distortion(DistortionSettings),
+	Filter(FilterSettings),
+	Flanger(FlangerSettings),
+	Gate(GateSettings),
+	Limiter(LimiterSettings),
+	ParametricEq(ParametricEqSettings),
+	Phaser(PhaserSettings),
+	Reverb(ReverbSettings),
+	StereoImager(StereoImagerSettings),
+);
+
+/// Settings for a mixer track and its effects.
+///
+/// Routes aren't included, since the tracks they point to
+/// only exist at runtime. They can be added to the
+/// [`TrackSettings`] after the preset is converted.
+#[derive(Debug, Clone)]
+#[cfg_attr(
+	feature = "serde_support",
+	derive(serde::Serialize, serde::Deserialize)
+)]
+#[cfg_attr(feature = "serde_support", serde(default))]
+pub struct TrackPreset {
+	/// The volume of the track.
+	pub volume: Value,
+	/// The panning of the track, where 0 is hard left
+	/// and 1 is hard right.
+	pub panning: Value,
+	/// The effects that should be applied to the input audio
+	/// for this track, in order.
+	pub effects: Vec<EffectSettings>,
+	/// Whether the track starts muted.
+	pub muted: bool,
+	/// Whether the track stays audible when other tracks
+	/// are soloed.
+	pub solo_safe: bool,
+	/// How the volume, panning, and route amounts move to new
+	/// values after they're changed.
+	pub smoothing: Option<Smoothing>,
+}
+
+impl TrackPreset {
+	/// Creates a new `TrackPreset` with the default settings.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Sets the volume of the track.
+	pub fn volume(self, volume: impl Into<Value>) -> Self {
+		Self {
+			volume: volume.into(),
+			..self
+		}
+	}
+
+	/// Sets the panning of the track, where 0 is hard left
+	/// and 1 is hard right.
+	pub fn panning(self, panning: impl Into<Value>) -> Self {
+		Self {
+			panning: panning.into(),
+			..self
+		}
+	}
+
+	/// Adds an effect to the track.
+	pub fn with_effect(mut self, effect: impl Into<EffectSettings>) -> Self {
+		self.effects.push(effect.into());
+		self
+	}
+
+	/// Sets whether the track starts muted.
+	pub fn muted(self, muted: bool) -> Self {
+		Self { muted, ..self }
+	}
+
+	/// Sets whether the track stays audible when other tracks
+	/// are soloed.
+	pub fn solo_safe(self, solo_safe: bool) -> Self {
+		Self { solo_safe, ..self }
+	}
+
+	/// Sets how the volume, panning, and route amounts move to new
+	/// values after they're changed.
+	pub fn smoothing(self, smoothing: impl Into<Option<Smoothing>>) -> Self {
+		Self {
+			smoothing: smoothing.into(),
+			..self
+		}
+	}
+
+	/// Creates the effects for the track and returns the
+	/// [`TrackSettings`] to create the track with.
+	pub fn into_track_settings(self) -> TrackSettings {
+		let mut settings = TrackSettings::new()
+			.volume(self.volume)
+			.panning(self.panning)
+			.muted(self.muted)
+			.solo_safe(self.solo_safe)
+			.smoothing(self.smoothing);
+		settings.effects = self
+			.effects
+			.into_iter()
+			.map(EffectSettings::into_effect)
+			.collect();
+		settings
+	}
+}
+
+impl Default for TrackPreset {
+	fn default() -> Self {
+		Self {
+			volume: Value::Fixed(1.0),
+			panning: Value::Fixed(0.5),
+			effects: vec![],
+			muted: false,
+			solo_safe: false,
+			smoothing: None,
+		}
+	}
+}
@@ -1,34 +1,94 @@
 //! Adds reverberations to a sound.
 
-use all_pass::AllPassFilter;
+use std::time::Duration;
+
+use all_pass::{AllPassFilter, FEEDBACK as ALL_PASS_FEEDBACK};
 use comb::CombFilter;
+use early_reflections::EarlyReflections;
+use fdn::{FeedbackDelayNetwork, DELAY_LINE_LENGTHS, NUM_DELAY_LINES};
 use kira::{
 	manager::resources::Parameters,
 	track::Effect,
-	value::{cached::CachedValue, Value},
+	value::{cached::CachedValue, Smoothing, Value},
 	Frame,
 };
 
 mod all_pass;
 mod comb;
+mod early_reflections;
+mod fdn;
+
+use crate::tail::feedback_tail_length;
 
 const NUM_COMB_FILTERS: usize = 8;
 const NUM_ALL_PASS_FILTERS: usize = 4;
 const GAIN: f32 = 0.015;
+const FDN_GAIN: f32 = 0.1;
 const STEREO_SPREAD: usize = 23;
+/// The sample rate the buffer sizes are tuned for.
+const REFERENCE_SAMPLE_RATE: u32 = 44100;
+
+/// The algorithm used to generate the reverberations.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde_support",
+	derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum ReverbKind {
+	/// A network of parallel comb filters and series all-pass
+	/// filters, based on Freeverb.
+	Freeverb,
+	/// A feedback delay network, which produces denser
+	/// and less metallic reverberations.
+	FeedbackDelayNetwork,
+}
+
+impl Default for ReverbKind {
+	fn default() -> Self {
+		Self::Freeverb
+	}
+}
 
 /// Settings for a `Reverb`.
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(
+	feature = "serde_support",
+	derive(serde::Serialize, serde::Deserialize)
+)]
+#[cfg_attr(feature = "serde_support", serde(default))]
 pub struct ReverbSettings {
+	/// The algorithm used to generate the reverberations.
+	pub kind: ReverbKind,
 	/// How much the room reverberates. A higher value will
 	/// result in a bigger sounding room. 1.0 gives an infinitely
 	/// reverberating room.
-	feedback: Value,
+	pub feedback: Value,
 	/// How quickly high frequencies disappear from the reverberation.
-	damping: Value,
+	pub damping: Value,
 	/// The stereo width of the reverb effect (0.0 being fully mono,
 	/// 1.0 being fully stereo).
-	stereo_width: Value,
+	pub stereo_width: Value,
+	/// The amount of time before the reverberations start.
+	pub pre_delay: Duration,
+	/// A factor that scales the lengths of the reverb's delay lines.
+	/// Higher values result in sparser echoes, like in a larger room.
+	pub room_size: f64,
+	/// The volume of the early reflections, the first few distinct
+	/// echoes off the walls of the room.
+	pub early_reflections: Value,
+	/// How frozen the reverb is. When the reverb is fully frozen
+	/// (1.0), it stops receiving input and the existing
+	/// reverberations sustain indefinitely.
+	pub freeze: Value,
+	/// The balance between dry (unaffected) signal and wet
+	/// (affected) signal to output. 0.0 is fully dry,
+	/// 1.0 is fully wet.
+	pub mix: Value,
+	/// How the settings move to new values after they're changed.
+	///
+	/// If `None`, the default smoothing of the audio manager
+	/// will be used.
+	pub smoothing: Option<Smoothing>,
 }
 
 impl ReverbSettings {
@@ -37,6 +97,11 @@ impl ReverbSettings {
 		Self::default()
 	}
 
+	/// Sets the algorithm used to generate the reverberations.
+	pub fn kind(self, kind: ReverbKind) -> Self {
+		Self { kind, ..self }
+	}
+
 	/// Sets how much the room reverberates. A higher value will
 	/// result in a bigger sounding room. 1.0 gives an infinitely
 	/// reverberating room.
@@ -63,34 +128,105 @@ impl ReverbSettings {
 			..self
 		}
 	}
+
+	/// Sets the amount of time before the reverberations start.
+	pub fn pre_delay(self, pre_delay: Duration) -> Self {
+		Self { pre_delay, ..self }
+	}
+
+	/// Sets the factor that scales the lengths of the reverb's
+	/// delay lines.
+	pub fn room_size(self, room_size: f64) -> Self {
+		Self { room_size, ..self }
+	}
+
+	/// Sets the volume of the early reflections.
+	pub fn early_reflections(self, early_reflections: impl Into<Value>) -> Self {
+		Self {
+			early_reflections: early_reflections.into(),
+			..self
+		}
+	}
+
+	/// Sets how frozen the reverb is.
+	pub fn freeze(self, freeze: impl Into<Value>) -> Self {
+		Self {
+			freeze: freeze.into(),
+			..self
+		}
+	}
+
+	/// Sets the balance between dry (unaffected) signal and wet
+	/// (affected) signal to output. 0.0 is fully dry, 1.0 is fully wet.
+	pub fn mix(self, mix: impl Into<Value>) -> Self {
+		Self {
+			mix: mix.into(),
+			..self
+		}
+	}
+
+	/// Sets how the settings move to new values after they're changed.
+	///
+	/// If `None`, the default smoothing of the audio manager
+	/// will be used.
+	pub fn smoothing(self, smoothing: impl Into<Option<Smoothing>>) -> Self {
+		Self {
+			smoothing: smoothing.into(),
+			..self
+		}
+	}
 }
 
 impl Default for ReverbSettings {
 	fn default() -> Self {
 		Self {
+			kind: ReverbKind::default(),
 			feedback: Value::Fixed(0.9),
 			damping: Value::Fixed(0.1),
 			stereo_width: Value::Fixed(1.0),
+			pre_delay: Duration::ZERO,
+			room_size: 1.0,
+			early_reflections: Value::Fixed(0.0),
+			freeze: Value::Fixed(0.0),
+			mix: Value::Fixed(1.0),
+			smoothing: None,
 		}
 	}
 }
 
+#[derive(Debug)]
+enum ReverbTail {
+	Freeverb {
+		comb_filters: [(CombFilter, CombFilter); NUM_COMB_FILTERS],
+		all_pass_filters: [(AllPassFilter, AllPassFilter); NUM_ALL_PASS_FILTERS],
+	},
+	FeedbackDelayNetwork(FeedbackDelayNetwork),
+}
+
 #[derive(Debug)]
 enum ReverbState {
 	Uninitialized,
 	Initialized {
-		comb_filters: [(CombFilter, CombFilter); NUM_COMB_FILTERS],
-		all_pass_filters: [(AllPassFilter, AllPassFilter); NUM_ALL_PASS_FILTERS],
+		pre_delay_buffer: Vec<f32>,
+		pre_delay_index: usize,
+		early_reflections: EarlyReflections,
+		tail: ReverbTail,
 	},
 }
 
 /// A reverb effect. Useul for simulating room tones.
-// This code is based on Freeverb by Jezar at Dreampoint, found here:
+// The Freeverb code is based on Freeverb by Jezar at Dreampoint, found here:
 // http://blog.bjornroche.com/2012/06/freeverb-original-public-domain-code-by.html
 pub struct Reverb {
+	kind: ReverbKind,
 	feedback: CachedValue,
 	damping: CachedValue,
 	stereo_width: CachedValue,
+	pre_delay: Duration,
+	room_size: f64,
+	early_reflections: CachedValue,
+	freeze: CachedValue,
+	mix: CachedValue,
 	state: ReverbState,
 }
 
@@ -98,9 +234,20 @@ impl Reverb {
 	/// Creates a new `Reverb` effect.
 	pub fn new(settings: ReverbSettings) -> Self {
 		Self {
-			feedback: CachedValue::new(-1.0..=1.0, settings.feedback, 0.9),
-			damping: CachedValue::new(0.0..=1.0, settings.damping, 0.1),
-			stereo_width: CachedValue::new(0.0..=1.0, settings.stereo_width, 1.0),
+			kind: settings.kind,
+			feedback: CachedValue::new(-1.0..=1.0, settings.feedback, 0.9)
+				.with_smoothing(settings.smoothing),
+			damping: CachedValue::new(0.0..=1.0, settings.damping, 0.1)
+				.with_smoothing(settings.smoothing),
+			stereo_width: CachedValue::new(0.0..=1.0, settings.stereo_width, 1.0)
+				.with_smoothing(settings.smoothing),
+			pre_delay: settings.pre_delay,
+			room_size: settings.room_size,
+			early_reflections: CachedValue::new(0.0.., settings.early_reflections, 0.0)
+				.with_smoothing(settings.smoothing),
+			freeze: CachedValue::new(0.0..=1.0, settings.freeze, 0.0)
+				.with_smoothing(settings.smoothing),
+			mix: CachedValue::new(0.0..=1.0, settings.mix, 1.0).with_smoothing(settings.smoothing),
 			state: ReverbState::Uninitialized,
 		}
 	}
@@ -109,104 +256,166 @@ impl Reverb {
 impl Effect for Reverb {
 	fn init(&mut self, sample_rate: u32) {
 		if let ReverbState::Uninitialized = &self.state {
-			const REFERENCE_SAMPLE_RATE: u32 = 44100;
-
+			let room_size = self.room_size;
 			let adjust_buffer_size = |buffer_size: usize| -> usize {
 				let sample_rate_factor = (sample_rate as f64) / (REFERENCE_SAMPLE_RATE as f64);
-				((buffer_size as f64) * sample_rate_factor) as usize
+				(((buffer_size as f64) * sample_rate_factor * room_size) as usize).max(1)
 			};
 
+			let pre_delay_frames = (self.pre_delay.as_secs_f64() * sample_rate as f64) as usize;
 			self.state = ReverbState::Initialized {
-				comb_filters: [
-					(
-						CombFilter::new(adjust_buffer_size(1116)),
-						CombFilter::new(adjust_buffer_size(1116 + STEREO_SPREAD)),
-					),
-					(
-						CombFilter::new(adjust_buffer_size(1188)),
-						CombFilter::new(adjust_buffer_size(1188 + STEREO_SPREAD)),
-					),
-					(
-						CombFilter::new(adjust_buffer_size(1277)),
-						CombFilter::new(adjust_buffer_size(1277 + STEREO_SPREAD)),
-					),
-					(
-						CombFilter::new(adjust_buffer_size(1356)),
-						CombFilter::new(adjust_buffer_size(1356 + STEREO_SPREAD)),
-					),
-					(
-						CombFilter::new(adjust_buffer_size(1422)),
-						CombFilter::new(adjust_buffer_size(1422 + STEREO_SPREAD)),
+				pre_delay_buffer: vec![0.0; pre_delay_frames + 1],
+				pre_delay_index: 0,
+				early_reflections: EarlyReflections::new(sample_rate, room_size),
+				tail: match self.kind {
+					ReverbKind::Freeverb => ReverbTail::Freeverb {
+						comb_filters: [
+							(
+								CombFilter::new(adjust_buffer_size(1116)),
+								CombFilter::new(adjust_buffer_size(1116 + STEREO_SPREAD)),
+							),
+							(
+								CombFilter::new(adjust_buffer_size(1188)),
+								CombFilter::new(adjust_buffer_size(1188 + STEREO_SPREAD)),
+							),
+							(
+								CombFilter::new(adjust_buffer_size(1277)),
+								CombFilter::new(adjust_buffer_size(1277 + STEREO_SPREAD)),
+							),
+							(
+								CombFilter::new(adjust_buffer_size(1356)),
+								CombFilter::new(adjust_buffer_size(1356 + STEREO_SPREAD)),
+							),
+							(
+								CombFilter::new(adjust_buffer_size(1422)),
+								CombFilter::new(adjust_buffer_size(1422 + STEREO_SPREAD)),
+							),
+							(
+								CombFilter::new(adjust_buffer_size(1491)),
+								CombFilter::new(adjust_buffer_size(1491 + STEREO_SPREAD)),
+							),
+							(
+								CombFilter::new(adjust_buffer_size(1557)),
+								CombFilter::new(adjust_buffer_size(1557 + STEREO_SPREAD)),
+							),
+							(
+								CombFilter::new(adjust_buffer_size(1617)),
+								CombFilter::new(adjust_buffer_size(1617 + STEREO_SPREAD)),
+							),
+						],
+						all_pass_filters: [
+							(
+								AllPassFilter::new(adjust_buffer_size(556)),
+								AllPassFilter::new(adjust_buffer_size(556 + STEREO_SPREAD)),
+							),
+							(
+								AllPassFilter::new(adjust_buffer_size(441)),
+								AllPassFilter::new(adjust_buffer_size(441 + STEREO_SPREAD)),
+							),
+							(
+								AllPassFilter::new(adjust_buffer_size(341)),
+								AllPassFilter::new(adjust_buffer_size(341 + STEREO_SPREAD)),
+							),
+							(
+								AllPassFilter::new(adjust_buffer_size(225)),
+								AllPassFilter::new(adjust_buffer_size(225 + STEREO_SPREAD)),
+							),
+						],
+					},
+					ReverbKind::FeedbackDelayNetwork => ReverbTail::FeedbackDelayNetwork(
+						FeedbackDelayNetwork::new(DELAY_LINE_LENGTHS.map(adjust_buffer_size)),
 					),
-					(
-						CombFilter::new(adjust_buffer_size(1491)),
-						CombFilter::new(adjust_buffer_size(1491 + STEREO_SPREAD)),
-					),
-					(
-						CombFilter::new(adjust_buffer_size(1557)),
-						CombFilter::new(adjust_buffer_size(1557 + STEREO_SPREAD)),
-					),
-					(
-						CombFilter::new(adjust_buffer_size(1617)),
-						CombFilter::new(adjust_buffer_size(1617 + STEREO_SPREAD)),
-					),
-				],
-				all_pass_filters: [
-					(
-						AllPassFilter::new(adjust_buffer_size(556)),
-						AllPassFilter::new(adjust_buffer_size(556 + STEREO_SPREAD)),
-					),
-					(
-						AllPassFilter::new(adjust_buffer_size(441)),
-						AllPassFilter::new(adjust_buffer_size(441 + STEREO_SPREAD)),
-					),
-					(
-						AllPassFilter::new(adjust_buffer_size(341)),
-						AllPassFilter::new(adjust_buffer_size(341 + STEREO_SPREAD)),
-					),
-					(
-						AllPassFilter::new(adjust_buffer_size(225)),
-						AllPassFilter::new(adjust_buffer_size(225 + STEREO_SPREAD)),
-					),
-				],
+				},
 			}
 		} else {
 			panic!("Reverb should be in the uninitialized state before init");
 		}
 	}
 
+	fn tail_length(&self) -> Option<f64> {
+		// a frozen reverb rings out forever
+		if self.freeze.get() > 0.0 {
+			return None;
+		}
+		let buffer_time = |buffer_size: usize| -> f64 {
+			buffer_size as f64 / REFERENCE_SAMPLE_RATE as f64 * self.room_size
+		};
+		let tail_length = match self.kind {
+			ReverbKind::Freeverb => {
+				feedback_tail_length(buffer_time(1617 + STEREO_SPREAD), self.feedback.get())?
+					+ feedback_tail_length(
+						buffer_time(556 + STEREO_SPREAD),
+						ALL_PASS_FEEDBACK as f64,
+					)? * NUM_ALL_PASS_FILTERS as f64
+			}
+			ReverbKind::FeedbackDelayNetwork => feedback_tail_length(
+				buffer_time(DELAY_LINE_LENGTHS[NUM_DELAY_LINES - 1]),
+				self.feedback.get(),
+			)?,
+		};
+		Some(self.pre_delay.as_secs_f64() + EarlyReflections::length(self.room_size) + tail_length)
+	}
+
 	fn process(&mut self, input: Frame, _dt: f64, parameters: &Parameters) -> Frame {
 		if let ReverbState::Initialized {
-			comb_filters,
-			all_pass_filters,
+			pre_delay_buffer,
+			pre_delay_index,
+			early_reflections,
+			tail,
 		} = &mut self.state
 		{
 			self.feedback.update(parameters);
 			self.damping.update(parameters);
 			self.stereo_width.update(parameters);
+			self.early_reflections.update(parameters);
+			self.freeze.update(parameters);
+			self.mix.update(parameters);
 
+			// freezing the reverb blends the feedback towards 1.0
+			// and the damping and input towards 0.0
+			let freeze = self.freeze.get() as f32;
 			let feedback = self.feedback.get() as f32;
-			let damping = self.damping.get() as f32;
+			let feedback = feedback + (1.0 - feedback) * freeze;
+			let damping = self.damping.get() as f32 * (1.0 - freeze);
 			let stereo_width = self.stereo_width.get() as f32;
 
-			let mut output = Frame::ZERO;
-			let input = (input.left + input.right) * GAIN;
-			// accumulate comb filters in parallel
-			for comb_filter in comb_filters {
-				output.left += comb_filter.0.process(input, feedback, damping);
-				output.right += comb_filter.1.process(input, feedback, damping);
-			}
-			// feed through all-pass filters in series
-			for all_pass_filter in all_pass_filters {
-				output.left = all_pass_filter.0.process(output.left);
-				output.right = all_pass_filter.1.process(output.right);
-			}
+			pre_delay_buffer[*pre_delay_index] = (input.left + input.right) * (1.0 - freeze);
+			*pre_delay_index = (*pre_delay_index + 1) % pre_delay_buffer.len();
+			let mono_input = pre_delay_buffer[*pre_delay_index];
+
+			let mut output = match tail {
+				ReverbTail::Freeverb {
+					comb_filters,
+					all_pass_filters,
+				} => {
+					let mut output = Frame::ZERO;
+					let input = mono_input * GAIN;
+					// accumulate comb filters in parallel
+					for comb_filter in comb_filters {
+						output.left += comb_filter.0.process(input, feedback, damping);
+						output.right += comb_filter.1.process(input, feedback, damping);
+					}
+					// feed through all-pass filters in series
+					for all_pass_filter in all_pass_filters {
+						output.left = all_pass_filter.0.process(output.left);
+						output.right = all_pass_filter.1.process(output.right);
+					}
+					output
+				}
+				ReverbTail::FeedbackDelayNetwork(fdn) => {
+					fdn.process(mono_input * FDN_GAIN, feedback, damping)
+				}
+			};
 			let wet_1 = stereo_width / 2.0 + 0.5;
 			let wet_2 = (1.0 - stereo_width) / 2.0;
-			Frame::new(
+			output = Frame::new(
 				output.left * wet_1 + output.right * wet_2,
 				output.right * wet_1 + output.left * wet_2,
-			)
+			);
+			output +=
+				early_reflections.process(mono_input / 2.0) * self.early_reflections.get() as f32;
+			let mix = self.mix.get() as f32;
+			output * mix + input * (1.0 - mix)
 		} else {
 			panic!("Reverb should be initialized before the first process call")
 		}
@@ -1,4 +1,4 @@
-const FEEDBACK: f32 = 0.5;
+pub const FEEDBACK: f32 = 0.5;
 
 #[derive(Debug)]
 pub struct AllPassFilter {
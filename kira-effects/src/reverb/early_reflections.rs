@@ -0,0 +1,59 @@
+use kira::Frame;
+
+/// The time (in seconds), volume, and panning of each
+/// early reflection at a room size of 1.0.
+const TAPS: [(f64, f32, f32); 8] = [
+	(0.0043, 0.841, 0.2),
+	(0.0215, 0.504, 0.8),
+	(0.0225, 0.491, 0.35),
+	(0.0268, 0.379, 0.65),
+	(0.0270, 0.380, 0.1),
+	(0.0298, 0.346, 0.9),
+	(0.0458, 0.289, 0.45),
+	(0.0485, 0.272, 0.55),
+];
+
+/// Simulates the first few echoes off the walls of a room
+/// with a tapped delay line.
+#[derive(Debug)]
+pub struct EarlyReflections {
+	buffer: Vec<f32>,
+	taps: [(usize, f32, f32); TAPS.len()],
+	current_index: usize,
+}
+
+impl EarlyReflections {
+	/// Returns the time (in seconds) of the last early reflection.
+	pub fn length(room_size: f64) -> f64 {
+		TAPS.iter().map(|(time, _, _)| *time).fold(0.0, f64::max) * room_size
+	}
+
+	pub fn new(sample_rate: u32, room_size: f64) -> Self {
+		let mut taps = [(0, 0.0, 0.0); TAPS.len()];
+		for (tap, (time, volume, panning)) in taps.iter_mut().zip(TAPS.iter()) {
+			*tap = (
+				(time * room_size * sample_rate as f64) as usize,
+				*volume,
+				*panning,
+			);
+		}
+		let buffer_size = taps.iter().map(|(delay, _, _)| *delay).max().unwrap_or(0) + 1;
+		Self {
+			buffer: vec![0.0; buffer_size],
+			taps,
+			current_index: 0,
+		}
+	}
+
+	pub fn process(&mut self, input: f32) -> Frame {
+		self.buffer[self.current_index] = input;
+		let mut output = Frame::ZERO;
+		for (delay, volume, panning) in &self.taps {
+			let index = (self.current_index + self.buffer.len() - delay) % self.buffer.len();
+			output += Frame::from_mono(self.buffer[index] * volume).panned(*panning);
+		}
+		self.current_index += 1;
+		self.current_index %= self.buffer.len();
+		output
+	}
+}
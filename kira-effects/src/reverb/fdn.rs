@@ -0,0 +1,91 @@
+use kira::Frame;
+
+pub const NUM_DELAY_LINES: usize = 8;
+
+/// The length of each delay line (in samples) at 44.1kHz
+/// and a room size of 1.0. The lengths are all prime so
+/// the echoes don't line up with each other.
+pub const DELAY_LINE_LENGTHS: [usize; NUM_DELAY_LINES] =
+	[1031, 1327, 1523, 1709, 1871, 2053, 2221, 2381];
+
+#[derive(Debug)]
+struct DelayLine {
+	buffer: Vec<f32>,
+	current_index: usize,
+	filter_store: f32,
+}
+
+impl DelayLine {
+	fn new(buffer_size: usize) -> Self {
+		Self {
+			buffer: vec![0.0; buffer_size],
+			current_index: 0,
+			filter_store: 0.0,
+		}
+	}
+
+	fn output(&self) -> f32 {
+		self.buffer[self.current_index]
+	}
+
+	fn write(&mut self, input: f32) {
+		self.buffer[self.current_index] = input;
+		self.current_index += 1;
+		self.current_index %= self.buffer.len();
+	}
+}
+
+/// A feedback delay network: a set of delay lines whose outputs are
+/// mixed together and fed back into each other. This produces a
+/// denser tail than parallel comb filters.
+#[derive(Debug)]
+pub struct FeedbackDelayNetwork {
+	delay_lines: [DelayLine; NUM_DELAY_LINES],
+}
+
+impl FeedbackDelayNetwork {
+	pub fn new(buffer_sizes: [usize; NUM_DELAY_LINES]) -> Self {
+		Self {
+			delay_lines: buffer_sizes.map(DelayLine::new),
+		}
+	}
+
+	pub fn process(&mut self, input: f32, feedback: f32, damp: f32) -> Frame {
+		let mut signals = [0.0; NUM_DELAY_LINES];
+		for (signal, delay_line) in signals.iter_mut().zip(self.delay_lines.iter_mut()) {
+			let output = delay_line.output();
+			delay_line.filter_store = output * (1.0 - damp) + delay_line.filter_store * damp;
+			*signal = delay_line.filter_store;
+		}
+		let output = Frame::new(
+			signals.iter().step_by(2).sum(),
+			signals.iter().skip(1).step_by(2).sum(),
+		);
+		hadamard(&mut signals);
+		for (signal, delay_line) in signals.iter().zip(self.delay_lines.iter_mut()) {
+			delay_line.write(input + signal * feedback);
+		}
+		output
+	}
+}
+
+/// Mixes the signals with a normalized Hadamard matrix, which
+/// keeps the total energy of the signals the same.
+fn hadamard(signals: &mut [f32; NUM_DELAY_LINES]) {
+	let mut size = 1;
+	while size < NUM_DELAY_LINES {
+		for start in (0..NUM_DELAY_LINES).step_by(size * 2) {
+			for i in start..start + size {
+				let a = signals[i];
+				let b = signals[i + size];
+				signals[i] = a + b;
+				signals[i + size] = a - b;
+			}
+		}
+		size *= 2;
+	}
+	let scale = 1.0 / (NUM_DELAY_LINES as f32).sqrt();
+	for signal in signals {
+		*signal *= scale;
+	}
+}
@@ -0,0 +1,234 @@
+//! Adjusts the stereo image of a sound.
+
+use std::f64::consts::{PI, SQRT_2};
+
+use kira::{
+	manager::resources::Parameters,
+	track::Effect,
+	value::{cached::CachedValue, Smoothing, Value},
+	Frame,
+};
+
+use crate::{
+	compressor::decibels_to_amplitude,
+	svf::{Svf, SvfCoefficients},
+	tail::FILTER_TAIL_LENGTH,
+};
+
+/// Settings for a [`StereoImager`].
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(
+	feature = "serde_support",
+	derive(serde::Serialize, serde::Deserialize)
+)]
+#[cfg_attr(feature = "serde_support", serde(default))]
+pub struct StereoImagerSettings {
+	/// How wide the stereo image is.
+	///
+	/// 0.0 is mono, 1.0 leaves the image unchanged, and values
+	/// above 1.0 make the image wider.
+	pub width: Value,
+	/// The volume adjustment to apply to the mid (center)
+	/// signal (in decibels).
+	pub mid_gain: Value,
+	/// The volume adjustment to apply to the side (stereo)
+	/// signal (in decibels).
+	pub side_gain: Value,
+	/// If set, frequencies below this frequency (in hertz)
+	/// will be made mono.
+	///
+	/// This keeps low frequencies from canceling out when the
+	/// output is mixed down to mono.
+	pub bass_mono_frequency: Option<Value>,
+	/// Whether the left and right channels should be swapped.
+	pub swap_channels: bool,
+	/// Whether the phase of the left channel should be inverted.
+	pub invert_left: bool,
+	/// Whether the phase of the right channel should be inverted.
+	pub invert_right: bool,
+	/// How the settings move to new values after they're changed.
+	///
+	/// If `None`, the default smoothing of the audio manager
+	/// will be used.
+	pub smoothing: Option<Smoothing>,
+}
+
+impl StereoImagerSettings {
+	/// Creates a new `StereoImagerSettings` with the default settings.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Sets how wide the stereo image is.
+	///
+	/// 0.0 is mono, 1.0 leaves the image unchanged, and values
+	/// above 1.0 make the image wider.
+	pub fn width(self, width: impl Into<Value>) -> Self {
+		Self {
+			width: width.into(),
+			..self
+		}
+	}
+
+	/// Sets the volume adjustment to apply to the mid (center)
+	/// signal (in decibels).
+	pub fn mid_gain(self, mid_gain: impl Into<Value>) -> Self {
+		Self {
+			mid_gain: mid_gain.into(),
+			..self
+		}
+	}
+
+	/// Sets the volume adjustment to apply to the side (stereo)
+	/// signal (in decibels).
+	pub fn side_gain(self, side_gain: impl Into<Value>) -> Self {
+		Self {
+			side_gain: side_gain.into(),
+			..self
+		}
+	}
+
+	/// Sets the frequency (in hertz) below which the sound
+	/// will be made mono.
+	pub fn bass_mono_frequency(self, bass_mono_frequency: impl Into<Value>) -> Self {
+		Self {
+			bass_mono_frequency: Some(bass_mono_frequency.into()),
+			..self
+		}
+	}
+
+	/// Sets whether the left and right channels should be swapped.
+	pub fn swap_channels(self, swap_channels: bool) -> Self {
+		Self {
+			swap_channels,
+			..self
+		}
+	}
+
+	/// Sets whether the phase of the left channel should be inverted.
+	pub fn invert_left(self, invert_left: bool) -> Self {
+		Self {
+			invert_left,
+			..self
+		}
+	}
+
+	/// Sets whether the phase of the right channel should be inverted.
+	pub fn invert_right(self, invert_right: bool) -> Self {
+		Self {
+			invert_right,
+			..self
+		}
+	}
+
+	/// Sets how the settings move to new values after they're changed.
+	pub fn smoothing(self, smoothing: impl Into<Option<Smoothing>>) -> Self {
+		Self {
+			smoothing: smoothing.into(),
+			..self
+		}
+	}
+}
+
+impl Default for StereoImagerSettings {
+	fn default() -> Self {
+		Self {
+			width: Value::Fixed(1.0),
+			mid_gain: Value::Fixed(0.0),
+			side_gain: Value::Fixed(0.0),
+			bass_mono_frequency: None,
+			swap_channels: false,
+			invert_left: false,
+			invert_right: false,
+			smoothing: None,
+		}
+	}
+}
+
+/// Removes low frequencies from the side signal.
+struct BassMono {
+	frequency: CachedValue,
+	/// Two filter stages give a 24 dB per octave slope, which
+	/// keeps the crossover tight.
+	stages: [Svf; 2],
+}
+
+impl BassMono {
+	fn process(&mut self, side: f32, dt: f64, parameters: &Parameters) -> f32 {
+		self.frequency.update(parameters);
+		let g = (PI * (self.frequency.get() * dt).min(0.49)).tan();
+		let coefficients = SvfCoefficients::high_pass(g, SQRT_2);
+		let mut side = Frame::from_mono(side);
+		for stage in &mut self.stages {
+			side = stage.process(side, coefficients);
+		}
+		side.left
+	}
+}
+
+/// An effect that adjusts the width and balance of the
+/// stereo image of input audio.
+pub struct StereoImager {
+	width: CachedValue,
+	mid_gain: CachedValue,
+	side_gain: CachedValue,
+	bass_mono: Option<BassMono>,
+	swap_channels: bool,
+	invert_left: bool,
+	invert_right: bool,
+}
+
+impl StereoImager {
+	/// Creates a new stereo imager.
+	pub fn new(settings: StereoImagerSettings) -> Self {
+		Self {
+			width: CachedValue::new(0.0.., settings.width, 1.0).with_smoothing(settings.smoothing),
+			mid_gain: CachedValue::new(.., settings.mid_gain, 0.0)
+				.with_smoothing(settings.smoothing),
+			side_gain: CachedValue::new(.., settings.side_gain, 0.0)
+				.with_smoothing(settings.smoothing),
+			bass_mono: settings.bass_mono_frequency.map(|frequency| BassMono {
+				frequency: CachedValue::new(20.0..=20000.0, frequency, 120.0)
+					.with_smoothing(settings.smoothing),
+				stages: [Svf::new(), Svf::new()],
+			}),
+			swap_channels: settings.swap_channels,
+			invert_left: settings.invert_left,
+			invert_right: settings.invert_right,
+		}
+	}
+}
+
+impl Effect for StereoImager {
+	fn tail_length(&self) -> Option<f64> {
+		Some(if self.bass_mono.is_some() {
+			FILTER_TAIL_LENGTH
+		} else {
+			0.0
+		})
+	}
+
+	fn process(&mut self, mut input: Frame, dt: f64, parameters: &Parameters) -> Frame {
+		self.width.update(parameters);
+		self.mid_gain.update(parameters);
+		self.side_gain.update(parameters);
+		if self.swap_channels {
+			input = Frame::new(input.right, input.left);
+		}
+		if self.invert_left {
+			input.left = -input.left;
+		}
+		if self.invert_right {
+			input.right = -input.right;
+		}
+		let mid = (input.left + input.right) / 2.0;
+		let mut side = (input.left - input.right) / 2.0;
+		if let Some(bass_mono) = &mut self.bass_mono {
+			side = bass_mono.process(side, dt, parameters);
+		}
+		let mid = mid * decibels_to_amplitude(self.mid_gain.get()) as f32;
+		let side =
+			side * self.width.get() as f32 * decibels_to_amplitude(self.side_gain.get()) as f32;
+		Frame::new(mid + side, mid - side)
+	}
+}
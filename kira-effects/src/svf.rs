@@ -0,0 +1,143 @@
+//! The state variable filter used by the [`Filter`](crate::filter::Filter)
+//! and [`ParametricEq`](crate::eq::ParametricEq) effects.
+
+use kira::Frame;
+
+// This filter code is based on the filter code from baseplug:
+// https://github.com/wrl/baseplug/blob/trunk/examples/svf/svf_simper.rs
+//
+// The coefficients for each filter shape are from "Linear Trap Optimised
+// State Variable Filter" by Andrew Simper:
+// https://cytomic.com/files/dsp/SvfLinearTrapOptimised2.pdf
+
+/// The coefficients of a state variable filter.
+///
+/// `g` is the warped cutoff frequency, `k` is the damping factor
+/// (the inverse of the Q), and `a` is the gain as an amplitude
+/// factor, square rooted. The output of the filter is
+/// `m0 * input + m1 * band_pass + m2 * low_pass`.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct SvfCoefficients {
+	g: f64,
+	k: f64,
+	m0: f64,
+	m1: f64,
+	m2: f64,
+}
+
+impl SvfCoefficients {
+	pub fn low_pass(g: f64, k: f64) -> Self {
+		Self {
+			g,
+			k,
+			m0: 0.0,
+			m1: 0.0,
+			m2: 1.0,
+		}
+	}
+
+	pub fn band_pass(g: f64, k: f64) -> Self {
+		Self {
+			g,
+			k,
+			m0: 0.0,
+			m1: 1.0,
+			m2: 0.0,
+		}
+	}
+
+	pub fn high_pass(g: f64, k: f64) -> Self {
+		Self {
+			g,
+			k,
+			m0: 1.0,
+			m1: -k,
+			m2: -1.0,
+		}
+	}
+
+	pub fn notch(g: f64, k: f64) -> Self {
+		Self {
+			g,
+			k,
+			m0: 1.0,
+			m1: -k,
+			m2: 0.0,
+		}
+	}
+
+	pub fn all_pass(g: f64, k: f64) -> Self {
+		Self {
+			g,
+			k,
+			m0: 1.0,
+			m1: -2.0 * k,
+			m2: 0.0,
+		}
+	}
+
+	pub fn peak(g: f64, k: f64, a: f64) -> Self {
+		let k = k / a;
+		Self {
+			g,
+			k,
+			m0: 1.0,
+			m1: k * (a * a - 1.0),
+			m2: 0.0,
+		}
+	}
+
+	pub fn low_shelf(g: f64, k: f64, a: f64) -> Self {
+		Self {
+			g: g / a.sqrt(),
+			k,
+			m0: 1.0,
+			m1: k * (a - 1.0),
+			m2: a * a - 1.0,
+		}
+	}
+
+	pub fn high_shelf(g: f64, k: f64, a: f64) -> Self {
+		Self {
+			g: g * a.sqrt(),
+			k,
+			m0: a * a,
+			m1: k * (1.0 - a) * a,
+			m2: 1.0 - a * a,
+		}
+	}
+}
+
+pub(crate) struct Svf {
+	ic1eq: Frame,
+	ic2eq: Frame,
+}
+
+impl Svf {
+	pub fn new() -> Self {
+		Self {
+			ic1eq: Frame::ZERO,
+			ic2eq: Frame::ZERO,
+		}
+	}
+
+	/// Filters a frame of audio.
+	pub fn process(&mut self, input: Frame, coefficients: SvfCoefficients) -> Frame {
+		let SvfCoefficients { g, k, m0, m1, m2 } = coefficients;
+		let a1 = 1.0 / (1.0 + (g * (g + k)));
+		let a2 = g * a1;
+		let a3 = g * a2;
+		let v3 = input - self.ic2eq;
+		let v1 = (self.ic1eq * (a1 as f32)) + (v3 * (a2 as f32));
+		let v2 = self.ic2eq + (self.ic1eq * (a2 as f32)) + (v3 * (a3 as f32));
+		self.ic1eq = (v1 * 2.0) - self.ic1eq;
+		self.ic2eq = (v2 * 2.0) - self.ic2eq;
+		input * m0 as f32 + v1 * m1 as f32 + v2 * m2 as f32
+	}
+}
+
+/// Converts a gain in decibels to the `a` value used by
+/// [`SvfCoefficients`].
+pub(crate) fn gain_to_a(gain: f64) -> f64 {
+	10.0f64.powf(gain / 40.0)
+}
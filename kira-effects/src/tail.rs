@@ -0,0 +1,43 @@
+//! Helpers for calculating how long effects ring out
+//! after their input stops.
+
+/// The amplitude below which an effect's output is
+/// considered silent.
+const SILENCE: f64 = 1.0e-6;
+
+/// A conservative tail length (in seconds) for effects built
+/// on filters, which ring for a short time after their input
+/// stops, especially at high resonances and low frequencies.
+pub(crate) const FILTER_TAIL_LENGTH: f64 = 1.0;
+
+/// Returns how long (in seconds) it takes a one-pole smoother
+/// with the given time constant (in seconds) to fall from full
+/// volume to silence.
+///
+/// Dynamics processors use this as their tail length even though
+/// silent input always produces silent output. Their gain still
+/// has to finish recovering after the input stops, so the next
+/// sound isn't turned down (or, for a gate, let through) by
+/// a gain left over from the previous one.
+pub(crate) fn one_pole_tail_length(time: f64) -> f64 {
+	-time * SILENCE.ln()
+}
+
+/// Returns how long (in seconds) it takes audio circulating
+/// through a feedback loop to decay to silence, or `None`
+/// if the audio never decays.
+///
+/// `loop_time` is how long (in seconds) it takes audio to go
+/// around the loop once.
+pub(crate) fn feedback_tail_length(loop_time: f64, feedback: f64) -> Option<f64> {
+	let feedback = feedback.abs();
+	if feedback >= 1.0 {
+		return None;
+	}
+	let num_repeats = if feedback > 0.0 {
+		(SILENCE.ln() / feedback.ln()).ceil()
+	} else {
+		0.0
+	};
+	Some(loop_time * (num_repeats + 1.0))
+}
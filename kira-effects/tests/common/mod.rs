@@ -0,0 +1,167 @@
+//! Helpers for running effects through a mixer track.
+
+// each test file only uses some of these helpers
+#![allow(dead_code)]
+
+use std::error::Error;
+
+use kira::{
+	manager::{AudioManager, AudioManagerSettings, MockBackend},
+	sound::{instance::InstanceSettings, static_sound::StaticSound},
+	track::{Effect, TrackId, TrackRoutes, TrackSettings},
+	Frame,
+};
+
+pub fn assert_approximate_eq(a: f32, b: f32) {
+	const ERROR_THRESHOLD: f32 = 1.0e-4;
+	if (a - b).abs() > ERROR_THRESHOLD {
+		assert_eq!(a, b);
+	}
+}
+
+pub fn assert_frame_approximate_eq(a: Frame, b: Frame) {
+	assert_approximate_eq(a.left, b.left);
+	assert_approximate_eq(a.right, b.right);
+}
+
+pub fn assert_decibels_approximate_eq(a: f64, b: f64) {
+	const ERROR_THRESHOLD: f64 = 0.1;
+	if (a - b).abs() > ERROR_THRESHOLD {
+		assert_eq!(a, b);
+	}
+}
+
+/// Converts a volume in decibels to an amplitude.
+pub fn decibels_to_amplitude(decibels: f64) -> f32 {
+	10.0f64.powf(decibels / 20.0) as f32
+}
+
+/// Plays a constant signal at the given level (in decibels)
+/// through the effect and returns the level of the output
+/// (in decibels) once it's settled.
+pub fn settled_level(effect: impl Effect + 'static, level: f64) -> Result<f64, Box<dyn Error>> {
+	let input = vec![Frame::from_mono(decibels_to_amplitude(level)); 1000];
+	let output = process(1000, effect, input)?;
+	Ok(20.0 * (output[999].left as f64).log10())
+}
+
+/// Returns a single full volume frame followed by silence.
+pub fn impulse(num_frames: usize) -> Vec<Frame> {
+	let mut frames = vec![Frame::ZERO; num_frames];
+	frames[0] = Frame::from_mono(1.0);
+	frames
+}
+
+/// Returns a sine wave at full volume.
+pub fn sine(frequency: f64, sample_rate: u32, num_frames: usize) -> Vec<Frame> {
+	(0..num_frames)
+		.map(|i| {
+			let phase = std::f64::consts::TAU * frequency * i as f64 / sample_rate as f64;
+			Frame::from_mono(phase.sin() as f32)
+		})
+		.collect()
+}
+
+/// Returns the highest amplitude of either channel.
+pub fn peak(frames: &[Frame]) -> f32 {
+	frames
+		.iter()
+		.map(|frame| frame.left.abs().max(frame.right.abs()))
+		.fold(0.0, f32::max)
+}
+
+/// Returns the gain (in decibels) the effect applies to a sine wave
+/// at the given frequency once it's settled.
+pub fn gain_at(
+	sample_rate: u32,
+	effect: impl Effect + 'static,
+	frequency: f64,
+) -> Result<f64, Box<dyn Error>> {
+	let num_frames = sample_rate as usize / 5;
+	let output = process(
+		sample_rate,
+		effect,
+		sine(frequency, sample_rate, num_frames),
+	)?;
+	// measure the second half so the effect has time to settle
+	let settled = &output[num_frames / 2..];
+	let mean_square = settled
+		.iter()
+		.map(|frame| (frame.left as f64).powi(2))
+		.sum::<f64>()
+		/ settled.len() as f64;
+	// a full volume sine wave has a mean square of 1/2
+	Ok(10.0 * (mean_square * 2.0).log10())
+}
+
+/// Creates an audio manager that outputs the main mixer
+/// track without limiting it.
+pub fn create_manager(sample_rate: u32) -> AudioManager<MockBackend> {
+	AudioManager::new(
+		AudioManagerSettings {
+			main_track_limiter: None,
+			..Default::default()
+		},
+		MockBackend::new(sample_rate),
+	)
+	.unwrap()
+}
+
+/// Plays the input through a mixer track with the given effect
+/// and returns one frame of output for each frame of input.
+pub fn process(
+	sample_rate: u32,
+	effect: impl Effect + 'static,
+	input: Vec<Frame>,
+) -> Result<Vec<Frame>, Box<dyn Error>> {
+	let mut manager = create_manager(sample_rate);
+	let track = manager.add_sub_track(TrackSettings::new().with_effect(effect))?;
+	play(&mut manager, sample_rate, &track, input)
+}
+
+/// Plays the input through a mixer track with the given effect
+/// while playing the sidechain input on a silent track that the
+/// effect is keyed from. Returns one frame of output for each
+/// frame of input.
+pub fn process_with_sidechain<E: Effect + 'static>(
+	sample_rate: u32,
+	effect: impl FnOnce(TrackId) -> E,
+	input: Vec<Frame>,
+	sidechain_input: Vec<Frame>,
+) -> Result<Vec<Frame>, Box<dyn Error>> {
+	let mut manager = create_manager(sample_rate);
+	let sidechain_track = manager.add_sub_track(
+		TrackSettings::new().routes(TrackRoutes::new().with_route(TrackId::Main, 0.0)),
+	)?;
+	let track =
+		manager.add_sub_track(TrackSettings::new().with_effect(effect(sidechain_track.id())))?;
+	let mut sidechain_sound = manager.add_sound(StaticSound::from_frames(
+		sample_rate,
+		sidechain_input,
+		Default::default(),
+	))?;
+	let _sidechain_instance =
+		sidechain_sound.play(InstanceSettings::new().track(&sidechain_track))?;
+	play(&mut manager, sample_rate, &track, input)
+}
+
+/// Plays the input on the given track and returns one frame
+/// of the audio manager's output for each frame of input.
+pub fn play(
+	manager: &mut AudioManager<MockBackend>,
+	sample_rate: u32,
+	track: impl Into<TrackId>,
+	input: Vec<Frame>,
+) -> Result<Vec<Frame>, Box<dyn Error>> {
+	let num_frames = input.len();
+	let mut sound = manager.add_sound(StaticSound::from_frames(
+		sample_rate,
+		input,
+		Default::default(),
+	))?;
+	let _instance = sound.play(InstanceSettings::new().track(track))?;
+	manager.backend_mut().on_start_processing(0.0);
+	Ok((0..num_frames)
+		.map(|_| manager.backend_mut().process())
+		.collect())
+}
@@ -0,0 +1,60 @@
+mod common;
+
+use std::error::Error;
+
+use common::{assert_approximate_eq, decibels_to_amplitude, process_with_sidechain, settled_level};
+use kira::Frame;
+use kira_effects::compressor::{Compressor, CompressorSettings};
+
+fn compressor() -> Compressor {
+	Compressor::new(
+		CompressorSettings::new()
+			.threshold(-20.0)
+			.ratio(4.0)
+			.knee(10.0),
+	)
+}
+
+#[test]
+fn leaves_signals_below_the_knee_alone() -> Result<(), Box<dyn Error>> {
+	assert_approximate_eq(settled_level(compressor(), -30.0)? as f32, -30.0);
+	Ok(())
+}
+
+#[test]
+fn gradually_compresses_signals_inside_the_knee() -> Result<(), Box<dyn Error>> {
+	// at the threshold, the signal is halfway through the knee,
+	// so it's turned down by (1 / 4 - 1) * 5^2 / (2 * 10) decibels
+	assert_approximate_eq(settled_level(compressor(), -20.0)? as f32, -20.9375);
+	Ok(())
+}
+
+#[test]
+fn compresses_signals_above_the_knee_by_the_ratio() -> Result<(), Box<dyn Error>> {
+	// 10 decibels over the threshold should come out
+	// 2.5 decibels over the threshold
+	assert_approximate_eq(settled_level(compressor(), -10.0)? as f32, -17.5);
+	Ok(())
+}
+
+#[test]
+fn compresses_based_on_the_sidechain_input() -> Result<(), Box<dyn Error>> {
+	let output = process_with_sidechain(
+		1000,
+		|sidechain| {
+			Compressor::new(
+				CompressorSettings::new()
+					.threshold(-20.0)
+					.ratio(4.0)
+					.knee(0.0)
+					.sidechain(sidechain),
+			)
+		},
+		vec![Frame::from_mono(decibels_to_amplitude(-30.0)); 1000],
+		vec![Frame::from_mono(decibels_to_amplitude(-10.0)); 1000],
+	)?;
+	// the quiet input is turned down by as much as the loud
+	// sidechain input would be
+	assert_approximate_eq(output[999].left, decibels_to_amplitude(-37.5));
+	Ok(())
+}
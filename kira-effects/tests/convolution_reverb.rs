@@ -0,0 +1,77 @@
+mod common;
+
+use std::error::Error;
+
+use common::{assert_approximate_eq, assert_frame_approximate_eq, impulse, process};
+use kira::{sound::static_sound::StaticSound, Frame};
+use kira_effects::convolution_reverb::{ConvolutionReverb, ConvolutionReverbSettings};
+
+/// The number of frames the convolution reverb delays
+/// the wet signal by.
+const BLOCK_SIZE: usize = 256;
+
+fn impulse_response(sample_rate: u32, spikes: &[(usize, f32)], num_frames: usize) -> StaticSound {
+	let mut frames = vec![Frame::ZERO; num_frames];
+	for (index, amplitude) in spikes {
+		frames[*index] = Frame::from_mono(*amplitude);
+	}
+	StaticSound::from_frames(sample_rate, frames, Default::default())
+}
+
+#[test]
+fn impulse_is_delayed_by_one_block() -> Result<(), Box<dyn Error>> {
+	let reverb = ConvolutionReverb::new(
+		impulse_response(1000, &[(0, 1.0), (3, 0.5)], 8),
+		ConvolutionReverbSettings::new().mix(1.0),
+	);
+	let output = process(1000, reverb, impulse(BLOCK_SIZE * 2))?;
+	for (i, frame) in output.into_iter().enumerate() {
+		let expected = match i {
+			i if i == BLOCK_SIZE => 1.0,
+			i if i == BLOCK_SIZE + 3 => 0.5,
+			_ => 0.0,
+		};
+		assert_frame_approximate_eq(frame, Frame::from_mono(expected));
+	}
+	Ok(())
+}
+
+#[test]
+fn impulse_responses_can_span_multiple_blocks() -> Result<(), Box<dyn Error>> {
+	let reverb = ConvolutionReverb::new(
+		impulse_response(1000, &[(0, 1.0), (300, 0.5), (599, 0.25)], 600),
+		ConvolutionReverbSettings::new().mix(1.0),
+	);
+	let output = process(1000, reverb, impulse(BLOCK_SIZE + 700))?;
+	for (i, frame) in output.into_iter().enumerate() {
+		let expected = match i {
+			i if i == BLOCK_SIZE => 1.0,
+			i if i == BLOCK_SIZE + 300 => 0.5,
+			i if i == BLOCK_SIZE + 599 => 0.25,
+			_ => 0.0,
+		};
+		assert_frame_approximate_eq(frame, Frame::from_mono(expected));
+	}
+	Ok(())
+}
+
+#[test]
+fn resamples_impulse_responses() -> Result<(), Box<dyn Error>> {
+	// a 100 millisecond impulse response recorded at half
+	// the output sample rate
+	let reverb = ConvolutionReverb::new(
+		StaticSound::from_frames(500, vec![Frame::from_mono(1.0); 50], Default::default()),
+		ConvolutionReverbSettings::new().mix(1.0),
+	);
+	let output = process(1000, reverb, impulse(BLOCK_SIZE + 150))?;
+	// the impulse response should still last 100 milliseconds,
+	// and each frame should be half as loud since there's twice
+	// as many of them
+	for i in [BLOCK_SIZE + 10, BLOCK_SIZE + 50, BLOCK_SIZE + 90] {
+		assert_approximate_eq(output[i].left, 0.5);
+	}
+	for i in [BLOCK_SIZE - 1, BLOCK_SIZE + 110, BLOCK_SIZE + 149] {
+		assert_approximate_eq(output[i].left, 0.0);
+	}
+	Ok(())
+}
@@ -0,0 +1,59 @@
+mod common;
+
+use std::error::Error;
+
+use common::{assert_frame_approximate_eq, impulse, process};
+use kira::Frame;
+use kira_effects::delay::{Delay, DelaySettings};
+
+#[test]
+fn echoes_each_channel_after_its_delay_time() -> Result<(), Box<dyn Error>> {
+	let delay = Delay::new(
+		DelaySettings::new()
+			.left_delay_time(0.01)
+			.right_delay_time(0.02)
+			.feedback(0.5)
+			.buffer_length(1.0),
+	);
+	let output = process(1000, delay, impulse(50))?;
+	for (i, frame) in output.into_iter().enumerate() {
+		// each echo repeats on the same channel at half the volume
+		let left = match i {
+			10 => 1.0,
+			20 => 0.5,
+			30 => 0.25,
+			40 => 0.125,
+			_ => 0.0,
+		};
+		let right = match i {
+			20 => 1.0,
+			40 => 0.5,
+			_ => 0.0,
+		};
+		assert_frame_approximate_eq(frame, Frame::new(left, right));
+	}
+	Ok(())
+}
+
+#[test]
+fn ping_pong_echoes_alternate_between_channels() -> Result<(), Box<dyn Error>> {
+	let delay = Delay::new(
+		DelaySettings::new()
+			.delay_time(0.01)
+			.feedback(0.5)
+			.ping_pong(true)
+			.buffer_length(1.0),
+	);
+	let output = process(1000, delay, impulse(50))?;
+	for (i, frame) in output.into_iter().enumerate() {
+		let expected = match i {
+			10 => Frame::new(1.0, 0.0),
+			20 => Frame::new(0.0, 0.5),
+			30 => Frame::new(0.25, 0.0),
+			40 => Frame::new(0.0, 0.125),
+			_ => Frame::ZERO,
+		};
+		assert_frame_approximate_eq(frame, expected);
+	}
+	Ok(())
+}
@@ -0,0 +1,107 @@
+mod common;
+
+use std::error::Error;
+
+use common::{assert_approximate_eq, impulse, process};
+use kira::{track::Effect, Frame};
+use kira_effects::distortion::{Distortion, DistortionKind, DistortionSettings, Oversampling};
+
+/// Distorts the input with the given settings and returns
+/// the left channel of the output.
+fn distort(settings: DistortionSettings, input: &[f32]) -> Result<Vec<f32>, Box<dyn Error>> {
+	let input = input.iter().copied().map(Frame::from_mono).collect();
+	Ok(process(1000, Distortion::new(settings), input)?
+		.into_iter()
+		.map(|frame| frame.left)
+		.collect())
+}
+
+#[test]
+fn oversampled_impulses_peak_after_the_reported_latency() -> Result<(), Box<dyn Error>> {
+	for oversampling in [Oversampling::X2, Oversampling::X4] {
+		let distortion = Distortion::new(
+			DistortionSettings::new()
+				.kind(DistortionKind::HardClip)
+				.oversampling(oversampling),
+		);
+		let latency = distortion.latency();
+		assert!(latency > 0);
+		let input = impulse(latency * 2 + 1)
+			.into_iter()
+			.map(|frame| frame * 0.5)
+			.collect();
+		let output = process(1000, distortion, input)?;
+		let peak_index = (0..output.len())
+			.max_by(|a, b| output[*a].left.abs().total_cmp(&output[*b].left.abs()))
+			.unwrap();
+		assert_eq!(peak_index, latency);
+	}
+	Ok(())
+}
+
+#[test]
+fn tube_saturates_positive_values_more() -> Result<(), Box<dyn Error>> {
+	let tube = |x: f32| (x + 0.25).tanh() - 0.25f32.tanh();
+	// the DC blocker lets the first frame through unchanged
+	for x in [0.5, -0.5] {
+		let output = distort(DistortionSettings::new().kind(DistortionKind::Tube), &[x])?;
+		assert_approximate_eq(output[0], tube(x));
+	}
+	assert!(tube(0.5) < -tube(-0.5));
+	Ok(())
+}
+
+#[test]
+fn foldback_folds_values_back_into_range() -> Result<(), Box<dyn Error>> {
+	let output = distort(
+		DistortionSettings::new().kind(DistortionKind::Foldback),
+		&[0.5, 1.5, -1.5, 2.5],
+	)?;
+	for (output, expected) in output.into_iter().zip([0.5, 0.5, -0.5, -0.5]) {
+		assert_approximate_eq(output, expected);
+	}
+	Ok(())
+}
+
+#[test]
+fn bit_crush_rounds_to_the_bit_depth() -> Result<(), Box<dyn Error>> {
+	// 2 bits leaves steps of 0.5
+	let output = distort(
+		DistortionSettings::new()
+			.kind(DistortionKind::BitCrush)
+			.bit_depth(2.0),
+		&[0.3, 0.2, -0.8, 1.0],
+	)?;
+	for (output, expected) in output.into_iter().zip([0.5, 0.0, -1.0, 1.0]) {
+		assert_approximate_eq(output, expected);
+	}
+	Ok(())
+}
+
+#[test]
+fn sample_rate_reduction_holds_frames() -> Result<(), Box<dyn Error>> {
+	// a quarter of the sample rate holds every fourth frame
+	let input = [0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8, 0.9];
+	let output = distort(
+		DistortionSettings::new()
+			.kind(DistortionKind::SampleRateReduction)
+			.reduced_sample_rate(250.0),
+		&input,
+	)?;
+	for (output, expected) in output
+		.into_iter()
+		.zip([0.1, 0.1, 0.1, 0.4, 0.4, 0.4, 0.4, 0.8, 0.8])
+	{
+		assert_approximate_eq(output, expected);
+	}
+	Ok(())
+}
+
+#[test]
+fn zero_drive_does_not_produce_nan() -> Result<(), Box<dyn Error>> {
+	let output = distort(DistortionSettings::new().drive(0.0), &[0.5, -0.5])?;
+	for output in output {
+		assert!(output.is_finite());
+	}
+	Ok(())
+}
@@ -0,0 +1,45 @@
+mod common;
+
+use std::error::Error;
+
+use common::{assert_approximate_eq, create_manager, play};
+use kira::{
+	sound::{instance::InstanceSettings, static_sound::StaticSound},
+	track::{TrackId, TrackRoutes, TrackSettings},
+	Frame,
+};
+use kira_effects::envelope_follower::{EnvelopeFollower, EnvelopeFollowerSettings};
+
+#[test]
+fn publishes_into_linked_values() -> Result<(), Box<dyn Error>> {
+	let mut manager = create_manager(1000);
+	let effect_output = manager.add_effect_output()?;
+	// a silent track that measures the loudness of its input
+	let follower_track = manager.add_sub_track(
+		TrackSettings::new()
+			.routes(TrackRoutes::new().with_route(TrackId::Main, 0.0))
+			.with_effect(EnvelopeFollower::new(
+				&effect_output,
+				EnvelopeFollowerSettings::new().attack(0.0).gain(0.5),
+			)),
+	)?;
+	// a track whose volume follows the envelope
+	let track = manager.add_sub_track(TrackSettings::new().volume(&effect_output))?;
+	let mut sound = manager.add_sound(StaticSound::from_frames(
+		1000,
+		vec![Frame::from_mono(0.8); 1000],
+		Default::default(),
+	))?;
+	sound.play(InstanceSettings::new().track(&follower_track))?;
+	let output = play(
+		&mut manager,
+		1000,
+		&track,
+		vec![Frame::from_mono(1.0); 1000],
+	)?;
+	// effect output handles are updated at the start of each batch
+	manager.backend_mut().on_start_processing(0.0);
+	assert_approximate_eq(effect_output.value() as f32, 0.4);
+	assert_approximate_eq(output[999].left, 0.4);
+	Ok(())
+}
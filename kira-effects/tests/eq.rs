@@ -0,0 +1,58 @@
+mod common;
+
+use std::error::Error;
+
+use common::{assert_decibels_approximate_eq, gain_at};
+use kira_effects::eq::{EqBandKind, ParametricEq, ParametricEqSettings};
+
+const SAMPLE_RATE: u32 = 48000;
+
+fn eq(kind: EqBandKind, frequency: f64, gain: f64) -> ParametricEq {
+	ParametricEq::new(ParametricEqSettings::new().with_band(kind, frequency, gain, 0.707))
+}
+
+#[test]
+fn peaking_bands_apply_their_gain_at_their_frequency() -> Result<(), Box<dyn Error>> {
+	for gain in [6.0, -6.0] {
+		let output_gain = gain_at(SAMPLE_RATE, eq(EqBandKind::Peaking, 1000.0, gain), 1000.0)?;
+		assert_decibels_approximate_eq(output_gain, gain);
+	}
+	// frequencies far from the band are left alone
+	let output_gain = gain_at(SAMPLE_RATE, eq(EqBandKind::Peaking, 1000.0, 6.0), 50.0)?;
+	assert!(output_gain.abs() < 0.5);
+	Ok(())
+}
+
+#[test]
+fn shelf_bands_apply_half_their_gain_at_their_frequency() -> Result<(), Box<dyn Error>> {
+	for (kind, shelf_frequency) in [
+		(EqBandKind::LowShelf, 20.0),
+		(EqBandKind::HighShelf, 15000.0),
+	] {
+		assert_decibels_approximate_eq(gain_at(SAMPLE_RATE, eq(kind, 1000.0, -6.0), 1000.0)?, -3.0);
+		let output_gain = gain_at(SAMPLE_RATE, eq(kind, 1000.0, -6.0), shelf_frequency)?;
+		assert!((output_gain + 6.0).abs() < 0.5);
+	}
+	Ok(())
+}
+
+#[test]
+fn cut_bands_are_3_decibels_down_at_their_frequency() -> Result<(), Box<dyn Error>> {
+	for kind in [EqBandKind::LowCut, EqBandKind::HighCut] {
+		// the gain is ignored
+		let output_gain = gain_at(SAMPLE_RATE, eq(kind, 1000.0, 12.0), 1000.0)?;
+		assert_decibels_approximate_eq(output_gain, -3.01);
+	}
+	Ok(())
+}
+
+#[test]
+fn bands_are_combined() -> Result<(), Box<dyn Error>> {
+	let eq = ParametricEq::new(
+		ParametricEqSettings::new()
+			.with_band(EqBandKind::Peaking, 1000.0, 6.0, 1.0)
+			.with_band(EqBandKind::Peaking, 1000.0, 3.0, 1.0),
+	);
+	assert_decibels_approximate_eq(gain_at(SAMPLE_RATE, eq, 1000.0)?, 9.0);
+	Ok(())
+}
@@ -0,0 +1,90 @@
+mod common;
+
+use std::error::Error;
+
+use common::{assert_decibels_approximate_eq, gain_at};
+use kira_effects::filter::{Filter, FilterMode, FilterSettings, FilterSlope};
+
+const SAMPLE_RATE: u32 = 48000;
+
+fn filter(mode: FilterMode, slope: FilterSlope) -> Filter {
+	Filter::new(FilterSettings::new().mode(mode).cutoff(1000.0).slope(slope))
+}
+
+#[test]
+fn cascaded_filters_are_3_decibels_down_at_the_cutoff() -> Result<(), Box<dyn Error>> {
+	for slope in [FilterSlope::Db24, FilterSlope::Db48] {
+		for mode in [FilterMode::LowPass, FilterMode::HighPass] {
+			let gain = gain_at(SAMPLE_RATE, filter(mode, slope), 1000.0)?;
+			assert_decibels_approximate_eq(gain, -3.01);
+		}
+	}
+	Ok(())
+}
+
+#[test]
+fn slopes_set_how_steeply_frequencies_are_removed() -> Result<(), Box<dyn Error>> {
+	for (slope, decibels_per_octave) in [
+		(FilterSlope::Db12, 12.0),
+		(FilterSlope::Db24, 24.0),
+		(FilterSlope::Db48, 48.0),
+	] {
+		// above the cutoff, each octave should be turned down by the
+		// slope. the single stage filter has a softer knee, so it
+		// doesn't reach its full slope this close to the cutoff.
+		let low_pass = || {
+			Filter::new(
+				FilterSettings::new()
+					.mode(FilterMode::LowPass)
+					.cutoff(250.0)
+					.slope(slope),
+			)
+		};
+		let difference =
+			gain_at(SAMPLE_RATE, low_pass(), 500.0)? - gain_at(SAMPLE_RATE, low_pass(), 1000.0)?;
+		assert!(
+			(difference - decibels_per_octave).abs() < decibels_per_octave * 0.15,
+			"expected about {} dB per octave, got {} dB",
+			decibels_per_octave,
+			difference
+		);
+	}
+	Ok(())
+}
+
+#[test]
+fn peak_filters_apply_their_gain_at_the_cutoff() -> Result<(), Box<dyn Error>> {
+	for slope in [FilterSlope::Db12, FilterSlope::Db24, FilterSlope::Db48] {
+		for gain in [12.0, -12.0] {
+			let filter = Filter::new(
+				FilterSettings::new()
+					.mode(FilterMode::Peak)
+					.cutoff(1000.0)
+					.gain(gain)
+					.slope(slope),
+			);
+			assert_decibels_approximate_eq(gain_at(SAMPLE_RATE, filter, 1000.0)?, gain);
+		}
+	}
+	Ok(())
+}
+
+#[test]
+fn shelf_filters_apply_half_their_gain_at_the_cutoff() -> Result<(), Box<dyn Error>> {
+	for mode in [FilterMode::LowShelf, FilterMode::HighShelf] {
+		let shelf = || Filter::new(FilterSettings::new().mode(mode).cutoff(1000.0).gain(12.0));
+		assert_decibels_approximate_eq(gain_at(SAMPLE_RATE, shelf(), 1000.0)?, 6.0);
+		// the full gain is applied to frequencies on the shelf
+		let shelf_frequency = match mode {
+			FilterMode::LowShelf => 50.0,
+			_ => 15000.0,
+		};
+		let gain = gain_at(SAMPLE_RATE, shelf(), shelf_frequency)?;
+		assert!(
+			(gain - 12.0).abs() < 0.5,
+			"expected about 12 dB, got {} dB",
+			gain
+		);
+	}
+	Ok(())
+}
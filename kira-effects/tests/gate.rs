@@ -0,0 +1,73 @@
+mod common;
+
+use std::error::Error;
+
+use common::{assert_approximate_eq, decibels_to_amplitude, process, settled_level};
+use kira::Frame;
+use kira_effects::gate::{Gate, GateSettings};
+
+/// Creates a gate that opens and closes instantly.
+fn gate(settings: GateSettings) -> Gate {
+	Gate::new(settings.attack(0.0).release(0.0))
+}
+
+#[test]
+fn leaves_signals_above_the_threshold_alone() -> Result<(), Box<dyn Error>> {
+	let output = settled_level(gate(GateSettings::new().threshold(-40.0)), -30.0)?;
+	assert_approximate_eq(output as f32, -30.0);
+	Ok(())
+}
+
+#[test]
+fn expands_signals_below_the_threshold_by_the_ratio() -> Result<(), Box<dyn Error>> {
+	// 10 decibels under the threshold should come out
+	// 20 decibels under the threshold
+	let output = settled_level(
+		gate(GateSettings::new().threshold(-40.0).ratio(2.0).range(-80.0)),
+		-50.0,
+	)?;
+	assert_approximate_eq(output as f32, -60.0);
+	Ok(())
+}
+
+#[test]
+fn turns_signals_down_by_no_more_than_the_range() -> Result<(), Box<dyn Error>> {
+	let output = settled_level(
+		gate(
+			GateSettings::new()
+				.threshold(-40.0)
+				.ratio(100.0)
+				.range(-20.0),
+		),
+		-50.0,
+	)?;
+	assert_approximate_eq(output as f32, -70.0);
+	Ok(())
+}
+
+#[test]
+fn stays_open_for_the_hold_time() -> Result<(), Box<dyn Error>> {
+	// 10 frames of a loud signal followed by a quiet signal
+	let mut input = vec![Frame::from_mono(1.0); 10];
+	input.extend(vec![Frame::from_mono(decibels_to_amplitude(-50.0)); 40]);
+	let gate = Gate::new(
+		GateSettings::new()
+			.threshold(-40.0)
+			.range(-20.0)
+			.attack(0.0)
+			.hold(0.0205)
+			.release(0.0),
+	);
+	let output = process(1000, gate, input.clone())?;
+	// the gate stays open for 20 frames after the signal
+	// falls below the threshold, then closes right away
+	for (i, (output, input)) in output.iter().zip(input.iter()).enumerate() {
+		let expected = if i < 30 {
+			input.left
+		} else {
+			input.left * decibels_to_amplitude(-20.0)
+		};
+		assert_approximate_eq(output.left, expected);
+	}
+	Ok(())
+}
@@ -0,0 +1,41 @@
+mod common;
+
+use std::error::Error;
+
+use common::{assert_approximate_eq, decibels_to_amplitude, process, process_with_sidechain};
+use kira::Frame;
+use kira_effects::limiter::{Limiter, LimiterSettings};
+
+#[test]
+fn turns_down_signals_above_the_threshold() -> Result<(), Box<dyn Error>> {
+	let limiter = Limiter::new(LimiterSettings::new().threshold(-6.0));
+	let output = process(1000, limiter, vec![Frame::from_mono(1.0); 100])?;
+	for frame in output {
+		assert_approximate_eq(frame.left, decibels_to_amplitude(-6.0));
+	}
+	Ok(())
+}
+
+#[test]
+fn leaves_signals_below_the_threshold_alone() -> Result<(), Box<dyn Error>> {
+	let limiter = Limiter::new(LimiterSettings::new().threshold(-6.0));
+	let output = process(1000, limiter, vec![Frame::from_mono(0.25); 100])?;
+	for frame in output {
+		assert_approximate_eq(frame.left, 0.25);
+	}
+	Ok(())
+}
+
+#[test]
+fn limits_based_on_the_sidechain_input() -> Result<(), Box<dyn Error>> {
+	let output = process_with_sidechain(
+		1000,
+		|sidechain| Limiter::new(LimiterSettings::new().threshold(-6.0).sidechain(sidechain)),
+		vec![Frame::from_mono(0.25); 100],
+		vec![Frame::from_mono(1.0); 100],
+	)?;
+	// the sidechain input is twice as loud as the threshold allows,
+	// so the input is turned down by the same amount
+	assert_approximate_eq(output[99].left, 0.25 * decibels_to_amplitude(-6.0));
+	Ok(())
+}
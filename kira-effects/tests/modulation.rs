@@ -0,0 +1,37 @@
+mod common;
+
+use std::error::Error;
+
+use common::{assert_frame_approximate_eq, process, sine};
+use kira::track::Effect;
+use kira_effects::{
+	chorus::{Chorus, ChorusSettings},
+	flanger::{Flanger, FlangerSettings},
+	phaser::{Phaser, PhaserSettings},
+};
+
+const SAMPLE_RATE: u32 = 44100;
+
+fn assert_passes_input_through(effect: impl Effect + 'static) -> Result<(), Box<dyn Error>> {
+	let input = sine(440.0, SAMPLE_RATE, 1000);
+	let output = process(SAMPLE_RATE, effect, input.clone())?;
+	for (output, input) in output.into_iter().zip(input) {
+		assert_frame_approximate_eq(output, input);
+	}
+	Ok(())
+}
+
+#[test]
+fn chorus_passes_input_through_when_fully_dry() -> Result<(), Box<dyn Error>> {
+	assert_passes_input_through(Chorus::new(ChorusSettings::new().feedback(0.5).mix(0.0)))
+}
+
+#[test]
+fn flanger_passes_input_through_when_fully_dry() -> Result<(), Box<dyn Error>> {
+	assert_passes_input_through(Flanger::new(FlangerSettings::new().feedback(0.5).mix(0.0)))
+}
+
+#[test]
+fn phaser_passes_input_through_when_fully_dry() -> Result<(), Box<dyn Error>> {
+	assert_passes_input_through(Phaser::new(PhaserSettings::new().feedback(0.5).mix(0.0)))
+}
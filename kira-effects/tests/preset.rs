@@ -0,0 +1,71 @@
+#![cfg(feature = "serde_support")]
+
+use std::error::Error;
+
+use kira::{
+	manager::{AudioManager, MockBackend},
+	value::Value,
+};
+use kira_effects::{
+	compressor::CompressorSettings,
+	filter::{FilterMode, FilterSettings},
+	preset::{EffectSettings, TrackPreset},
+	reverb::ReverbSettings,
+};
+
+#[test]
+fn round_trips_track_presets() -> Result<(), Box<dyn Error>> {
+	let preset = TrackPreset::new()
+		.volume(0.5)
+		.panning(0.25)
+		.muted(true)
+		.solo_safe(true)
+		.with_effect(
+			FilterSettings::new()
+				.mode(FilterMode::HighPass)
+				.cutoff(500.0),
+		)
+		.with_effect(CompressorSettings::new().threshold(-20.0).ratio(4.0))
+		.with_effect(ReverbSettings::new().mix(0.3));
+	let serialized = serde_json::to_string(&preset)?;
+	let deserialized: TrackPreset = serde_json::from_str(&serialized)?;
+	assert_eq!(deserialized.volume, Value::Fixed(0.5));
+	assert_eq!(deserialized.panning, Value::Fixed(0.25));
+	assert!(deserialized.muted);
+	assert!(deserialized.solo_safe);
+	assert_eq!(deserialized.effects.len(), 3);
+	match &deserialized.effects[0] {
+		EffectSettings::Filter(settings) => {
+			assert!(matches!(settings.mode, FilterMode::HighPass));
+			assert_eq!(settings.cutoff, Value::Fixed(500.0));
+		}
+		effect => panic!("expected a filter, got {:?}", effect),
+	}
+	match &deserialized.effects[1] {
+		EffectSettings::Compressor(settings) => {
+			assert_eq!(settings.threshold, Value::Fixed(-20.0));
+			assert_eq!(settings.ratio, Value::Fixed(4.0));
+		}
+		effect => panic!("expected a compressor, got {:?}", effect),
+	}
+	match &deserialized.effects[2] {
+		EffectSettings::Reverb(settings) => assert_eq!(settings.mix, Value::Fixed(0.3)),
+		effect => panic!("expected a reverb, got {:?}", effect),
+	}
+	// nothing should be lost the second time around either
+	assert_eq!(serde_json::to_string(&deserialized)?, serialized);
+	let settings = deserialized.into_track_settings();
+	assert!(settings.muted);
+	assert!(settings.solo_safe);
+	assert_eq!(settings.effects.len(), 3);
+	Ok(())
+}
+
+#[test]
+fn linked_values_cannot_be_serialized() -> Result<(), Box<dyn Error>> {
+	let mut manager = AudioManager::new(Default::default(), MockBackend::new(1)).unwrap();
+	let parameter = manager.add_parameter(1000.0)?;
+	let preset = TrackPreset::new().with_effect(FilterSettings::new().cutoff(&parameter));
+	assert!(serde_json::to_string(&preset).is_err());
+	Ok(())
+}
@@ -0,0 +1,79 @@
+mod common;
+
+use std::error::Error;
+
+use common::{assert_frame_approximate_eq, create_manager, peak, process, sine};
+use kira::{
+	parameter::Tween,
+	sound::{instance::InstanceSettings, static_sound::StaticSound},
+	track::{Effect, TrackSettings},
+};
+use kira_effects::reverb::{Reverb, ReverbKind, ReverbSettings};
+
+const SAMPLE_RATE: u32 = 44100;
+
+#[test]
+fn passes_input_through_when_fully_dry() -> Result<(), Box<dyn Error>> {
+	for kind in [ReverbKind::Freeverb, ReverbKind::FeedbackDelayNetwork] {
+		let reverb = Reverb::new(ReverbSettings::new().kind(kind).mix(0.0));
+		let input = sine(440.0, SAMPLE_RATE, 1000);
+		let output = process(SAMPLE_RATE, reverb, input.clone())?;
+		for (output, input) in output.into_iter().zip(input) {
+			assert_frame_approximate_eq(output, input);
+		}
+	}
+	Ok(())
+}
+
+/// Plays a short sound through a reverb, optionally freezing the
+/// reverb once the sound is over, and returns the peak volume of
+/// the output a second later.
+fn peak_after_a_second(kind: ReverbKind, freeze: bool) -> Result<f32, Box<dyn Error>> {
+	let mut manager = create_manager(SAMPLE_RATE);
+	let mut freeze_parameter = manager.add_parameter(0.0)?;
+	let track = manager.add_sub_track(
+		TrackSettings::new().with_effect(Reverb::new(
+			ReverbSettings::new()
+				.kind(kind)
+				.feedback(0.5)
+				.freeze(&freeze_parameter),
+		)),
+	)?;
+	let input_length = SAMPLE_RATE as usize / 10;
+	let mut sound = manager.add_sound(StaticSound::from_frames(
+		SAMPLE_RATE,
+		sine(440.0, SAMPLE_RATE, input_length),
+		Default::default(),
+	))?;
+	let _instance = sound.play(InstanceSettings::new().track(&track))?;
+	manager.backend_mut().on_start_processing(0.0);
+	for _ in 0..input_length {
+		manager.backend_mut().process();
+	}
+	if freeze {
+		freeze_parameter.set(1.0, Tween::default())?;
+	}
+	manager.backend_mut().on_start_processing(0.0);
+	let output: Vec<_> = (0..SAMPLE_RATE)
+		.map(|_| manager.backend_mut().process())
+		.collect();
+	Ok(peak(&output[output.len() - input_length..]))
+}
+
+#[test]
+fn freezing_sustains_the_reverberations() -> Result<(), Box<dyn Error>> {
+	for kind in [ReverbKind::Freeverb, ReverbKind::FeedbackDelayNetwork] {
+		assert!(peak_after_a_second(kind, false)? < 1.0e-4);
+		assert!(peak_after_a_second(kind, true)? > 1.0e-2);
+	}
+	Ok(())
+}
+
+#[test]
+fn frozen_reverbs_have_no_tail_length() {
+	for kind in [ReverbKind::Freeverb, ReverbKind::FeedbackDelayNetwork] {
+		let settings = ReverbSettings::new().kind(kind).feedback(0.5);
+		assert!(Reverb::new(settings).tail_length().is_some());
+		assert!(Reverb::new(settings.freeze(1.0)).tail_length().is_none());
+	}
+}
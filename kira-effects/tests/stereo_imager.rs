@@ -0,0 +1,65 @@
+mod common;
+
+use std::error::Error;
+
+use common::{assert_frame_approximate_eq, peak, process, sine};
+use kira::Frame;
+use kira_effects::stereo_imager::{StereoImager, StereoImagerSettings};
+
+const SAMPLE_RATE: u32 = 44100;
+
+/// Runs a single stereo frame through a stereo imager.
+fn image(settings: StereoImagerSettings, input: Frame) -> Result<Frame, Box<dyn Error>> {
+	Ok(process(SAMPLE_RATE, StereoImager::new(settings), vec![input])?[0])
+}
+
+#[test]
+fn zero_width_collapses_to_mono() -> Result<(), Box<dyn Error>> {
+	let output = image(StereoImagerSettings::new().width(0.0), Frame::new(1.0, 0.2))?;
+	assert_frame_approximate_eq(output, Frame::from_mono(0.6));
+	Ok(())
+}
+
+#[test]
+fn swaps_and_inverts_channels() -> Result<(), Box<dyn Error>> {
+	let input = Frame::new(1.0, 0.2);
+	assert_frame_approximate_eq(
+		image(StereoImagerSettings::new().swap_channels(true), input)?,
+		Frame::new(0.2, 1.0),
+	);
+	assert_frame_approximate_eq(
+		image(StereoImagerSettings::new().invert_left(true), input)?,
+		Frame::new(-1.0, 0.2),
+	);
+	assert_frame_approximate_eq(
+		image(StereoImagerSettings::new().invert_right(true), input)?,
+		Frame::new(1.0, -0.2),
+	);
+	Ok(())
+}
+
+/// Returns a sine wave that's only in the side channel.
+fn side_sine(frequency: f64) -> Vec<Frame> {
+	sine(frequency, SAMPLE_RATE, SAMPLE_RATE as usize / 5)
+		.into_iter()
+		.map(|frame| Frame::new(frame.left, -frame.left))
+		.collect()
+}
+
+#[test]
+fn bass_mono_removes_side_content_below_the_crossover() -> Result<(), Box<dyn Error>> {
+	let settings = StereoImagerSettings::new().bass_mono_frequency(500.0);
+	let settled = |output: Vec<Frame>| peak(&output[output.len() / 2..]);
+	let low_side = process(SAMPLE_RATE, StereoImager::new(settings), side_sine(50.0))?;
+	assert!(settled(low_side) < 0.01);
+	let high_side = process(SAMPLE_RATE, StereoImager::new(settings), side_sine(5000.0))?;
+	assert!(settled(high_side) > 0.95);
+	// mid content is never affected
+	let low_mid = process(
+		SAMPLE_RATE,
+		StereoImager::new(settings),
+		sine(50.0, SAMPLE_RATE, SAMPLE_RATE as usize / 5),
+	)?;
+	assert!(settled(low_mid) > 0.99);
+	Ok(())
+}
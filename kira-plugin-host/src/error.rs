@@ -0,0 +1,66 @@
+use std::{
+	error::Error,
+	fmt::{Display, Formatter},
+};
+
+/// Errors that can occur when loading a plugin.
+#[derive(Debug)]
+pub enum LoadPluginError {
+	/// The plugin library could not be loaded.
+	LibraryError(libloading::Error),
+	/// The plugin was made for an incompatible version of CLAP.
+	IncompatibleVersion,
+	/// The plugin library failed to initialize.
+	InitFailed,
+	/// The plugin library does not provide a plugin factory.
+	NoPluginFactory,
+	/// The plugin library does not contain a plugin with
+	/// the requested ID, or doesn't contain any plugins.
+	PluginNotFound,
+	/// The plugin could not be created.
+	CreatePluginFailed,
+	/// The plugin does not have a stereo input and output.
+	UnsupportedAudioPorts,
+	/// The settings set a parameter the plugin doesn't have.
+	UnknownParameter(String),
+}
+
+impl Display for LoadPluginError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			LoadPluginError::LibraryError(error) => error.fmt(f),
+			LoadPluginError::IncompatibleVersion => {
+				f.write_str("The plugin uses an incompatible version of CLAP")
+			}
+			LoadPluginError::InitFailed => f.write_str("The plugin library failed to initialize"),
+			LoadPluginError::NoPluginFactory => {
+				f.write_str("The plugin library does not provide a plugin factory")
+			}
+			LoadPluginError::PluginNotFound => {
+				f.write_str("The plugin library does not contain the requested plugin")
+			}
+			LoadPluginError::CreatePluginFailed => f.write_str("The plugin could not be created"),
+			LoadPluginError::UnsupportedAudioPorts => {
+				f.write_str("Only plugins with a stereo input and output are supported")
+			}
+			LoadPluginError::UnknownParameter(name) => {
+				write!(f, "The plugin does not have a parameter named \"{}\"", name)
+			}
+		}
+	}
+}
+
+impl Error for LoadPluginError {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		match self {
+			LoadPluginError::LibraryError(error) => Some(error),
+			_ => None,
+		}
+	}
+}
+
+impl From<libloading::Error> for LoadPluginError {
+	fn from(v: libloading::Error) -> Self {
+		Self::LibraryError(v)
+	}
+}
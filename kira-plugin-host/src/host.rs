@@ -0,0 +1,94 @@
+//! The parts of the CLAP host interface that plugins call into.
+
+use std::{
+	ffi::c_void,
+	os::raw::c_char,
+	ptr::{null, null_mut},
+};
+
+use clap_sys::{
+	events::{clap_event_header, clap_event_param_value, clap_input_events, clap_output_events},
+	host::clap_host,
+	version::CLAP_VERSION,
+};
+
+const NAME: &[u8] = b"Kira\0";
+const VENDOR: &[u8] = b"Kira\0";
+const URL: &[u8] = b"https://github.com/tesselode/kira\0";
+const VERSION: &[u8] = concat!(env!("CARGO_PKG_VERSION"), "\0").as_bytes();
+
+/// Creates the host interface to pass to a plugin.
+///
+/// The host is boxed because plugins hold on to a pointer to it.
+pub(crate) fn create_host() -> Box<clap_host> {
+	Box::new(clap_host {
+		clap_version: CLAP_VERSION,
+		host_data: null_mut(),
+		name: NAME.as_ptr() as *const c_char,
+		vendor: VENDOR.as_ptr() as *const c_char,
+		url: URL.as_ptr() as *const c_char,
+		version: VERSION.as_ptr() as *const c_char,
+		get_extension: Some(get_extension),
+		request_restart: Some(request_restart),
+		request_process: Some(request_process),
+		request_callback: Some(request_callback),
+	})
+}
+
+unsafe extern "C" fn get_extension(_host: *const clap_host, _id: *const c_char) -> *const c_void {
+	// no host extensions are supported
+	null()
+}
+
+// the plugin is always active and processing, and the host
+// doesn't have a main thread loop, so these requests
+// can be ignored
+unsafe extern "C" fn request_restart(_host: *const clap_host) {}
+
+unsafe extern "C" fn request_process(_host: *const clap_host) {}
+
+unsafe extern "C" fn request_callback(_host: *const clap_host) {}
+
+/// Creates an input event list that reads from a list of
+/// parameter change events.
+///
+/// The returned list must not outlive `events`.
+pub(crate) fn input_events(events: &Vec<clap_event_param_value>) -> clap_input_events {
+	clap_input_events {
+		ctx: events as *const Vec<clap_event_param_value> as *mut c_void,
+		size: Some(input_events_size),
+		get: Some(input_events_get),
+	}
+}
+
+unsafe extern "C" fn input_events_size(list: *const clap_input_events) -> u32 {
+	let events = &*((*list).ctx as *const Vec<clap_event_param_value>);
+	events.len() as u32
+}
+
+unsafe extern "C" fn input_events_get(
+	list: *const clap_input_events,
+	index: u32,
+) -> *const clap_event_header {
+	let events = &*((*list).ctx as *const Vec<clap_event_param_value>);
+	match events.get(index as usize) {
+		Some(event) => &event.header,
+		None => null(),
+	}
+}
+
+/// Creates an output event list that discards any events
+/// the plugin sends.
+pub(crate) fn output_events() -> clap_output_events {
+	clap_output_events {
+		ctx: null_mut(),
+		try_push: Some(output_events_try_push),
+	}
+}
+
+unsafe extern "C" fn output_events_try_push(
+	_list: *const clap_output_events,
+	_event: *const clap_event_header,
+) -> bool {
+	true
+}
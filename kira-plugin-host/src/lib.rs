@@ -0,0 +1,29 @@
+//! Hosts [CLAP](https://cleveraudio.org) audio plugins as Kira effects.
+//!
+//! A [`ClapPlugin`] loads an audio effect plugin from a `.clap`
+//! file and processes a mixer track's audio with it, just like
+//! any other [`Effect`](kira::track::Effect). The plugin's
+//! parameters can be set to any [`Value`](kira::value::Value),
+//! so they can be linked to parameters and modulators.
+//!
+//! ```no_run
+//! use kira_plugin_host::{ClapPlugin, ClapPluginSettings};
+//!
+//! let settings = ClapPluginSettings::new().parameter("Room Size", 0.75);
+//! let plugin = unsafe { ClapPlugin::load("plugins/reverb.clap", settings) }?;
+//! # Result::<(), kira_plugin_host::LoadPluginError>::Ok(())
+//! ```
+//!
+//! Plugins are processed one frame at a time and don't receive
+//! any note or transport events, so only audio effect plugins
+//! with a stereo input and output are supported.
+
+mod error;
+mod host;
+mod plugin;
+mod settings;
+
+pub use clap_sys;
+pub use error::*;
+pub use plugin::*;
+pub use settings::*;
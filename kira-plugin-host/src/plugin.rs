@@ -0,0 +1,429 @@
+use std::{
+	ffi::{c_void, CStr, CString},
+	mem::MaybeUninit,
+	path::Path,
+	ptr::{null, null_mut},
+};
+
+use clap_sys::{
+	audio_buffer::clap_audio_buffer,
+	entry::clap_plugin_entry,
+	events::{
+		clap_event_header, clap_event_param_value, CLAP_CORE_EVENT_SPACE_ID, CLAP_EVENT_PARAM_VALUE,
+	},
+	ext::{
+		audio_ports::{clap_audio_port_info, clap_plugin_audio_ports, CLAP_EXT_AUDIO_PORTS},
+		latency::{clap_plugin_latency, CLAP_EXT_LATENCY},
+		params::{clap_param_info, clap_plugin_params, CLAP_EXT_PARAMS},
+		tail::{clap_plugin_tail, CLAP_EXT_TAIL},
+	},
+	factory::plugin_factory::{clap_plugin_factory, CLAP_PLUGIN_FACTORY_ID},
+	host::clap_host,
+	id::clap_id,
+	plugin::clap_plugin,
+	process::{clap_process, CLAP_PROCESS_ERROR},
+	version::clap_version_is_compatible,
+};
+use kira::{manager::resources::Parameters, track::Effect, value::cached::CachedValue, Frame};
+use libloading::Library;
+
+use crate::{
+	host::{create_host, input_events, output_events},
+	ClapPluginSettings, LoadPluginError,
+};
+
+/// The symbol CLAP plugin libraries export their entry point as.
+const ENTRY_SYMBOL: &[u8] = b"clap_entry\0";
+
+/// A plugin parameter that's controlled by a [`Value`](kira::value::Value).
+struct MappedParameter {
+	id: clap_id,
+	cookie: *mut c_void,
+	value: CachedValue,
+	min_value: f64,
+	max_value: f64,
+	/// The value most recently sent to the plugin.
+	sent_value: Option<f64>,
+}
+
+/// An effect that processes audio with a CLAP plugin.
+pub struct ClapPlugin {
+	entry: *const clap_plugin_entry,
+	host: Box<clap_host>,
+	plugin: *const clap_plugin,
+	parameters: Vec<MappedParameter>,
+	/// The parameter change events to send to the plugin in
+	/// the next process call.
+	events: Vec<clap_event_param_value>,
+	sample_rate: u32,
+	activated: bool,
+	processing: bool,
+	/// The number of frames processed so far.
+	steady_time: i64,
+	// the library has to be unloaded after the plugin is destroyed,
+	// so this field needs to be dropped last
+	_library: Option<Library>,
+}
+
+impl ClapPlugin {
+	/// Loads a plugin from a `.clap` file.
+	///
+	/// # Safety
+	///
+	/// Loading a plugin runs code from the plugin library,
+	/// which can't be checked for safety. Only load plugins
+	/// you trust.
+	pub unsafe fn load(
+		path: impl AsRef<Path>,
+		settings: ClapPluginSettings,
+	) -> Result<Self, LoadPluginError> {
+		let library = Library::new(path.as_ref())?;
+		let entry = *library.get::<*const clap_plugin_entry>(ENTRY_SYMBOL)?;
+		let path = CString::new(path.as_ref().to_string_lossy().as_bytes())
+			.map_err(|_| LoadPluginError::InitFailed)?;
+		Self::new(entry, &path, settings, Some(library))
+	}
+
+	/// Creates a plugin from a CLAP entry point that's
+	/// already loaded, like one that's statically linked
+	/// into the game.
+	///
+	/// # Safety
+	///
+	/// `entry` must point to a valid CLAP plugin entry that
+	/// lives for as long as the plugin does.
+	pub unsafe fn from_entry(
+		entry: *const clap_plugin_entry,
+		settings: ClapPluginSettings,
+	) -> Result<Self, LoadPluginError> {
+		let path = CString::default();
+		Self::new(entry, &path, settings, None)
+	}
+
+	unsafe fn new(
+		entry: *const clap_plugin_entry,
+		path: &CStr,
+		settings: ClapPluginSettings,
+		library: Option<Library>,
+	) -> Result<Self, LoadPluginError> {
+		if !clap_version_is_compatible((*entry).clap_version) {
+			return Err(LoadPluginError::IncompatibleVersion);
+		}
+		let init = (*entry).init.ok_or(LoadPluginError::InitFailed)?;
+		if !init(path.as_ptr()) {
+			return Err(LoadPluginError::InitFailed);
+		}
+		// from here on, dropping the plugin will clean up the
+		// entry and plugin, so errors can be returned with `?`
+		let mut plugin = Self {
+			entry,
+			host: create_host(),
+			plugin: null(),
+			parameters: vec![],
+			events: vec![],
+			sample_rate: 0,
+			activated: false,
+			processing: false,
+			steady_time: 0,
+			_library: library,
+		};
+		plugin.create_plugin(settings.plugin_id.as_deref())?;
+		plugin.check_audio_ports()?;
+		plugin.map_parameters(settings)?;
+		Ok(plugin)
+	}
+
+	unsafe fn create_plugin(&mut self, plugin_id: Option<&str>) -> Result<(), LoadPluginError> {
+		let factory = match (*self.entry).get_factory {
+			Some(get_factory) => {
+				get_factory(CLAP_PLUGIN_FACTORY_ID.as_ptr()) as *const clap_plugin_factory
+			}
+			None => null(),
+		};
+		if factory.is_null() {
+			return Err(LoadPluginError::NoPluginFactory);
+		}
+		let (get_plugin_count, get_plugin_descriptor, create_plugin) = match *factory {
+			clap_plugin_factory {
+				get_plugin_count: Some(get_plugin_count),
+				get_plugin_descriptor: Some(get_plugin_descriptor),
+				create_plugin: Some(create_plugin),
+			} => (get_plugin_count, get_plugin_descriptor, create_plugin),
+			_ => return Err(LoadPluginError::NoPluginFactory),
+		};
+		let id = (0..get_plugin_count(factory))
+			.map(|index| get_plugin_descriptor(factory, index))
+			.filter(|descriptor| !descriptor.is_null() && !(**descriptor).id.is_null())
+			.map(|descriptor| CStr::from_ptr((*descriptor).id))
+			.find(|id| match plugin_id {
+				Some(plugin_id) => id.to_bytes() == plugin_id.as_bytes(),
+				None => true,
+			})
+			.ok_or(LoadPluginError::PluginNotFound)?;
+		self.plugin = create_plugin(factory, &*self.host, id.as_ptr());
+		if self.plugin.is_null() {
+			return Err(LoadPluginError::CreatePluginFailed);
+		}
+		let init = (*self.plugin)
+			.init
+			.ok_or(LoadPluginError::CreatePluginFailed)?;
+		if !init(self.plugin) {
+			// the plugin should still be destroyed, but not
+			// deactivated or stopped
+			return Err(LoadPluginError::CreatePluginFailed);
+		}
+		Ok(())
+	}
+
+	/// Returns the plugin extension with the given ID, if the
+	/// plugin supports it.
+	unsafe fn extension<T>(&self, id: &CStr) -> Option<&T> {
+		let get_extension = (*self.plugin).get_extension?;
+		(get_extension(self.plugin, id.as_ptr()) as *const T).as_ref()
+	}
+
+	/// Checks that the main input and output ports of the
+	/// plugin are stereo.
+	unsafe fn check_audio_ports(&self) -> Result<(), LoadPluginError> {
+		let audio_ports = self
+			.extension::<clap_plugin_audio_ports>(CLAP_EXT_AUDIO_PORTS)
+			.ok_or(LoadPluginError::UnsupportedAudioPorts)?;
+		let (count, get) = match (audio_ports.count, audio_ports.get) {
+			(Some(count), Some(get)) => (count, get),
+			_ => return Err(LoadPluginError::UnsupportedAudioPorts),
+		};
+		for is_input in [true, false] {
+			if count(self.plugin, is_input) == 0 {
+				return Err(LoadPluginError::UnsupportedAudioPorts);
+			}
+			let mut info = MaybeUninit::<clap_audio_port_info>::zeroed();
+			if !get(self.plugin, 0, is_input, info.as_mut_ptr())
+				|| info.assume_init().channel_count != 2
+			{
+				return Err(LoadPluginError::UnsupportedAudioPorts);
+			}
+		}
+		Ok(())
+	}
+
+	/// Links the plugin's parameters to the values from the settings.
+	unsafe fn map_parameters(
+		&mut self,
+		mut settings: ClapPluginSettings,
+	) -> Result<(), LoadPluginError> {
+		if let Some(params) = self.extension::<clap_plugin_params>(CLAP_EXT_PARAMS) {
+			if let (Some(count), Some(get_info)) = (params.count, params.get_info) {
+				for index in 0..count(self.plugin) {
+					let mut info = MaybeUninit::<clap_param_info>::zeroed();
+					if !get_info(self.plugin, index, info.as_mut_ptr()) {
+						continue;
+					}
+					let info = info.assume_init();
+					let name = CStr::from_ptr(info.name.as_ptr()).to_string_lossy();
+					if let Some(value) = settings.parameters.remove(name.as_ref()) {
+						self.parameters.push(MappedParameter {
+							id: info.id,
+							cookie: info.cookie,
+							value: CachedValue::new(
+								info.min_value..=info.max_value,
+								value,
+								info.default_value,
+							)
+							.with_smoothing(settings.smoothing),
+							min_value: info.min_value,
+							max_value: info.max_value,
+							sent_value: None,
+						});
+					}
+				}
+			}
+		}
+		if let Some(name) = settings.parameters.into_keys().next() {
+			return Err(LoadPluginError::UnknownParameter(name));
+		}
+		self.events = Vec::with_capacity(self.parameters.len());
+		Ok(())
+	}
+
+	/// Queues up events for any parameters whose values
+	/// have changed.
+	fn update_parameters(&mut self, parameters: &Parameters) {
+		self.events.clear();
+		for parameter in &mut self.parameters {
+			parameter.value.update(parameters);
+			// plugins expect values within the range they reported
+			let value = parameter
+				.value
+				.get()
+				.max(parameter.min_value)
+				.min(parameter.max_value);
+			if parameter.sent_value == Some(value) {
+				continue;
+			}
+			parameter.sent_value = Some(value);
+			self.events.push(clap_event_param_value {
+				header: clap_event_header {
+					size: std::mem::size_of::<clap_event_param_value>() as u32,
+					time: 0,
+					space_id: CLAP_CORE_EVENT_SPACE_ID,
+					type_: CLAP_EVENT_PARAM_VALUE,
+					flags: 0,
+				},
+				param_id: parameter.id,
+				cookie: parameter.cookie,
+				note_id: -1,
+				port_index: -1,
+				channel: -1,
+				key: -1,
+				value,
+			});
+		}
+	}
+}
+
+impl Effect for ClapPlugin {
+	fn init(&mut self, sample_rate: u32) {
+		self.sample_rate = sample_rate;
+		unsafe {
+			if let Some(activate) = (*self.plugin).activate {
+				// the plugin is given one frame at a time
+				self.activated = activate(self.plugin, sample_rate as f64, 1, 1);
+			}
+		}
+	}
+
+	fn tail_length(&self) -> Option<f64> {
+		let tail = unsafe { self.extension::<clap_plugin_tail>(CLAP_EXT_TAIL) }?;
+		let frames = unsafe { (tail.get?)(self.plugin) };
+		// CLAP treats any tail of at least `i32::MAX` frames
+		// as infinite
+		if frames >= i32::MAX as u32 {
+			return None;
+		}
+		Some(frames as f64 / self.sample_rate as f64)
+	}
+
+	fn latency(&self) -> usize {
+		if !self.activated {
+			return 0;
+		}
+		unsafe { self.extension::<clap_plugin_latency>(CLAP_EXT_LATENCY) }
+			.and_then(|latency| latency.get)
+			.map(|get| unsafe { get(self.plugin) } as usize)
+			.unwrap_or(0)
+	}
+
+	fn shutdown(&mut self) {
+		// CLAP requires processing to be stopped from the
+		// audio thread
+		if self.processing {
+			if let Some(stop_processing) = unsafe { (*self.plugin).stop_processing } {
+				unsafe { stop_processing(self.plugin) };
+			}
+			self.processing = false;
+		}
+	}
+
+	fn process(&mut self, input: Frame, _dt: f64, parameters: &Parameters) -> Frame {
+		// plugins that failed to activate are bypassed
+		if !self.activated {
+			return input;
+		}
+		if !self.processing {
+			self.processing = match unsafe { (*self.plugin).start_processing } {
+				Some(start_processing) => unsafe { start_processing(self.plugin) },
+				None => true,
+			};
+			if !self.processing {
+				return input;
+			}
+		}
+		let process = match unsafe { (*self.plugin).process } {
+			Some(process) => process,
+			None => return input,
+		};
+		self.update_parameters(parameters);
+		let mut input_samples = [input.left, input.right];
+		let mut output_samples = [0.0f32; 2];
+		let mut input_channels = [input_samples.as_mut_ptr(), unsafe {
+			input_samples.as_mut_ptr().add(1)
+		}];
+		let mut output_channels = [output_samples.as_mut_ptr(), unsafe {
+			output_samples.as_mut_ptr().add(1)
+		}];
+		let input_buffer = clap_audio_buffer {
+			data32: input_channels.as_mut_ptr(),
+			data64: null_mut(),
+			channel_count: 2,
+			latency: 0,
+			constant_mask: 0,
+		};
+		let mut output_buffer = clap_audio_buffer {
+			data32: output_channels.as_mut_ptr(),
+			data64: null_mut(),
+			channel_count: 2,
+			latency: 0,
+			constant_mask: 0,
+		};
+		let in_events = input_events(&self.events);
+		let out_events = output_events();
+		let status = unsafe {
+			process(
+				self.plugin,
+				&clap_process {
+					steady_time: self.steady_time,
+					frames_count: 1,
+					transport: null(),
+					audio_inputs: &input_buffer,
+					audio_outputs: &mut output_buffer,
+					audio_inputs_count: 1,
+					audio_outputs_count: 1,
+					in_events: &in_events,
+					out_events: &out_events,
+				},
+			)
+		};
+		self.steady_time += 1;
+		if status == CLAP_PROCESS_ERROR {
+			return Frame::ZERO;
+		}
+		Frame::new(output_samples[0], output_samples[1])
+	}
+}
+
+impl Drop for ClapPlugin {
+	fn drop(&mut self) {
+		unsafe {
+			if !self.plugin.is_null() {
+				// `shutdown` normally stops processing on the audio
+				// thread, but it isn't called if the audio manager is
+				// dropped with the track still in the mixer. The plugin
+				// still has to stop processing before it can be
+				// deactivated, so do it here as a last resort, even
+				// though CLAP expects this on the audio thread.
+				if self.processing {
+					if let Some(stop_processing) = (*self.plugin).stop_processing {
+						stop_processing(self.plugin);
+					}
+				}
+				if self.activated {
+					if let Some(deactivate) = (*self.plugin).deactivate {
+						deactivate(self.plugin);
+					}
+				}
+				if let Some(destroy) = (*self.plugin).destroy {
+					destroy(self.plugin);
+				}
+			}
+			if let Some(deinit) = (*self.entry).deinit {
+				deinit();
+			}
+		}
+	}
+}
+
+// plugin instances are only used by one thread at a time: they're
+// created and destroyed on the gameplay thread and processed on the
+// audio thread, which is how CLAP hosts are expected to use them
+unsafe impl Send for ClapPlugin {}
+unsafe impl Sync for ClapPlugin {}
@@ -0,0 +1,62 @@
+use std::collections::HashMap;
+
+use kira::value::{Smoothing, Value};
+
+/// Settings for a [`ClapPlugin`](crate::ClapPlugin).
+#[derive(Debug, Clone)]
+pub struct ClapPluginSettings {
+	/// The ID of the plugin to load from the plugin library.
+	///
+	/// If `None`, the first plugin in the library will be loaded.
+	pub plugin_id: Option<String>,
+	/// The values to set the plugin's parameters to, keyed by
+	/// parameter name.
+	///
+	/// Parameters that aren't listed here keep the values
+	/// the plugin gives them.
+	pub parameters: HashMap<String, Value>,
+	/// How the parameters move to new values after they're changed.
+	///
+	/// If `None`, the default smoothing of the audio manager
+	/// will be used.
+	pub smoothing: Option<Smoothing>,
+}
+
+impl ClapPluginSettings {
+	/// Creates a new `ClapPluginSettings` with the default settings.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Sets the ID of the plugin to load from the plugin library.
+	pub fn plugin_id(self, plugin_id: impl Into<String>) -> Self {
+		Self {
+			plugin_id: Some(plugin_id.into()),
+			..self
+		}
+	}
+
+	/// Sets the value of the plugin parameter with the given name.
+	pub fn parameter(mut self, name: impl Into<String>, value: impl Into<Value>) -> Self {
+		self.parameters.insert(name.into(), value.into());
+		self
+	}
+
+	/// Sets how the parameters move to new values after they're changed.
+	pub fn smoothing(self, smoothing: impl Into<Option<Smoothing>>) -> Self {
+		Self {
+			smoothing: smoothing.into(),
+			..self
+		}
+	}
+}
+
+impl Default for ClapPluginSettings {
+	fn default() -> Self {
+		Self {
+			plugin_id: None,
+			parameters: HashMap::new(),
+			smoothing: None,
+		}
+	}
+}
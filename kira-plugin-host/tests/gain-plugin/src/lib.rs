@@ -0,0 +1,322 @@
+//! A tiny CLAP plugin that multiplies its input by a gain parameter.
+//!
+//! The integration tests use this both as a dynamic library loaded
+//! from a file and as a module linked into the test binary.
+
+use std::{
+	cell::Cell,
+	ffi::{c_void, CStr},
+	os::raw::c_char,
+	ptr::{null, null_mut},
+};
+
+use clap_sys::{
+	audio_buffer::clap_audio_buffer,
+	entry::clap_plugin_entry,
+	events::{clap_event_param_value, CLAP_EVENT_PARAM_VALUE},
+	ext::{
+		audio_ports::{clap_audio_port_info, clap_plugin_audio_ports, CLAP_EXT_AUDIO_PORTS},
+		params::{clap_param_info, clap_plugin_params, CLAP_EXT_PARAMS},
+		tail::{clap_plugin_tail, CLAP_EXT_TAIL},
+	},
+	factory::plugin_factory::{clap_plugin_factory, CLAP_PLUGIN_FACTORY_ID},
+	host::clap_host,
+	id::{clap_id, CLAP_INVALID_ID},
+	plugin::{clap_plugin, clap_plugin_descriptor},
+	process::{clap_process, clap_process_status, CLAP_PROCESS_CONTINUE},
+	version::CLAP_VERSION,
+};
+
+pub const ID: &str = "kira.test.gain";
+/// A version of the plugin that reports an infinite tail.
+pub const INFINITE_TAIL_ID: &str = "kira.test.gain-infinite-tail";
+const GAIN_PARAMETER_ID: clap_id = 7;
+
+/// Lets CLAP structs containing raw pointers be stored in statics.
+struct Static<T>(T);
+
+unsafe impl<T> Sync for Static<T> {}
+
+static FEATURES: Static<[*const c_char; 2]> =
+	Static([b"audio-effect\0".as_ptr() as *const c_char, null()]);
+
+static DESCRIPTORS: Static<[clap_plugin_descriptor; 2]> = Static([
+	clap_plugin_descriptor {
+		clap_version: CLAP_VERSION,
+		id: b"kira.test.gain\0".as_ptr() as *const c_char,
+		name: b"Gain\0".as_ptr() as *const c_char,
+		vendor: b"Kira\0".as_ptr() as *const c_char,
+		url: b"\0".as_ptr() as *const c_char,
+		manual_url: b"\0".as_ptr() as *const c_char,
+		support_url: b"\0".as_ptr() as *const c_char,
+		version: b"0.1.0\0".as_ptr() as *const c_char,
+		description: b"\0".as_ptr() as *const c_char,
+		features: &FEATURES.0 as *const [*const c_char; 2] as *const *const c_char,
+	},
+	clap_plugin_descriptor {
+		clap_version: CLAP_VERSION,
+		id: b"kira.test.gain-infinite-tail\0".as_ptr() as *const c_char,
+		name: b"Gain (Infinite Tail)\0".as_ptr() as *const c_char,
+		vendor: b"Kira\0".as_ptr() as *const c_char,
+		url: b"\0".as_ptr() as *const c_char,
+		manual_url: b"\0".as_ptr() as *const c_char,
+		support_url: b"\0".as_ptr() as *const c_char,
+		version: b"0.1.0\0".as_ptr() as *const c_char,
+		description: b"\0".as_ptr() as *const c_char,
+		features: &FEATURES.0 as *const [*const c_char; 2] as *const *const c_char,
+	},
+]);
+
+static FACTORY: Static<clap_plugin_factory> = Static(clap_plugin_factory {
+	get_plugin_count: Some(get_plugin_count),
+	get_plugin_descriptor: Some(get_plugin_descriptor),
+	create_plugin: Some(create_plugin),
+});
+
+/// The entry point hosts look for when loading the plugin
+/// from a dynamic library.
+#[allow(non_upper_case_globals)]
+#[no_mangle]
+pub static clap_entry: clap_plugin_entry = clap_plugin_entry {
+	clap_version: CLAP_VERSION,
+	init: Some(init),
+	deinit: Some(deinit),
+	get_factory: Some(get_factory),
+};
+
+static AUDIO_PORTS: clap_plugin_audio_ports = clap_plugin_audio_ports {
+	count: Some(audio_ports_count),
+	get: Some(audio_ports_get),
+};
+
+static PARAMS: clap_plugin_params = clap_plugin_params {
+	count: Some(params_count),
+	get_info: Some(params_get_info),
+	get_value: Some(params_get_value),
+	value_to_text: None,
+	text_to_value: None,
+	flush: None,
+};
+
+static TAIL: clap_plugin_tail = clap_plugin_tail {
+	get: Some(tail_get),
+};
+
+thread_local! {
+	/// How many times `stop_processing` has been called on
+	/// this thread.
+	pub static STOP_PROCESSING_CALLS: Cell<usize> = const { Cell::new(0) };
+}
+
+pub fn entry() -> *const clap_plugin_entry {
+	&clap_entry
+}
+
+struct GainPlugin {
+	plugin: clap_plugin,
+	gain: f64,
+	/// The tail length reported to the host (in frames).
+	tail: u32,
+}
+
+unsafe fn gain_plugin<'a>(plugin: *const clap_plugin) -> &'a mut GainPlugin {
+	&mut *((*plugin).plugin_data as *mut GainPlugin)
+}
+
+unsafe extern "C" fn init(_plugin_path: *const c_char) -> bool {
+	true
+}
+
+unsafe extern "C" fn deinit() {}
+
+unsafe extern "C" fn get_factory(factory_id: *const c_char) -> *const c_void {
+	if CStr::from_ptr(factory_id) == CLAP_PLUGIN_FACTORY_ID {
+		&FACTORY.0 as *const clap_plugin_factory as *const c_void
+	} else {
+		null()
+	}
+}
+
+unsafe extern "C" fn get_plugin_count(_factory: *const clap_plugin_factory) -> u32 {
+	DESCRIPTORS.0.len() as u32
+}
+
+unsafe extern "C" fn get_plugin_descriptor(
+	_factory: *const clap_plugin_factory,
+	index: u32,
+) -> *const clap_plugin_descriptor {
+	match DESCRIPTORS.0.get(index as usize) {
+		Some(descriptor) => descriptor,
+		None => null(),
+	}
+}
+
+unsafe extern "C" fn create_plugin(
+	_factory: *const clap_plugin_factory,
+	_host: *const clap_host,
+	plugin_id: *const c_char,
+) -> *const clap_plugin {
+	let plugin_id = CStr::from_ptr(plugin_id).to_bytes();
+	let (descriptor, tail) = if plugin_id == ID.as_bytes() {
+		(&DESCRIPTORS.0[0], 0)
+	} else if plugin_id == INFINITE_TAIL_ID.as_bytes() {
+		// CLAP treats any tail of at least `i32::MAX` frames as infinite
+		(&DESCRIPTORS.0[1], i32::MAX as u32)
+	} else {
+		return null();
+	};
+	let gain_plugin = Box::into_raw(Box::new(GainPlugin {
+		plugin: clap_plugin {
+			desc: descriptor,
+			plugin_data: null_mut(),
+			init: Some(plugin_init),
+			destroy: Some(plugin_destroy),
+			activate: Some(plugin_activate),
+			deactivate: Some(plugin_deactivate),
+			start_processing: Some(plugin_start_processing),
+			stop_processing: Some(plugin_stop_processing),
+			reset: Some(plugin_reset),
+			process: Some(plugin_process),
+			get_extension: Some(plugin_get_extension),
+			on_main_thread: Some(plugin_on_main_thread),
+		},
+		gain: 1.0,
+		tail,
+	}));
+	(*gain_plugin).plugin.plugin_data = gain_plugin as *mut c_void;
+	&(*gain_plugin).plugin
+}
+
+unsafe extern "C" fn plugin_init(_plugin: *const clap_plugin) -> bool {
+	true
+}
+
+unsafe extern "C" fn plugin_destroy(plugin: *const clap_plugin) {
+	drop(Box::from_raw((*plugin).plugin_data as *mut GainPlugin));
+}
+
+unsafe extern "C" fn plugin_activate(
+	_plugin: *const clap_plugin,
+	_sample_rate: f64,
+	_min_frames_count: u32,
+	_max_frames_count: u32,
+) -> bool {
+	true
+}
+
+unsafe extern "C" fn plugin_deactivate(_plugin: *const clap_plugin) {}
+
+unsafe extern "C" fn plugin_start_processing(_plugin: *const clap_plugin) -> bool {
+	true
+}
+
+unsafe extern "C" fn plugin_stop_processing(_plugin: *const clap_plugin) {
+	STOP_PROCESSING_CALLS.with(|calls| calls.set(calls.get() + 1));
+}
+
+unsafe extern "C" fn plugin_reset(_plugin: *const clap_plugin) {}
+
+unsafe extern "C" fn plugin_process(
+	plugin: *const clap_plugin,
+	process: *const clap_process,
+) -> clap_process_status {
+	let gain_plugin = gain_plugin(plugin);
+	let process = &*process;
+	let in_events = &*process.in_events;
+	for index in 0..in_events.size.unwrap()(in_events) {
+		let header = in_events.get.unwrap()(in_events, index);
+		if (*header).type_ == CLAP_EVENT_PARAM_VALUE {
+			let event = &*(header as *const clap_event_param_value);
+			if event.param_id == GAIN_PARAMETER_ID {
+				gain_plugin.gain = event.value;
+			}
+		}
+	}
+	let input: &clap_audio_buffer = &*process.audio_inputs;
+	let output: &clap_audio_buffer = &*process.audio_outputs;
+	for channel in 0..2 {
+		let input = *input.data32.add(channel);
+		let output = *output.data32.add(channel);
+		for frame in 0..process.frames_count as usize {
+			*output.add(frame) = *input.add(frame) * gain_plugin.gain as f32;
+		}
+	}
+	CLAP_PROCESS_CONTINUE
+}
+
+unsafe extern "C" fn plugin_get_extension(
+	_plugin: *const clap_plugin,
+	id: *const c_char,
+) -> *const c_void {
+	let id = CStr::from_ptr(id);
+	if id == CLAP_EXT_AUDIO_PORTS {
+		&AUDIO_PORTS as *const clap_plugin_audio_ports as *const c_void
+	} else if id == CLAP_EXT_PARAMS {
+		&PARAMS as *const clap_plugin_params as *const c_void
+	} else if id == CLAP_EXT_TAIL {
+		&TAIL as *const clap_plugin_tail as *const c_void
+	} else {
+		null()
+	}
+}
+
+unsafe extern "C" fn plugin_on_main_thread(_plugin: *const clap_plugin) {}
+
+unsafe extern "C" fn audio_ports_count(_plugin: *const clap_plugin, _is_input: bool) -> u32 {
+	1
+}
+
+unsafe extern "C" fn audio_ports_get(
+	_plugin: *const clap_plugin,
+	index: u32,
+	_is_input: bool,
+	info: *mut clap_audio_port_info,
+) -> bool {
+	if index != 0 {
+		return false;
+	}
+	let info = &mut *info;
+	info.id = 0;
+	info.channel_count = 2;
+	info.in_place_pair = CLAP_INVALID_ID;
+	true
+}
+
+unsafe extern "C" fn params_count(_plugin: *const clap_plugin) -> u32 {
+	1
+}
+
+unsafe extern "C" fn params_get_info(
+	_plugin: *const clap_plugin,
+	param_index: u32,
+	param_info: *mut clap_param_info,
+) -> bool {
+	if param_index != 0 {
+		return false;
+	}
+	let param_info = &mut *param_info;
+	param_info.id = GAIN_PARAMETER_ID;
+	for (i, byte) in b"Gain\0".iter().enumerate() {
+		param_info.name[i] = *byte as c_char;
+	}
+	param_info.min_value = 0.0;
+	param_info.max_value = 2.0;
+	param_info.default_value = 1.0;
+	true
+}
+
+unsafe extern "C" fn params_get_value(
+	plugin: *const clap_plugin,
+	param_id: clap_id,
+	out_value: *mut f64,
+) -> bool {
+	if param_id != GAIN_PARAMETER_ID {
+		return false;
+	}
+	*out_value = gain_plugin(plugin).gain;
+	true
+}
+
+unsafe extern "C" fn tail_get(plugin: *const clap_plugin) -> u32 {
+	gain_plugin(plugin).tail
+}
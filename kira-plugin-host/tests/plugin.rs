@@ -0,0 +1,199 @@
+use std::{
+	error::Error,
+	path::{Path, PathBuf},
+	process::Command,
+};
+
+use kira::{
+	manager::{AudioManager, AudioManagerSettings, MockBackend},
+	sound::{instance::InstanceSettings, static_sound::StaticSound},
+	track::{Effect, TrackSettings},
+	value::Value,
+	Frame,
+};
+use kira_plugin_host::{ClapPlugin, ClapPluginSettings, LoadPluginError};
+
+#[path = "gain-plugin/src/lib.rs"]
+mod gain_plugin;
+
+/// Plays a single frame of audio on a track with the given
+/// effects and returns the output.
+fn render(effects: Vec<Box<dyn Effect>>) -> Result<Frame, Box<dyn Error>> {
+	let mut manager = AudioManager::new(
+		AudioManagerSettings {
+			main_track_limiter: None,
+			..Default::default()
+		},
+		MockBackend::new(1),
+	)
+	.unwrap();
+	let mut settings = TrackSettings::new();
+	settings.effects = effects;
+	let track = manager.add_sub_track(settings)?;
+	let mut sound = manager.add_sound(StaticSound::from_frames(
+		1,
+		vec![Frame::from_mono(1.0)],
+		Default::default(),
+	))?;
+	sound.play(InstanceSettings::new().track(&track))?;
+	manager.backend_mut().on_start_processing(0.0);
+	Ok(manager.backend_mut().process())
+}
+
+fn gain_plugin(gain: impl Into<Value>) -> Result<ClapPlugin, LoadPluginError> {
+	unsafe {
+		ClapPlugin::from_entry(
+			gain_plugin::entry(),
+			ClapPluginSettings::new()
+				.plugin_id(gain_plugin::ID)
+				.parameter("Gain", gain),
+		)
+	}
+}
+
+#[test]
+fn processes_audio_with_plugins() -> Result<(), Box<dyn Error>> {
+	let dry = render(vec![])?;
+	let wet = render(vec![Box::new(gain_plugin(0.5)?)])?;
+	assert_eq!(wet, dry * 0.5);
+	Ok(())
+}
+
+#[test]
+fn clamps_parameters_to_plugin_range() -> Result<(), Box<dyn Error>> {
+	let dry = render(vec![])?;
+	let wet = render(vec![Box::new(gain_plugin(5.0)?)])?;
+	assert_eq!(wet, dry * 2.0);
+	Ok(())
+}
+
+#[test]
+fn reports_tail_length() -> Result<(), Box<dyn Error>> {
+	let mut plugin = gain_plugin(1.0)?;
+	plugin.init(48000);
+	assert_eq!(plugin.tail_length(), Some(0.0));
+	Ok(())
+}
+
+#[test]
+fn reports_infinite_tails() -> Result<(), Box<dyn Error>> {
+	let mut plugin = unsafe {
+		ClapPlugin::from_entry(
+			gain_plugin::entry(),
+			ClapPluginSettings::new().plugin_id(gain_plugin::INFINITE_TAIL_ID),
+		)
+	}?;
+	plugin.init(48000);
+	assert_eq!(plugin.tail_length(), None);
+	Ok(())
+}
+
+#[test]
+fn unknown_parameters_are_errors() {
+	let result = unsafe {
+		ClapPlugin::from_entry(
+			gain_plugin::entry(),
+			ClapPluginSettings::new().parameter("Volume", 1.0),
+		)
+	};
+	assert!(matches!(
+		result,
+		Err(LoadPluginError::UnknownParameter(name)) if name == "Volume"
+	));
+}
+
+#[test]
+fn unknown_plugin_ids_are_errors() {
+	let result = unsafe {
+		ClapPlugin::from_entry(
+			gain_plugin::entry(),
+			ClapPluginSettings::new().plugin_id("kira.test.missing"),
+		)
+	};
+	assert!(matches!(result, Err(LoadPluginError::PluginNotFound)));
+}
+
+#[test]
+fn stops_processing_when_the_track_is_removed() -> Result<(), Box<dyn Error>> {
+	let mut manager = AudioManager::new(Default::default(), MockBackend::new(1)).unwrap();
+	let track = manager.add_sub_track(TrackSettings::new().with_effect(gain_plugin(1.0)?))?;
+	let mut sound = manager.add_sound(StaticSound::from_frames(
+		1,
+		vec![Frame::from_mono(1.0)],
+		Default::default(),
+	))?;
+	sound.play(InstanceSettings::new().track(&track))?;
+	manager.backend_mut().on_start_processing(0.0);
+	manager.backend_mut().process();
+	let stop_processing_calls = || gain_plugin::STOP_PROCESSING_CALLS.with(|calls| calls.get());
+	assert_eq!(stop_processing_calls(), 0);
+	// the mock backend processes audio on this thread, so the call
+	// has to happen during processing rather than when the plugin
+	// is dropped
+	drop(track);
+	manager.backend_mut().on_start_processing(0.0);
+	assert_eq!(stop_processing_calls(), 1);
+	drop(manager);
+	assert_eq!(stop_processing_calls(), 1);
+	Ok(())
+}
+
+#[test]
+fn missing_libraries_are_errors() {
+	let result = unsafe {
+		ClapPlugin::load(
+			Path::new("missing").join("plugin.clap"),
+			ClapPluginSettings::new(),
+		)
+	};
+	assert!(matches!(result, Err(LoadPluginError::LibraryError(_))));
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn libraries_without_a_clap_entry_are_errors() {
+	// the C library is always available, but isn't a plugin
+	let result = unsafe { ClapPlugin::load("libc.so.6", ClapPluginSettings::new()) };
+	assert!(matches!(result, Err(LoadPluginError::LibraryError(_))));
+}
+
+/// Builds the gain plugin as a dynamic library and returns
+/// the path to it.
+fn build_gain_plugin() -> PathBuf {
+	let manifest_path = Path::new(env!("CARGO_MANIFEST_DIR"))
+		.join("tests")
+		.join("gain-plugin")
+		.join("Cargo.toml");
+	let target_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("gain-plugin");
+	let status = Command::new(env!("CARGO"))
+		.arg("build")
+		.arg("--manifest-path")
+		.arg(manifest_path)
+		.arg("--target-dir")
+		.arg(&target_dir)
+		.status()
+		.expect("could not run cargo");
+	assert!(status.success(), "could not build the gain plugin");
+	target_dir.join("debug").join(format!(
+		"{}gain_plugin{}",
+		std::env::consts::DLL_PREFIX,
+		std::env::consts::DLL_SUFFIX
+	))
+}
+
+#[test]
+fn loads_plugins_from_files() -> Result<(), Box<dyn Error>> {
+	let path = build_gain_plugin();
+	let plugin = unsafe {
+		ClapPlugin::load(
+			path,
+			ClapPluginSettings::new()
+				.plugin_id(gain_plugin::ID)
+				.parameter("Gain", 0.5),
+		)
+	}?;
+	let dry = render(vec![])?;
+	let wet = render(vec![Box::new(plugin)])?;
+	assert_eq!(wet, dry * 0.5);
+	Ok(())
+}
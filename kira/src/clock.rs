@@ -15,6 +15,7 @@ use atomic_arena::Key;
 
 use crate::{
 	manager::resources::Parameters,
+	parameter::Tween,
 	value::{cached::CachedValue, Value},
 };
 
@@ -85,8 +86,27 @@ impl Clock {
 		self.ticks
 	}
 
-	pub fn set_interval(&mut self, interval: Value) {
-		self.interval.set(interval);
+	pub fn interval(&self) -> f64 {
+		self.interval.get()
+	}
+
+	/// Returns the elapsed time of the clock in ticks, including
+	/// the progress towards the next tick.
+	pub fn fractional_position(&self) -> f64 {
+		self.ticks as f64 + (1.0 - self.tick_timer)
+	}
+
+	pub fn set_interval(&mut self, interval: Value, tween: Option<Tween>) {
+		self.interval.set(interval, tween);
+	}
+
+	/// Returns the clock time the interval's tween is waiting for, if any.
+	pub fn interval_tween_start_time(&self) -> Option<ClockTime> {
+		self.interval.waiting_for()
+	}
+
+	pub fn start_interval_tween(&mut self) {
+		self.interval.start_waiting_tween();
 	}
 
 	pub fn start(&mut self) {
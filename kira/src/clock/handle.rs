@@ -3,6 +3,7 @@ use std::sync::Arc;
 use crate::{
 	error::CommandError,
 	manager::command::{producer::CommandProducer, ClockCommand, Command},
+	parameter::Tween,
 	value::Value,
 };
 
@@ -39,11 +40,20 @@ impl ClockHandle {
 	}
 
 	/// Sets the duration of time between each tick (in seconds).
-	pub fn set_interval(&mut self, interval: impl Into<Value>) -> Result<(), CommandError> {
+	///
+	/// If a tween is provided, the interval will move to the new
+	/// value following the tween. Otherwise, the default smoothing
+	/// will be used.
+	pub fn set_interval(
+		&mut self,
+		interval: impl Into<Value>,
+		tween: impl Into<Option<Tween>>,
+	) -> Result<(), CommandError> {
 		self.command_producer
 			.push(Command::Clock(ClockCommand::SetInterval(
 				self.id,
 				interval.into(),
+				tween.into(),
 			)))
 	}
 
@@ -10,6 +10,7 @@ mod error;
 mod frame;
 mod loop_behavior;
 pub mod manager;
+pub mod modulator;
 pub mod parameter;
 pub mod sound;
 mod start_time;
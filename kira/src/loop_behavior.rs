@@ -2,6 +2,10 @@
 ///
 /// The end of the loop is always at the end of the sound.
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+#[cfg_attr(
+	feature = "serde_support",
+	derive(serde::Serialize, serde::Deserialize)
+)]
 pub struct LoopBehavior {
 	/// The position that playback should jump to when the
 	/// end of the sound has been reached.
@@ -9,7 +9,7 @@ pub mod resources;
 pub use backend::*;
 pub use renderer::*;
 
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use ringbuf::RingBuffer;
 
@@ -17,28 +17,41 @@ use crate::{
 	audio_stream::{AudioStream, AudioStreamHandle, AudioStreamId, AudioStreamWrapper},
 	clock::{Clock, ClockHandle, ClockId},
 	error::CommandError,
+	modulator::{
+		EffectOutputHandle, EnvelopeHandle, EnvelopeSettings, LfoHandle, LfoSettings, Modulator,
+		ModulatorId,
+	},
 	parameter::{Parameter, ParameterHandle, ParameterId, Tween},
 	sound::{
 		wrapper::{SoundWrapper, SoundWrapperShared},
 		Sound, SoundHandle, SoundId,
 	},
-	track::{SubTrackId, Track, TrackHandle, TrackId, TrackSettings},
-	value::Value,
+	track::{
+		MainTrackLimiterSettings, SubTrackId, Track, TrackGraph, TrackHandle, TrackId, TrackMeter,
+		TrackSettings, TrackShared, Vca, VcaHandle, VcaId,
+	},
+	value::{Smoothing, Value},
 };
 
 use self::{
 	command::{
 		producer::CommandProducer, AudioStreamCommand, ClockCommand, Command, MixerCommand,
-		ParameterCommand, SoundCommand,
+		ModulatorCommand, ParameterCommand, SoundCommand,
 	},
 	error::{
-		AddAudioStreamError, AddClockError, AddParameterError, AddSoundError, AddSubTrackError,
+		AddAudioStreamError, AddClockError, AddModulatorError, AddParameterError, AddSoundError,
+		AddSubTrackError, AddVcaError,
 	},
 	renderer::context::Context,
 	resources::{create_resources, create_unused_resource_channels, ResourceControllers},
 };
 
 /// Settings for an [`AudioManager`].
+#[cfg_attr(
+	feature = "serde_support",
+	derive(serde::Serialize, serde::Deserialize)
+)]
+#[cfg_attr(feature = "serde_support", serde(default))]
 pub struct AudioManagerSettings {
 	/// The number of commands that be sent to the renderer at a time.
 	///
@@ -51,12 +64,30 @@ pub struct AudioManagerSettings {
 	pub instance_capacity: usize,
 	/// The maximum number of parameters that can exist at a time.
 	pub parameter_capacity: usize,
+	/// The maximum number of modulators that can exist at a time.
+	pub modulator_capacity: usize,
 	/// The maximum number of mixer sub-tracks that can exist at a time.
 	pub sub_track_capacity: usize,
+	/// The maximum number of VCAs that can exist at a time.
+	pub vca_capacity: usize,
 	/// The maximum number of clocks that can exist at a time.
 	pub clock_capacity: usize,
 	/// The maximum number of audio streams that can be loaded at a time.
 	pub audio_stream_capacity: usize,
+	/// How settings move to new values after they're changed, unless
+	/// the settings specify their own [`Smoothing`].
+	pub default_smoothing: Smoothing,
+	/// The settings for the limiter that keeps the output of the
+	/// main mixer track from clipping, or `None` to disable it.
+	pub main_track_limiter: Option<MainTrackLimiterSettings>,
+	/// The maximum latency (in frames) the mixer can compensate for.
+	///
+	/// When the output of effects with latency is mixed with
+	/// audio that didn't pass through those effects, the mixer
+	/// delays the other audio so everything stays in sync.
+	/// Each mixer track and route preallocates enough memory to
+	/// delay audio by this many frames.
+	pub latency_compensation_capacity: usize,
 }
 
 impl Default for AudioManagerSettings {
@@ -66,9 +97,14 @@ impl Default for AudioManagerSettings {
 			command_capacity: 128,
 			instance_capacity: 128,
 			parameter_capacity: 128,
+			modulator_capacity: 32,
 			sub_track_capacity: 128,
+			vca_capacity: 16,
 			clock_capacity: 1,
 			audio_stream_capacity: 32,
+			default_smoothing: Smoothing::default(),
+			main_track_limiter: Some(MainTrackLimiterSettings::default()),
+			latency_compensation_capacity: 1024,
 		}
 	}
 }
@@ -79,17 +115,23 @@ pub struct AudioManager<B: Backend> {
 	context: Arc<Context>,
 	command_producer: CommandProducer,
 	resource_controllers: ResourceControllers,
+	track_graph: Arc<Mutex<TrackGraph>>,
+	main_track_shared: Arc<TrackShared>,
 }
 
 impl<B: Backend> AudioManager<B> {
 	/// Creates a new [`AudioManager`].
 	pub fn new(settings: AudioManagerSettings, mut backend: B) -> Result<Self, B::InitError> {
 		let sample_rate = backend.sample_rate();
-		let context = Arc::new(Context::new(sample_rate));
+		let context = Arc::new(Context::new(
+			sample_rate,
+			settings.latency_compensation_capacity,
+		));
 		let (unused_resource_producers, unused_resource_collector) =
 			create_unused_resource_channels(&settings);
 		let (resources, resource_controllers) =
 			create_resources(&settings, unused_resource_producers, &context);
+		let main_track_shared = resources.mixer.main_track_shared();
 		let (command_producer, command_consumer) =
 			RingBuffer::new(settings.command_capacity).split();
 		let renderer = Renderer::new(context.clone(), resources, command_consumer);
@@ -99,6 +141,8 @@ impl<B: Backend> AudioManager<B> {
 			context,
 			command_producer: CommandProducer::new(command_producer),
 			resource_controllers,
+			track_graph: Arc::new(Mutex::new(TrackGraph::new())),
+			main_track_shared,
 		})
 	}
 
@@ -107,6 +151,20 @@ impl<B: Backend> AudioManager<B> {
 		&mut self.backend
 	}
 
+	/// Returns `true` if the main track limiter has turned down
+	/// the output since the last time this function was called.
+	pub fn main_track_limiter_engaged(&self) -> bool {
+		self.context.take_main_track_limiter_engaged()
+	}
+
+	/// Returns the latest levels of the main mixer track's output,
+	/// measured before the main track limiter.
+	///
+	/// The meter is updated every 100 milliseconds.
+	pub fn main_track_meter(&self) -> TrackMeter {
+		self.main_track_shared.meter()
+	}
+
 	/// Returns the current playback state of the [`Renderer`].
 	pub fn state(&self) -> RendererState {
 		self.context.state()
@@ -155,25 +213,145 @@ impl<B: Backend> AudioManager<B> {
 		Ok(handle)
 	}
 
+	/// Creates a low frequency oscillator that can be used
+	/// as a source for [`Value`]s.
+	///
+	/// Returns an error if the rate or depth is linked to
+	/// another modulator.
+	pub fn add_lfo(&mut self, settings: LfoSettings) -> Result<LfoHandle, AddModulatorError> {
+		if settings.is_linked_to_modulator() {
+			return Err(AddModulatorError::LinkedToModulator);
+		}
+		let id = ModulatorId(
+			self.resource_controllers
+				.modulator_controller
+				.try_reserve()
+				.map_err(|_| AddModulatorError::ModulatorLimitReached)?,
+		);
+		let modulator = Modulator::new_lfo(settings);
+		let handle = LfoHandle {
+			id,
+			shared: modulator.shared(),
+			command_producer: self.command_producer.clone(),
+		};
+		self.command_producer
+			.push(Command::Modulator(ModulatorCommand::Add(id, modulator)))?;
+		Ok(handle)
+	}
+
+	/// Creates an ADSR envelope that can be used as a source
+	/// for [`Value`]s.
+	///
+	/// Returns an error if any of the settings are linked to
+	/// another modulator.
+	pub fn add_envelope(
+		&mut self,
+		settings: EnvelopeSettings,
+	) -> Result<EnvelopeHandle, AddModulatorError> {
+		if settings.is_linked_to_modulator() {
+			return Err(AddModulatorError::LinkedToModulator);
+		}
+		let id = ModulatorId(
+			self.resource_controllers
+				.modulator_controller
+				.try_reserve()
+				.map_err(|_| AddModulatorError::ModulatorLimitReached)?,
+		);
+		let modulator = Modulator::new_envelope(settings);
+		let handle = EnvelopeHandle {
+			id,
+			shared: modulator.shared(),
+			command_producer: self.command_producer.clone(),
+		};
+		self.command_producer
+			.push(Command::Modulator(ModulatorCommand::Add(id, modulator)))?;
+		Ok(handle)
+	}
+
+	/// Creates an effect output, a modulator whose value is
+	/// set by an effect, that can be used as a source for
+	/// [`Value`]s.
+	///
+	/// Effects can set the value of the effect output using
+	/// [`Parameters::publish`](crate::manager::resources::Parameters::publish).
+	pub fn add_effect_output(&mut self) -> Result<EffectOutputHandle, AddModulatorError> {
+		let id = ModulatorId(
+			self.resource_controllers
+				.modulator_controller
+				.try_reserve()
+				.map_err(|_| AddModulatorError::ModulatorLimitReached)?,
+		);
+		let modulator = Modulator::new_effect_output();
+		let handle = EffectOutputHandle {
+			id,
+			shared: modulator.shared(),
+		};
+		self.command_producer
+			.push(Command::Modulator(ModulatorCommand::Add(id, modulator)))?;
+		Ok(handle)
+	}
+
 	/// Creates a mixer sub-track.
+	///
+	/// Returns an error if any of the track's routes lead to
+	/// a track that doesn't exist.
 	pub fn add_sub_track(
 		&mut self,
 		settings: TrackSettings,
 	) -> Result<TrackHandle, AddSubTrackError> {
+		let mut track_graph = self
+			.track_graph
+			.lock()
+			.map_err(|_| CommandError::MutexPoisoned)?;
+		let destinations = settings.routes.destinations().collect::<Vec<_>>();
+		// a new track doesn't have any routes leading to it, so as long
+		// as it's only routed to existing tracks, it can't be part of
+		// a routing cycle
+		if !destinations
+			.iter()
+			.all(|destination| track_graph.contains(*destination))
+		{
+			return Err(AddSubTrackError::NonexistentTrack);
+		}
 		let id = SubTrackId(
 			self.resource_controllers
 				.sub_track_controller
 				.try_reserve()
 				.map_err(|_| AddSubTrackError::SubTrackLimitReached)?,
 		);
+		let smoothing = settings.smoothing;
 		let sub_track = Track::new(settings, &self.context);
 		let handle = TrackHandle {
 			id: TrackId::Sub(id),
 			shared: sub_track.shared(),
 			command_producer: self.command_producer.clone(),
+			track_graph: self.track_graph.clone(),
+			smoothing,
+			latency_compensation_capacity: self.context.latency_compensation_capacity(),
 		};
 		self.command_producer
 			.push(Command::Mixer(MixerCommand::AddSubTrack(id, sub_track)))?;
+		track_graph.add_track(TrackId::Sub(id), handle.shared.clone(), destinations);
+		Ok(handle)
+	}
+
+	/// Creates a VCA, which scales the volume of every mixer
+	/// track assigned to it.
+	pub fn add_vca(&mut self, volume: impl Into<Value>) -> Result<VcaHandle, AddVcaError> {
+		let id = VcaId(
+			self.resource_controllers
+				.vca_controller
+				.try_reserve()
+				.map_err(|_| AddVcaError::VcaLimitReached)?,
+		);
+		let vca = Vca::new(volume.into());
+		let handle = VcaHandle {
+			id,
+			shared: vca.shared(),
+			command_producer: self.command_producer.clone(),
+		};
+		self.command_producer
+			.push(Command::Mixer(MixerCommand::AddVca(id, vca)))?;
 		Ok(handle)
 	}
 
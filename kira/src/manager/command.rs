@@ -3,13 +3,14 @@ pub mod producer;
 use crate::{
 	audio_stream::{AudioStreamId, AudioStreamWrapper},
 	clock::{Clock, ClockId},
-	parameter::{Parameter, ParameterId, Tween},
+	modulator::{Modulator, ModulatorId},
+	parameter::{Automation, Parameter, ParameterId, Tween},
 	sound::{
 		instance::{Instance, InstanceId},
 		wrapper::SoundWrapper,
 		SoundId,
 	},
-	track::{SubTrackId, Track, TrackId},
+	track::{Route, SendPoint, SubTrackId, Track, TrackId, Vca, VcaId},
 	value::Value,
 };
 
@@ -19,9 +20,9 @@ pub(crate) enum SoundCommand {
 
 pub(crate) enum InstanceCommand {
 	Add(InstanceId, Instance),
-	SetVolume(InstanceId, Value),
-	SetPlaybackRate(InstanceId, Value),
-	SetPanning(InstanceId, Value),
+	SetVolume(InstanceId, Value, Option<Tween>),
+	SetPlaybackRate(InstanceId, Value, Option<Tween>),
+	SetPanning(InstanceId, Value, Option<Tween>),
 	Pause { id: InstanceId, tween: Tween },
 	Resume { id: InstanceId, tween: Tween },
 	Stop { id: InstanceId, tween: Tween },
@@ -36,19 +37,59 @@ pub(crate) enum ParameterCommand {
 		target: f64,
 		tween: Tween,
 	},
+	SetAutomation {
+		id: ParameterId,
+		automation: Automation,
+	},
+	CancelAutomation(ParameterId),
 	Pause(ParameterId),
 	Resume(ParameterId),
 }
 
+pub(crate) enum ModulatorCommand {
+	Add(ModulatorId, Modulator),
+	SetLfoRate(ModulatorId, Value),
+	SetLfoDepth(ModulatorId, Value),
+	TriggerEnvelope(ModulatorId),
+	ReleaseEnvelope(ModulatorId),
+}
+
 pub(crate) enum MixerCommand {
 	AddSubTrack(SubTrackId, Track),
-	SetTrackVolume(TrackId, Value),
-	SetTrackPanning(TrackId, Value),
+	SetTrackVolume(TrackId, Value, Option<Tween>),
+	SetTrackPanning(TrackId, Value, Option<Tween>),
+	SetTrackMuted(TrackId, bool),
+	SetTrackSoloed(TrackId, bool),
+	SetTrackSoloSafe(TrackId, bool),
+	SetTrackVca(TrackId, Option<VcaId>),
+	AddVca(VcaId, Vca),
+	SetVcaVolume(VcaId, Value, Option<Tween>),
+	SetRoute {
+		track: TrackId,
+		destination: TrackId,
+		amount: Value,
+		tween: Option<Tween>,
+		send_point: SendPoint,
+	},
+	/// Adds a route to a track. `routes` is an empty list with
+	/// enough capacity for the track's new routes.
+	AddRoute {
+		track: TrackId,
+		route: Route,
+		routes: Vec<Route>,
+	},
+	/// Removes a route from a track. `routes` is an empty list
+	/// with enough capacity for the track's remaining routes.
+	RemoveRoute {
+		track: TrackId,
+		destination: TrackId,
+		routes: Vec<Route>,
+	},
 }
 
 pub(crate) enum ClockCommand {
 	Add(ClockId, Clock),
-	SetInterval(ClockId, Value),
+	SetInterval(ClockId, Value, Option<Tween>),
 	Start(ClockId),
 	Pause(ClockId),
 	Stop(ClockId),
@@ -62,6 +103,7 @@ pub(crate) enum Command {
 	Sound(SoundCommand),
 	Instance(InstanceCommand),
 	Parameter(ParameterCommand),
+	Modulator(ModulatorCommand),
 	Mixer(MixerCommand),
 	Clock(ClockCommand),
 	AudioStream(AudioStreamCommand),
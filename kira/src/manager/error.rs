@@ -75,11 +75,51 @@ impl From<CommandError> for AddParameterError {
 	}
 }
 
+/// Errors that can occur when creating a modulator.
+#[derive(Debug)]
+pub enum AddModulatorError {
+	/// Could not add a modulator because the maximum number of modulators has been reached.
+	ModulatorLimitReached,
+	/// Could not add a modulator because one of its settings
+	/// is linked to another modulator.
+	LinkedToModulator,
+	/// An error occured when sending a command to the renderer.
+	CommandError(CommandError),
+}
+
+impl Display for AddModulatorError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			AddModulatorError::ModulatorLimitReached => f.write_str("Could not add a modulator because the maximum number of modulators has been reached."),
+			AddModulatorError::LinkedToModulator => f.write_str("Could not add a modulator because one of its settings is linked to another modulator."),
+			AddModulatorError::CommandError(error) => error.fmt(f),
+		}
+	}
+}
+
+impl Error for AddModulatorError {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		match self {
+			AddModulatorError::CommandError(error) => Some(error),
+			_ => None,
+		}
+	}
+}
+
+impl From<CommandError> for AddModulatorError {
+	fn from(v: CommandError) -> Self {
+		Self::CommandError(v)
+	}
+}
+
 /// Errors that can occur when creating a mixer sub-track.
 #[derive(Debug)]
 pub enum AddSubTrackError {
 	/// Could not add a sub-track because the maximum number of sub-tracks has been reached.
 	SubTrackLimitReached,
+	/// Could not add a sub-track because one of its routes
+	/// leads to a track that doesn't exist.
+	NonexistentTrack,
 	/// An error occured when sending a command to the renderer.
 	CommandError(CommandError),
 }
@@ -88,6 +128,7 @@ impl Display for AddSubTrackError {
 	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
 		match self {
 			AddSubTrackError::SubTrackLimitReached => f.write_str("Could not add a sub-track because the maximum number of sub-tracks has been reached."),
+			AddSubTrackError::NonexistentTrack => f.write_str("Could not add a sub-track because one of its routes leads to a track that doesn't exist."),
 			AddSubTrackError::CommandError(error) => error.fmt(f),
 		}
 	}
@@ -108,6 +149,41 @@ impl From<CommandError> for AddSubTrackError {
 	}
 }
 
+/// Errors that can occur when creating a VCA.
+#[derive(Debug)]
+pub enum AddVcaError {
+	/// Could not add a VCA because the maximum number of VCAs has been reached.
+	VcaLimitReached,
+	/// An error occured when sending a command to the renderer.
+	CommandError(CommandError),
+}
+
+impl Display for AddVcaError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			AddVcaError::VcaLimitReached => f.write_str(
+				"Could not add a VCA because the maximum number of VCAs has been reached.",
+			),
+			AddVcaError::CommandError(error) => error.fmt(f),
+		}
+	}
+}
+
+impl Error for AddVcaError {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		match self {
+			AddVcaError::CommandError(error) => Some(error),
+			_ => None,
+		}
+	}
+}
+
+impl From<CommandError> for AddVcaError {
+	fn from(v: CommandError) -> Self {
+		Self::CommandError(v)
+	}
+}
+
 /// Errors that can occur when creating a clock.
 #[derive(Debug)]
 pub enum AddClockError {
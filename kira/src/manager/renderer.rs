@@ -79,6 +79,9 @@ impl Renderer {
 				Command::Sound(command) => self.resources.sounds.run_command(command),
 				Command::Instance(command) => self.resources.instances.run_command(command),
 				Command::Parameter(command) => self.resources.parameters.run_command(command),
+				Command::Modulator(command) => {
+					self.resources.parameters.run_modulator_command(command)
+				}
 				Command::Mixer(command) => self.resources.mixer.run_command(command),
 				Command::Clock(command) => self.resources.clocks.run_command(command),
 				Command::AudioStream(command) => self.resources.audio_streams.run_command(command),
@@ -134,10 +137,11 @@ impl Renderer {
 			&self.resources.parameters,
 			&mut self.resources.mixer,
 		);
-		let out = self
-			.resources
-			.mixer
-			.process(self.context.dt, &self.resources.parameters);
+		let out = self.resources.mixer.process(
+			self.context.dt,
+			&self.resources.parameters,
+			&self.resources.clocks,
+		);
 		out * self.fade_volume.value() as f32
 	}
 }
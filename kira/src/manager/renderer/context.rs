@@ -1,19 +1,23 @@
-use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
 
 use super::RendererState;
 
 pub(crate) struct Context {
 	pub(super) sample_rate: u32,
 	pub(super) dt: f64,
+	latency_compensation_capacity: usize,
 	pub(super) state: AtomicU8,
+	main_track_limiter_engaged: AtomicBool,
 }
 
 impl Context {
-	pub fn new(sample_rate: u32) -> Self {
+	pub fn new(sample_rate: u32, latency_compensation_capacity: usize) -> Self {
 		Self {
 			sample_rate,
 			dt: 1.0 / sample_rate as f64,
+			latency_compensation_capacity,
 			state: AtomicU8::new(RendererState::Playing as u8),
+			main_track_limiter_engaged: AtomicBool::new(false),
 		}
 	}
 
@@ -21,7 +25,25 @@ impl Context {
 		self.sample_rate
 	}
 
+	/// Returns the maximum number of frames a mixer track's
+	/// input can be delayed by to compensate for effect latency.
+	pub fn latency_compensation_capacity(&self) -> usize {
+		self.latency_compensation_capacity
+	}
+
 	pub fn state(&self) -> RendererState {
 		RendererState::from_u8(self.state.load(Ordering::SeqCst))
 	}
+
+	pub fn set_main_track_limiter_engaged(&self) {
+		self.main_track_limiter_engaged
+			.store(true, Ordering::SeqCst);
+	}
+
+	/// Returns whether the main track limiter has engaged since
+	/// the last time this function was called.
+	pub fn take_main_track_limiter_engaged(&self) -> bool {
+		self.main_track_limiter_engaged
+			.swap(false, Ordering::SeqCst)
+	}
 }
@@ -19,9 +19,10 @@ use ringbuf::{Consumer, Producer, RingBuffer};
 use crate::{
 	audio_stream::AudioStreamWrapper,
 	clock::Clock,
-	parameter::Parameter,
+	modulator::Modulator,
+	parameter::{Automation, Parameter},
 	sound::{instance::Instance, wrapper::SoundWrapper},
-	track::Track,
+	track::{Route, Track, Vca},
 };
 
 use self::{
@@ -34,7 +35,11 @@ pub(super) struct UnusedResourceProducers {
 	pub sound: Producer<SoundWrapper>,
 	pub instance: Producer<Instance>,
 	pub parameter: Producer<Parameter>,
+	pub modulator: Producer<Modulator>,
+	pub automation: Producer<Automation>,
 	pub sub_track: Producer<Track>,
+	pub route_list: Producer<Vec<Route>>,
+	pub vca: Producer<Vca>,
 	pub clock: Producer<Clock>,
 	pub audio_stream: Producer<AudioStreamWrapper>,
 }
@@ -46,7 +51,11 @@ pub struct UnusedResourceCollector {
 	unused_sound_consumer: Consumer<SoundWrapper>,
 	unused_instance_consumer: Consumer<Instance>,
 	unused_parameter_consumer: Consumer<Parameter>,
+	unused_modulator_consumer: Consumer<Modulator>,
+	unused_automation_consumer: Consumer<Automation>,
 	unused_sub_track_consumer: Consumer<Track>,
+	unused_route_list_consumer: Consumer<Vec<Route>>,
+	unused_vca_consumer: Consumer<Vca>,
 	unused_clock_consumer: Consumer<Clock>,
 	unused_audio_stream_consumer: Consumer<AudioStreamWrapper>,
 }
@@ -57,7 +66,11 @@ impl UnusedResourceCollector {
 		while self.unused_sound_consumer.pop().is_some() {}
 		while self.unused_instance_consumer.pop().is_some() {}
 		while self.unused_parameter_consumer.pop().is_some() {}
+		while self.unused_modulator_consumer.pop().is_some() {}
+		while self.unused_automation_consumer.pop().is_some() {}
 		while self.unused_sub_track_consumer.pop().is_some() {}
+		while self.unused_route_list_consumer.pop().is_some() {}
+		while self.unused_vca_consumer.pop().is_some() {}
 		while self.unused_clock_consumer.pop().is_some() {}
 		while self.unused_audio_stream_consumer.pop().is_some() {}
 	}
@@ -72,8 +85,15 @@ pub(super) fn create_unused_resource_channels(
 		RingBuffer::new(settings.instance_capacity).split();
 	let (unused_parameter_producer, unused_parameter_consumer) =
 		RingBuffer::new(settings.parameter_capacity).split();
+	let (unused_modulator_producer, unused_modulator_consumer) =
+		RingBuffer::new(settings.modulator_capacity).split();
+	let (unused_automation_producer, unused_automation_consumer) =
+		RingBuffer::new(settings.parameter_capacity + settings.command_capacity).split();
 	let (unused_sub_track_producer, unused_sub_track_consumer) =
 		RingBuffer::new(settings.sub_track_capacity).split();
+	let (unused_route_list_producer, unused_route_list_consumer) =
+		RingBuffer::new(settings.command_capacity).split();
+	let (unused_vca_producer, unused_vca_consumer) = RingBuffer::new(settings.vca_capacity).split();
 	let (unused_clock_producer, unused_clock_consumer) =
 		RingBuffer::new(settings.clock_capacity).split();
 	let (unused_audio_stream_producer, unused_audio_stream_consumer) =
@@ -83,7 +103,11 @@ pub(super) fn create_unused_resource_channels(
 			sound: unused_sound_producer,
 			instance: unused_instance_producer,
 			parameter: unused_parameter_producer,
+			modulator: unused_modulator_producer,
+			automation: unused_automation_producer,
 			sub_track: unused_sub_track_producer,
+			route_list: unused_route_list_producer,
+			vca: unused_vca_producer,
 			clock: unused_clock_producer,
 			audio_stream: unused_audio_stream_producer,
 		},
@@ -91,7 +115,11 @@ pub(super) fn create_unused_resource_channels(
 			unused_sound_consumer,
 			unused_instance_consumer,
 			unused_parameter_consumer,
+			unused_modulator_consumer,
+			unused_automation_consumer,
 			unused_sub_track_consumer,
+			unused_route_list_consumer,
+			unused_vca_consumer,
 			unused_clock_consumer,
 			unused_audio_stream_consumer,
 		},
@@ -111,7 +139,9 @@ pub(super) struct ResourceControllers {
 	pub sound_controller: Controller,
 	pub instance_controller: Controller,
 	pub parameter_controller: Controller,
+	pub modulator_controller: Controller,
 	pub sub_track_controller: Controller,
+	pub vca_controller: Controller,
 	pub clock_controller: Controller,
 	pub audio_stream_controller: Controller,
 }
@@ -129,16 +159,25 @@ pub(super) fn create_resources(
 	);
 	let instance_controller = instances.controller();
 	let parameters = Parameters::new(
-		settings.parameter_capacity,
+		1.0 / context.sample_rate() as f64,
+		settings,
 		unused_resource_producers.parameter,
+		unused_resource_producers.modulator,
+		unused_resource_producers.automation,
 	);
 	let parameter_controller = parameters.controller();
+	let modulator_controller = parameters.modulator_controller();
 	let mixer = Mixer::new(
 		settings.sub_track_capacity,
+		settings.main_track_limiter,
 		unused_resource_producers.sub_track,
+		unused_resource_producers.route_list,
+		settings.vca_capacity,
+		unused_resource_producers.vca,
 		context,
 	);
 	let sub_track_controller = mixer.sub_track_controller();
+	let vca_controller = mixer.vca_controller();
 	let clocks = Clocks::new(settings.clock_capacity, unused_resource_producers.clock);
 	let clock_controller = clocks.controller();
 	let audio_streams = AudioStreams::new(
@@ -159,7 +198,9 @@ pub(super) fn create_resources(
 			sound_controller,
 			instance_controller,
 			parameter_controller,
+			modulator_controller,
 			sub_track_controller,
+			vca_controller,
 			clock_controller,
 			audio_stream_controller,
 		},
@@ -1,8 +1,8 @@
-use atomic_arena::{Arena, Controller};
+use atomic_arena::{Arena, Controller, Key};
 use ringbuf::Producer;
 
 use crate::{
-	clock::{Clock, ClockId},
+	clock::{Clock, ClockId, ClockTime},
 	manager::command::ClockCommand,
 };
 
@@ -11,6 +11,7 @@ use super::Parameters;
 pub(crate) struct Clocks {
 	clocks: Arena<Clock>,
 	unused_clock_producer: Producer<Clock>,
+	interval_tweens_to_start: Vec<Key>,
 }
 
 impl Clocks {
@@ -18,6 +19,7 @@ impl Clocks {
 		Self {
 			clocks: Arena::new(capacity),
 			unused_clock_producer,
+			interval_tweens_to_start: Vec::with_capacity(capacity),
 		}
 	}
 
@@ -29,6 +31,10 @@ impl Clocks {
 		self.clocks.get(id.0)
 	}
 
+	pub fn iter(&self) -> impl Iterator<Item = (ClockId, &Clock)> {
+		self.clocks.iter().map(|(key, clock)| (ClockId(key), clock))
+	}
+
 	pub fn on_start_processing(&mut self) {
 		if self.unused_clock_producer.is_full() {
 			return;
@@ -52,9 +58,9 @@ impl Clocks {
 				.clocks
 				.insert_with_key(id.0, clock)
 				.expect("Clock arena is full"),
-			ClockCommand::SetInterval(id, interval) => {
+			ClockCommand::SetInterval(id, interval, tween) => {
 				if let Some(clock) = self.clocks.get_mut(id.0) {
-					clock.set_interval(interval);
+					clock.set_interval(interval, tween);
 				}
 			}
 			ClockCommand::Start(id) => {
@@ -76,6 +82,23 @@ impl Clocks {
 	}
 
 	pub fn update(&mut self, dt: f64, parameters: &Parameters) {
+		// a clock's interval tween can wait on another clock, so
+		// we find all the tweens that are ready to start before
+		// mutably borrowing any clocks
+		for (key, clock) in &self.clocks {
+			if let Some(ClockTime { clock: id, ticks }) = clock.interval_tween_start_time() {
+				if let Some(other_clock) = self.clocks.get(id.0) {
+					if other_clock.ticking() && other_clock.ticks() >= ticks {
+						self.interval_tweens_to_start.push(key);
+					}
+				}
+			}
+		}
+		for key in self.interval_tweens_to_start.drain(..) {
+			if let Some(clock) = self.clocks.get_mut(key) {
+				clock.start_interval_tween();
+			}
+		}
 		for (_, clock) in &mut self.clocks {
 			clock.update(dt, parameters);
 		}
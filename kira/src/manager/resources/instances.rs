@@ -56,19 +56,19 @@ impl Instances {
 					.insert_with_key(id.0, instance)
 					.expect("Instance arena is full");
 			}
-			InstanceCommand::SetVolume(id, volume) => {
+			InstanceCommand::SetVolume(id, volume, tween) => {
 				if let Some(instance) = self.instances.get_mut(id.0) {
-					instance.set_volume(volume);
+					instance.set_volume(volume, tween);
 				}
 			}
-			InstanceCommand::SetPlaybackRate(id, playback_rate) => {
+			InstanceCommand::SetPlaybackRate(id, playback_rate, tween) => {
 				if let Some(instance) = self.instances.get_mut(id.0) {
-					instance.set_playback_rate(playback_rate);
+					instance.set_playback_rate(playback_rate, tween);
 				}
 			}
-			InstanceCommand::SetPanning(id, panning) => {
+			InstanceCommand::SetPanning(id, panning, tween) => {
 				if let Some(instance) = self.instances.get_mut(id.0) {
-					instance.set_panning(panning);
+					instance.set_panning(panning, tween);
 				}
 			}
 			InstanceCommand::Pause { id, tween } => {
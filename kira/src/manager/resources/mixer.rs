@@ -6,39 +6,73 @@ use ringbuf::Producer;
 use crate::{
 	frame::Frame,
 	manager::{command::MixerCommand, context::Context},
-	track::{SubTrackId, Track, TrackId},
-	value::cached::CachedValue,
+	track::{
+		MainTrackLimiter, MainTrackLimiterSettings, Route, SendPoint, SubTrackId, Track, TrackId,
+		TrackShared, Vca, VcaId,
+	},
 };
 
-use super::Parameters;
+use super::{clocks::Clocks, Parameters};
 
 pub(crate) struct Mixer {
 	main_track: Track,
+	main_track_limiter: Option<MainTrackLimiter>,
 	sub_tracks: Arena<Track>,
 	sub_track_ids: Vec<SubTrackId>,
-	dummy_routes: Vec<(TrackId, CachedValue)>,
+	/// The sub-tracks in the order they should be processed, so
+	/// each track is processed before the tracks it's routed to.
+	processing_order: Vec<SubTrackId>,
+	dummy_routes: Vec<Route>,
 	unused_track_producer: Producer<Track>,
+	unused_route_list_producer: Producer<Vec<Route>>,
+	vcas: Arena<Vca>,
+	unused_vca_producer: Producer<Vca>,
 }
 
 impl Mixer {
 	pub fn new(
 		sub_track_capacity: usize,
+		main_track_limiter_settings: Option<MainTrackLimiterSettings>,
 		unused_sub_track_producer: Producer<Track>,
+		unused_route_list_producer: Producer<Vec<Route>>,
+		vca_capacity: usize,
+		unused_vca_producer: Producer<Vca>,
 		context: &Arc<Context>,
 	) -> Self {
 		Self {
 			main_track: Track::new(Default::default(), context),
+			main_track_limiter: main_track_limiter_settings
+				.map(|settings| MainTrackLimiter::new(settings, context)),
 			sub_tracks: Arena::new(sub_track_capacity),
 			sub_track_ids: Vec::with_capacity(sub_track_capacity),
+			processing_order: Vec::with_capacity(sub_track_capacity),
 			dummy_routes: vec![],
 			unused_track_producer: unused_sub_track_producer,
+			unused_route_list_producer,
+			vcas: Arena::new(vca_capacity),
+			unused_vca_producer,
 		}
 	}
 
+	pub fn main_track_shared(&self) -> Arc<TrackShared> {
+		self.main_track.shared()
+	}
+
 	pub fn sub_track_controller(&self) -> Controller {
 		self.sub_tracks.controller()
 	}
 
+	pub fn vca_controller(&self) -> Controller {
+		self.vcas.controller()
+	}
+
+	pub fn track(&self, id: TrackId) -> Option<&Track> {
+		match id {
+			TrackId::Main => Some(&self.main_track),
+			TrackId::Sub(id) => self.sub_tracks.get(id.0),
+		}
+	}
+
 	pub fn track_mut(&mut self, id: TrackId) -> Option<&mut Track> {
 		match id {
 			TrackId::Main => Some(&mut self.main_track),
@@ -53,65 +87,417 @@ impl Mixer {
 					.insert_with_key(id.0, track)
 					.expect("Sub-track arena is full");
 				self.sub_track_ids.push(id);
+				self.update_routing();
+			}
+			MixerCommand::SetTrackVolume(id, volume, tween) => {
+				if let Some(track) = self.track_mut(id) {
+					track.set_volume(volume, tween);
+				}
 			}
-			MixerCommand::SetTrackVolume(id, volume) => {
+			MixerCommand::SetTrackPanning(id, panning, tween) => {
 				if let Some(track) = self.track_mut(id) {
-					track.set_volume(volume);
+					track.set_panning(panning, tween);
 				}
 			}
-			MixerCommand::SetTrackPanning(id, panning) => {
+			MixerCommand::SetTrackMuted(id, muted) => {
 				if let Some(track) = self.track_mut(id) {
-					track.set_panning(panning);
+					track.set_muted(muted);
 				}
 			}
+			MixerCommand::SetTrackSoloed(id, soloed) => {
+				if let Some(track) = self.track_mut(id) {
+					track.solo_mut().soloed = soloed;
+				}
+				self.update_solo();
+			}
+			MixerCommand::SetTrackSoloSafe(id, solo_safe) => {
+				if let Some(track) = self.track_mut(id) {
+					track.solo_mut().solo_safe = solo_safe;
+				}
+				self.update_solo();
+			}
+			MixerCommand::SetTrackVca(id, vca) => {
+				if let Some(track) = self.track_mut(id) {
+					track.set_vca(vca);
+				}
+			}
+			MixerCommand::AddVca(id, vca) => self
+				.vcas
+				.insert_with_key(id.0, vca)
+				.expect("VCA arena is full"),
+			MixerCommand::SetVcaVolume(id, volume, tween) => {
+				if let Some(vca) = self.vcas.get_mut(id.0) {
+					vca.set_volume(volume, tween);
+				}
+			}
+			MixerCommand::SetRoute {
+				track,
+				destination,
+				amount,
+				tween,
+				send_point,
+			} => {
+				if let Some(route) = self.track_mut(track).and_then(|track| {
+					track
+						.routes_mut()
+						.iter_mut()
+						.find(|route| route.destination == destination)
+				}) {
+					route.amount.set(amount, tween);
+					route.send_point = send_point;
+				}
+			}
+			MixerCommand::AddRoute {
+				track,
+				route,
+				mut routes,
+			} => {
+				routes.push(route);
+				if let Some(track) = self.track_mut(track) {
+					routes.append(track.routes_mut());
+					std::mem::swap(track.routes_mut(), &mut routes);
+				}
+				self.discard_routes(routes);
+				self.update_routing();
+			}
+			MixerCommand::RemoveRoute {
+				track,
+				destination,
+				mut routes,
+			} => {
+				if let Some(track) = self.track_mut(track) {
+					if let Some(index) = track
+						.routes()
+						.iter()
+						.position(|route| route.destination == destination)
+					{
+						// the old list keeps the removed route, so
+						// it's deallocated along with the list
+						let old_routes = track.routes_mut();
+						let last_index = old_routes.len() - 1;
+						old_routes.swap(index, last_index);
+						routes.extend(old_routes.drain(..last_index));
+						std::mem::swap(track.routes_mut(), &mut routes);
+					}
+				}
+				self.discard_routes(routes);
+				self.update_routing();
+			}
 		}
 	}
 
+	/// Sends a list of routes that's no longer used to be
+	/// deallocated on another thread.
+	fn discard_routes(&mut self, routes: Vec<Route>) {
+		// if the unused resource collector hasn't caught up, the
+		// routes are dropped here as a last resort
+		let _ = self.unused_route_list_producer.push(routes);
+	}
+
 	pub fn on_start_processing(&mut self) {
+		let mut removed_tracks = false;
 		let mut i = 0;
 		while i < self.sub_track_ids.len() && !self.unused_track_producer.is_full() {
 			let id = self.sub_track_ids[i];
 			let track = &mut self.sub_tracks[id.0];
 			if track.shared().is_marked_for_removal() {
-				if self
-					.unused_track_producer
-					.push(
-						self.sub_tracks
-							.remove(id.0)
-							.unwrap_or_else(|| panic!("Sub track with ID {:?} does not exist", id)),
-					)
-					.is_err()
-				{
+				let mut track = self
+					.sub_tracks
+					.remove(id.0)
+					.unwrap_or_else(|| panic!("Sub track with ID {:?} does not exist", id));
+				for effect in track.effects_mut() {
+					effect.shutdown();
+				}
+				if self.unused_track_producer.push(track).is_err() {
 					panic!("Unused track producer is full")
 				}
 				self.sub_track_ids.remove(i);
+				removed_tracks = true;
 			} else {
 				i += 1;
 			}
 		}
+		if removed_tracks {
+			self.update_routing();
+		}
+		self.remove_unused_vcas();
+	}
+
+	fn remove_unused_vcas(&mut self) {
+		if self.unused_vca_producer.is_full() {
+			return;
+		}
+		for (_, vca) in self
+			.vcas
+			.drain_filter(|vca| vca.shared().is_marked_for_removal())
+		{
+			if self.unused_vca_producer.push(vca).is_err() {
+				panic!("Unused VCA producer is full")
+			}
+			if self.unused_vca_producer.is_full() {
+				return;
+			}
+		}
+	}
+
+	/// Updates everything that depends on how the tracks
+	/// are routed to each other.
+	fn update_routing(&mut self) {
+		self.update_processing_order();
+		self.update_latencies();
+		self.update_solo();
 	}
 
-	pub fn process(&mut self, dt: f64, parameters: &Parameters) -> Frame {
-		// iterate through the sub-tracks newest to oldest
+	/// Sorts the sub-tracks so that every track is processed
+	/// before the tracks it's routed to.
+	///
+	/// This doesn't allocate, since the processing order has
+	/// enough capacity for every sub-track.
+	fn update_processing_order(&mut self) {
+		// count how many tracks are routed to each track
+		for id in &self.sub_track_ids {
+			self.sub_tracks[id.0].set_unsorted_inputs(0);
+		}
+		for i in 0..self.sub_track_ids.len() {
+			let id = self.sub_track_ids[i];
+			for j in 0..self.sub_tracks[id.0].routes().len() {
+				if let TrackId::Sub(destination) = self.sub_tracks[id.0].routes()[j].destination {
+					if let Some(destination_track) = self.sub_tracks.get_mut(destination.0) {
+						destination_track
+							.set_unsorted_inputs(destination_track.unsorted_inputs() + 1);
+					}
+				}
+			}
+		}
+		// start with the tracks nothing is routed to, newest first
+		self.processing_order.clear();
 		for id in self.sub_track_ids.iter().rev() {
+			if self.sub_tracks[id.0].unsorted_inputs() == 0 {
+				self.processing_order.push(*id);
+			}
+		}
+		// once every track routed to a track has been placed,
+		// that track can be placed too
+		let mut i = 0;
+		while i < self.processing_order.len() {
+			let id = self.processing_order[i];
+			for j in 0..self.sub_tracks[id.0].routes().len() {
+				if let TrackId::Sub(destination) = self.sub_tracks[id.0].routes()[j].destination {
+					if let Some(destination_track) = self.sub_tracks.get_mut(destination.0) {
+						let unsorted_inputs = destination_track.unsorted_inputs() - 1;
+						destination_track.set_unsorted_inputs(unsorted_inputs);
+						if unsorted_inputs == 0 {
+							self.processing_order.push(destination);
+						}
+					}
+				}
+			}
+			i += 1;
+		}
+		// the manager doesn't allow cycles, but if one somehow
+		// exists, the tracks in it should still be processed
+		if self.processing_order.len() < self.sub_track_ids.len() {
+			for id in self.sub_track_ids.iter().rev() {
+				if self.sub_tracks[id.0].unsorted_inputs() > 0 {
+					self.processing_order.push(*id);
+				}
+			}
+		}
+	}
+
+	/// Works out which tracks should be silenced because
+	/// other tracks are soloed.
+	fn update_solo(&mut self) {
+		let any_soloed = self
+			.sub_track_ids
+			.iter()
+			.any(|id| self.sub_tracks[id.0].solo().soloed);
+		for id in &self.sub_track_ids {
+			let solo = self.sub_tracks[id.0].solo_mut();
+			solo.receives_solo = false;
+			solo.feeds_solo = false;
+		}
+		// audio from soloed tracks stays audible on its way to
+		// the main track
+		for i in 0..self.processing_order.len() {
+			let id = self.processing_order[i];
+			let solo = self.sub_tracks[id.0].solo();
+			if !(solo.soloed || solo.receives_solo) {
+				continue;
+			}
+			for j in 0..self.sub_tracks[id.0].routes().len() {
+				if let TrackId::Sub(destination) = self.sub_tracks[id.0].routes()[j].destination {
+					if let Some(destination_track) = self.sub_tracks.get_mut(destination.0) {
+						destination_track.solo_mut().receives_solo = true;
+					}
+				}
+			}
+		}
+		// tracks routed into soloed tracks stay audible too
+		for i in (0..self.processing_order.len()).rev() {
+			let id = self.processing_order[i];
+			let feeds_solo =
+				self.sub_tracks[id.0]
+					.routes()
+					.iter()
+					.any(|route| match route.destination {
+						TrackId::Main => false,
+						TrackId::Sub(destination) => {
+							self.sub_tracks.get(destination.0).map_or(false, |track| {
+								track.solo().soloed || track.solo().feeds_solo
+							})
+						}
+					});
+			self.sub_tracks[id.0].solo_mut().feeds_solo = feeds_solo;
+		}
+		for id in &self.sub_track_ids {
+			let track = &mut self.sub_tracks[id.0];
+			let solo_muted = any_soloed && !track.solo().audible_while_soloing();
+			track.set_solo_muted(solo_muted);
+		}
+	}
+
+	/// Sends the latest volume of the track's VCA to the
+	/// given track.
+	fn update_vca_volume(&mut self, id: TrackId) {
+		let volume = match self.track(id).and_then(|track| track.vca()) {
+			Some(VcaId(key)) => self.vcas.get(key).map_or(1.0, |vca| vca.volume()),
+			None => 1.0,
+		};
+		if let Some(track) = self.track_mut(id) {
+			track.set_vca_volume(volume);
+		}
+	}
+
+	/// Works out how much audio arriving at each track needs to
+	/// be delayed so that audio that took different paths through
+	/// the mixer stays in sync.
+	fn update_latencies(&mut self) {
+		self.main_track.set_input_latency(0);
+		for id in &self.sub_track_ids {
+			self.sub_tracks[id.0].set_input_latency(0);
+		}
+		// each track comes after every track routed to it in the
+		// processing order, so the input latency of each track is
+		// known by the time we reach it
+		for i in 0..self.processing_order.len() {
+			let id = self.processing_order[i];
+			let output_latency = self.sub_tracks[id.0].output_latency();
+			for j in 0..self.sub_tracks[id.0].routes().len() {
+				let destination = self.sub_tracks[id.0].routes()[j].destination;
+				if let Some(destination_track) = self.track_mut(destination) {
+					if output_latency > destination_track.input_latency() {
+						destination_track.set_input_latency(output_latency);
+					}
+				}
+			}
+		}
+		// delay each route so its audio arrives at the same time
+		// as the most delayed audio sent to the destination track
+		for i in 0..self.sub_track_ids.len() {
+			let id = self.sub_track_ids[i];
+			let output_latency = self.sub_tracks[id.0].output_latency();
+			for j in 0..self.sub_tracks[id.0].routes().len() {
+				let destination = self.sub_tracks[id.0].routes()[j].destination;
+				let destination_latency = self
+					.track(destination)
+					.map(|track| track.input_latency())
+					.unwrap_or_default();
+				self.sub_tracks[id.0].routes_mut()[j]
+					.compensation
+					.set_delay(destination_latency.saturating_sub(output_latency));
+			}
+		}
+	}
+
+	/// Sends the latest output of each sidechain track to the
+	/// effects on the given track that use it.
+	fn update_sidechain_inputs(&mut self, id: TrackId) {
+		let num_effects = match self.track(id) {
+			Some(track) => track.effects().len(),
+			None => return,
+		};
+		for i in 0..num_effects {
+			let sidechain = match self.track(id) {
+				Some(track) => track.effects()[i].sidechain(),
+				None => return,
+			};
+			if let Some(sidechain) = sidechain {
+				let input = self
+					.track(sidechain)
+					.map(|track| track.output())
+					.unwrap_or(Frame::ZERO);
+				if let Some(track) = self.track_mut(id) {
+					track.effects_mut()[i].receive_sidechain_input(input);
+				}
+			}
+		}
+	}
+
+	/// Sends the latest output of the track's ducking source
+	/// to the given track.
+	fn update_ducking_input(&mut self, id: TrackId) {
+		let source = match self.track(id).and_then(|track| track.ducking_source()) {
+			Some(source) => source,
+			None => return,
+		};
+		let output = self
+			.track(source)
+			.map(|track| track.output())
+			.unwrap_or(Frame::ZERO);
+		if let Some(track) = self.track_mut(id) {
+			track.receive_ducking_source_output(output);
+		}
+	}
+
+	pub fn process(&mut self, dt: f64, parameters: &Parameters, clocks: &Clocks) -> Frame {
+		for (_, vca) in &mut self.vcas {
+			vca.update(parameters, clocks);
+		}
+		// process each sub-track before the tracks it's routed to
+		for i in 0..self.processing_order.len() {
+			let id = self.processing_order[i];
+			self.update_sidechain_inputs(TrackId::Sub(id));
+			self.update_ducking_input(TrackId::Sub(id));
+			self.update_vca_volume(TrackId::Sub(id));
 			// process the track and get its output
 			let track = self
 				.sub_tracks
 				.get_mut(id.0)
 				.expect("sub track IDs and sub tracks are out of sync");
-			let output = track.process(dt, parameters);
+			let output = track.process(dt, parameters, clocks);
+			let pre_fader_output = track.pre_fader_output();
+			// idle tracks don't have anything to send, unless they're
+			// still sending delayed audio
+			if output == Frame::ZERO
+				&& pre_fader_output == Frame::ZERO
+				&& track
+					.routes()
+					.iter()
+					.all(|route| route.compensation.delay() == 0)
+			{
+				continue;
+			}
 			// temporarily take ownership of its routes. we can't just
 			// borrow the routes because then we can't get mutable
 			// references to the other tracks
 			std::mem::swap(track.routes_mut(), &mut self.dummy_routes);
 			// send the output to the destination tracks
-			for (id, amount) in &self.dummy_routes {
-				let destination_track = match id {
+			for route in &mut self.dummy_routes {
+				let destination_track = match route.destination {
 					TrackId::Main => Some(&mut self.main_track),
 					TrackId::Sub(id) => self.sub_tracks.get_mut(id.0),
 				};
 				if let Some(destination_track) = destination_track {
-					destination_track.add_input(output * amount.get() as f32);
+					let signal = match route.send_point {
+						SendPoint::PreFader => pre_fader_output,
+						SendPoint::PostFader => output,
+					};
+					destination_track.add_routed_input(
+						route
+							.compensation
+							.process(signal * route.amount.get() as f32),
+					);
 				}
 			}
 			// borrow the track again and give it back its routes
@@ -121,6 +507,12 @@ impl Mixer {
 				.expect("sub track IDs and sub tracks are out of sync");
 			std::mem::swap(track.routes_mut(), &mut self.dummy_routes);
 		}
-		self.main_track.process(dt, parameters)
+		self.update_sidechain_inputs(TrackId::Main);
+		self.update_ducking_input(TrackId::Main);
+		let output = self.main_track.process(dt, parameters, clocks);
+		match &mut self.main_track_limiter {
+			Some(limiter) => limiter.process(output),
+			None => output,
+		}
 	}
 }
@@ -2,26 +2,62 @@ use atomic_arena::{Arena, Controller};
 use ringbuf::Producer;
 
 use crate::{
-	manager::command::ParameterCommand,
-	parameter::{Parameter, ParameterId},
+	clock::ClockId,
+	manager::{
+		command::{ModulatorCommand, ParameterCommand},
+		AudioManagerSettings,
+	},
+	modulator::{Modulator, ModulatorId},
+	parameter::{Automation, Parameter, ParameterId},
+	value::Smoothing,
 };
 
 use super::clocks::Clocks;
 
-/// Contains the values of each parameter.
+#[cfg(test)]
+mod test;
+
+#[derive(Debug, Clone, Copy)]
+struct ClockState {
+	ticking: bool,
+	position: f64,
+	interval: f64,
+}
+
+/// Contains the values of each parameter and modulator.
 ///
-/// This is an opaque type that's only useful for passing to
-/// [`CachedValue::update`](crate::value::cached::CachedValue::update).
+/// This is mostly an opaque type that's only useful for passing to
+/// [`CachedValue::update`](crate::value::cached::CachedValue::update),
+/// but it can also be used to sync effects to clocks.
 pub struct Parameters {
+	dt: f64,
+	default_smoothing: Smoothing,
 	parameters: Arena<Parameter>,
+	modulators: Arena<Modulator>,
+	/// The state of each clock as of the last update.
+	clock_states: Vec<(ClockId, ClockState)>,
 	unused_parameter_producer: Producer<Parameter>,
+	unused_modulator_producer: Producer<Modulator>,
+	unused_automation_producer: Producer<Automation>,
 }
 
 impl Parameters {
-	pub(crate) fn new(capacity: usize, unused_parameter_producer: Producer<Parameter>) -> Self {
+	pub(crate) fn new(
+		dt: f64,
+		settings: &AudioManagerSettings,
+		unused_parameter_producer: Producer<Parameter>,
+		unused_modulator_producer: Producer<Modulator>,
+		unused_automation_producer: Producer<Automation>,
+	) -> Self {
 		Self {
-			parameters: Arena::new(capacity),
+			dt,
+			default_smoothing: settings.default_smoothing,
+			parameters: Arena::new(settings.parameter_capacity),
+			modulators: Arena::new(settings.modulator_capacity),
+			clock_states: Vec::with_capacity(settings.clock_capacity),
 			unused_parameter_producer,
+			unused_modulator_producer,
+			unused_automation_producer,
 		}
 	}
 
@@ -29,10 +65,62 @@ impl Parameters {
 		self.parameters.controller()
 	}
 
+	pub(crate) fn modulator_controller(&self) -> Controller {
+		self.modulators.controller()
+	}
+
+	pub(crate) fn dt(&self) -> f64 {
+		self.dt
+	}
+
+	pub(crate) fn default_smoothing(&self) -> Smoothing {
+		self.default_smoothing
+	}
+
 	pub(crate) fn get(&self, id: ParameterId) -> Option<&Parameter> {
 		self.parameters.get(id.0)
 	}
 
+	pub(crate) fn modulator(&self, id: ModulatorId) -> Option<&Modulator> {
+		self.modulators.get(id.0)
+	}
+
+	/// Sets the value of an effect output.
+	///
+	/// Settings linked to the effect output will see the new value
+	/// the next time they're updated. If the modulator with the
+	/// given ID isn't an effect output, this does nothing.
+	pub fn publish(&self, id: ModulatorId, value: f64) {
+		if let Some(effect_output) = self.modulator(id).and_then(Modulator::effect_output) {
+			effect_output.set(value);
+		}
+	}
+
+	/// Returns the elapsed time of a clock in ticks, including
+	/// progress towards the next tick.
+	///
+	/// Returns `None` if the clock doesn't exist or isn't ticking.
+	pub fn clock_position(&self, id: ClockId) -> Option<f64> {
+		self.clock_state(id)
+			.filter(|state| state.ticking)
+			.map(|state| state.position)
+	}
+
+	/// Returns the current duration of time between each tick
+	/// of a clock (in seconds).
+	///
+	/// Returns `None` if the clock doesn't exist.
+	pub fn clock_interval(&self, id: ClockId) -> Option<f64> {
+		self.clock_state(id).map(|state| state.interval)
+	}
+
+	fn clock_state(&self, id: ClockId) -> Option<ClockState> {
+		self.clock_states
+			.iter()
+			.find(|(clock_id, _)| *clock_id == id)
+			.map(|(_, state)| *state)
+	}
+
 	fn remove_unused_parameters(&mut self) {
 		if self.unused_parameter_producer.is_full() {
 			return;
@@ -50,11 +138,32 @@ impl Parameters {
 		}
 	}
 
+	fn remove_unused_modulators(&mut self) {
+		if self.unused_modulator_producer.is_full() {
+			return;
+		}
+		for (_, modulator) in self
+			.modulators
+			.drain_filter(|modulator| modulator.shared().is_marked_for_removal())
+		{
+			if self.unused_modulator_producer.push(modulator).is_err() {
+				panic!("Unused modulator producer is full")
+			}
+			if self.unused_modulator_producer.is_full() {
+				return;
+			}
+		}
+	}
+
 	pub(crate) fn on_start_processing(&mut self) {
 		self.remove_unused_parameters();
+		self.remove_unused_modulators();
 		for (_, parameter) in &self.parameters {
 			parameter.on_start_processing();
 		}
+		for (_, modulator) in &self.modulators {
+			modulator.on_start_processing();
+		}
 	}
 
 	pub(crate) fn run_command(&mut self, command: ParameterCommand) {
@@ -65,7 +174,26 @@ impl Parameters {
 				.expect("Parameter arena is full"),
 			ParameterCommand::Set { id, target, tween } => {
 				if let Some(parameter) = self.parameters.get_mut(id.0) {
-					parameter.set(target, tween)
+					discard_automation(
+						&mut self.unused_automation_producer,
+						parameter.set(target, tween),
+					);
+				}
+			}
+			ParameterCommand::SetAutomation { id, automation } => {
+				if let Some(parameter) = self.parameters.get_mut(id.0) {
+					discard_automation(
+						&mut self.unused_automation_producer,
+						parameter.set_automation(automation),
+					);
+				}
+			}
+			ParameterCommand::CancelAutomation(id) => {
+				if let Some(parameter) = self.parameters.get_mut(id.0) {
+					discard_automation(
+						&mut self.unused_automation_producer,
+						parameter.cancel_automation(),
+					);
 				}
 			}
 			ParameterCommand::Pause(id) => {
@@ -81,9 +209,79 @@ impl Parameters {
 		}
 	}
 
+	pub(crate) fn run_modulator_command(&mut self, command: ModulatorCommand) {
+		match command {
+			ModulatorCommand::Add(id, modulator) => self
+				.modulators
+				.insert_with_key(id.0, modulator)
+				.expect("Modulator arena is full"),
+			ModulatorCommand::SetLfoRate(id, rate) => {
+				if let Some(lfo) = self.modulators.get_mut(id.0).and_then(Modulator::lfo_mut) {
+					lfo.set_rate(rate);
+				}
+			}
+			ModulatorCommand::SetLfoDepth(id, depth) => {
+				if let Some(lfo) = self.modulators.get_mut(id.0).and_then(Modulator::lfo_mut) {
+					lfo.set_depth(depth);
+				}
+			}
+			ModulatorCommand::TriggerEnvelope(id) => {
+				if let Some(envelope) = self
+					.modulators
+					.get_mut(id.0)
+					.and_then(Modulator::envelope_mut)
+				{
+					envelope.trigger();
+				}
+			}
+			ModulatorCommand::ReleaseEnvelope(id) => {
+				if let Some(envelope) = self
+					.modulators
+					.get_mut(id.0)
+					.and_then(Modulator::envelope_mut)
+				{
+					envelope.release();
+				}
+			}
+		}
+	}
+
 	pub(crate) fn update(&mut self, dt: f64, clocks: &Clocks) {
 		for (_, parameter) in &mut self.parameters {
 			parameter.update(dt, clocks);
+			discard_automation(
+				&mut self.unused_automation_producer,
+				parameter.take_finished_automation(),
+			);
+		}
+		for (_, modulator) in &mut self.modulators {
+			modulator.update(dt, &self.parameters, clocks);
 		}
+		// the vec has enough capacity for every clock, so this
+		// won't allocate
+		self.clock_states.clear();
+		self.clock_states.extend(clocks.iter().map(|(id, clock)| {
+			(
+				id,
+				ClockState {
+					ticking: clock.ticking(),
+					position: clock.fractional_position(),
+					interval: clock.interval(),
+				},
+			)
+		}));
+	}
+}
+
+/// Sends an automation that's no longer used back to the gameplay
+/// thread so it isn't deallocated on the audio thread.
+///
+/// The queue has room for one automation per parameter plus one per
+/// command, so it only fills up if the unused resources aren't
+/// collected for a long time. In that case, the automation is dropped
+/// here as a last resort.
+fn discard_automation(producer: &mut Producer<Automation>, automation: Option<Automation>) {
+	if let Some(automation) = automation {
+		producer.push(automation).ok();
 	}
 }
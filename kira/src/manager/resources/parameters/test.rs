@@ -0,0 +1,64 @@
+use ringbuf::{Consumer, RingBuffer};
+
+use crate::{
+	manager::{command::ParameterCommand, resources::clocks::Clocks, AudioManagerSettings},
+	parameter::{Automation, Easing, Parameter, ParameterId},
+};
+
+use super::Parameters;
+
+fn create_parameters() -> (Parameters, ParameterId, Consumer<Automation>) {
+	let settings = AudioManagerSettings::default();
+	let (unused_parameter_producer, _) = RingBuffer::new(1).split();
+	let (unused_modulator_producer, _) = RingBuffer::new(1).split();
+	let (unused_automation_producer, unused_automation_consumer) =
+		RingBuffer::new(settings.command_capacity).split();
+	let mut parameters = Parameters::new(
+		1.0,
+		&settings,
+		unused_parameter_producer,
+		unused_modulator_producer,
+		unused_automation_producer,
+	);
+	let id = ParameterId(parameters.controller().try_reserve().unwrap());
+	parameters.run_command(ParameterCommand::Add(id, Parameter::new(0.0)));
+	(parameters, id, unused_automation_consumer)
+}
+
+fn automation() -> Automation {
+	Automation::new()
+		.with_keyframe(0.0, 0.0, Easing::Linear)
+		.with_keyframe(2.0, 1.0, Easing::Linear)
+}
+
+#[test]
+fn replaced_automations_are_all_sent_to_the_gameplay_thread() {
+	let (mut parameters, id, unused_automation_consumer) = create_parameters();
+	// replace the automation twice before the renderer gets
+	// a chance to collect unused resources
+	for _ in 0..3 {
+		parameters.run_command(ParameterCommand::SetAutomation {
+			id,
+			automation: automation(),
+		});
+	}
+	parameters.on_start_processing();
+	assert_eq!(unused_automation_consumer.len(), 2);
+	parameters.run_command(ParameterCommand::CancelAutomation(id));
+	assert_eq!(unused_automation_consumer.len(), 3);
+}
+
+#[test]
+fn finished_automations_are_sent_to_the_gameplay_thread() {
+	let (mut parameters, id, unused_automation_consumer) = create_parameters();
+	let (unused_clock_producer, _) = RingBuffer::new(1).split();
+	let clocks = Clocks::new(1, unused_clock_producer);
+	parameters.run_command(ParameterCommand::SetAutomation {
+		id,
+		automation: automation(),
+	});
+	parameters.update(1.0, &clocks);
+	assert_eq!(unused_automation_consumer.len(), 0);
+	parameters.update(1.0, &clocks);
+	assert_eq!(unused_automation_consumer.len(), 1);
+}
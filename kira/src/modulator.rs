@@ -0,0 +1,145 @@
+//! Periodic and triggered sources of values for settings.
+//!
+//! Modulators live on the audio thread and produce a new value
+//! every sample, so they can be used for effects like tremolo,
+//! vibrato, and auto-panning without gameplay code having to
+//! update parameters every frame. A modulator can be linked to
+//! any setting that accepts a [`Value`](crate::value::Value),
+//! except for the settings of other modulators.
+//!
+//! Effect outputs are modulators whose values are set by
+//! effects using [`Parameters::publish`](crate::manager::resources::Parameters::publish),
+//! which lets settings react to the audio passing through
+//! a track.
+
+mod effect_output;
+mod envelope;
+mod handle;
+mod lfo;
+
+use effect_output::EffectOutput;
+pub use envelope::*;
+pub use handle::*;
+pub use lfo::*;
+
+use std::sync::{
+	atomic::{AtomicBool, AtomicU64, Ordering},
+	Arc,
+};
+
+use atomic_arena::{Arena, Key};
+
+use crate::{manager::resources::clocks::Clocks, parameter::Parameter};
+
+/// A unique identifier for a modulator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ModulatorId(pub(crate) Key);
+
+pub(crate) struct ModulatorShared {
+	value: AtomicU64,
+	removed: AtomicBool,
+}
+
+impl ModulatorShared {
+	pub fn new(value: f64) -> Self {
+		Self {
+			value: AtomicU64::new(value.to_bits()),
+			removed: AtomicBool::new(false),
+		}
+	}
+
+	pub fn value(&self) -> f64 {
+		f64::from_bits(self.value.load(Ordering::SeqCst))
+	}
+
+	pub fn is_marked_for_removal(&self) -> bool {
+		self.removed.load(Ordering::SeqCst)
+	}
+
+	pub fn mark_for_removal(&self) {
+		self.removed.store(true, Ordering::SeqCst);
+	}
+}
+
+enum ModulatorKind {
+	Lfo(Lfo),
+	Envelope(Envelope),
+	EffectOutput(EffectOutput),
+}
+
+pub(crate) struct Modulator {
+	kind: ModulatorKind,
+	shared: Arc<ModulatorShared>,
+}
+
+impl Modulator {
+	pub fn new_lfo(settings: LfoSettings) -> Self {
+		let lfo = Lfo::new(settings);
+		Self {
+			shared: Arc::new(ModulatorShared::new(lfo.value())),
+			kind: ModulatorKind::Lfo(lfo),
+		}
+	}
+
+	pub fn new_envelope(settings: EnvelopeSettings) -> Self {
+		let envelope = Envelope::new(settings);
+		Self {
+			shared: Arc::new(ModulatorShared::new(envelope.value())),
+			kind: ModulatorKind::Envelope(envelope),
+		}
+	}
+
+	pub fn new_effect_output() -> Self {
+		Self {
+			shared: Arc::new(ModulatorShared::new(0.0)),
+			kind: ModulatorKind::EffectOutput(EffectOutput::new()),
+		}
+	}
+
+	pub fn shared(&self) -> Arc<ModulatorShared> {
+		self.shared.clone()
+	}
+
+	pub fn value(&self) -> f64 {
+		match &self.kind {
+			ModulatorKind::Lfo(lfo) => lfo.value(),
+			ModulatorKind::Envelope(envelope) => envelope.value(),
+			ModulatorKind::EffectOutput(effect_output) => effect_output.value(),
+		}
+	}
+
+	pub fn lfo_mut(&mut self) -> Option<&mut Lfo> {
+		match &mut self.kind {
+			ModulatorKind::Lfo(lfo) => Some(lfo),
+			_ => None,
+		}
+	}
+
+	pub fn envelope_mut(&mut self) -> Option<&mut Envelope> {
+		match &mut self.kind {
+			ModulatorKind::Envelope(envelope) => Some(envelope),
+			_ => None,
+		}
+	}
+
+	pub fn effect_output(&self) -> Option<&EffectOutput> {
+		match &self.kind {
+			ModulatorKind::EffectOutput(effect_output) => Some(effect_output),
+			_ => None,
+		}
+	}
+
+	pub fn on_start_processing(&self) {
+		self.shared
+			.value
+			.store(self.value().to_bits(), Ordering::SeqCst);
+	}
+
+	pub fn update(&mut self, dt: f64, parameters: &Arena<Parameter>, clocks: &Clocks) {
+		match &mut self.kind {
+			ModulatorKind::Lfo(lfo) => lfo.update(dt, parameters, clocks),
+			ModulatorKind::Envelope(envelope) => envelope.update(dt, parameters),
+			ModulatorKind::EffectOutput(_) => {}
+		}
+	}
+}
@@ -0,0 +1,24 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// A modulator whose value is set by an effect.
+pub(crate) struct EffectOutput {
+	/// Effects only get shared access to modulators, so the
+	/// value has to be stored atomically.
+	value: AtomicU64,
+}
+
+impl EffectOutput {
+	pub fn new() -> Self {
+		Self {
+			value: AtomicU64::new(0.0f64.to_bits()),
+		}
+	}
+
+	pub fn value(&self) -> f64 {
+		f64::from_bits(self.value.load(Ordering::Relaxed))
+	}
+
+	pub fn set(&self, value: f64) {
+		self.value.store(value.to_bits(), Ordering::Relaxed);
+	}
+}
@@ -0,0 +1,198 @@
+use atomic_arena::Arena;
+
+use crate::{
+	parameter::Parameter,
+	value::{cached::CachedValue, Value},
+};
+
+/// Settings for an ADSR envelope.
+///
+/// The envelope rises from 0 to 1 over the attack time, falls
+/// to the sustain level over the decay time, holds the sustain
+/// level until it's released, and then falls back to 0 over
+/// the release time.
+///
+/// The settings can be linked to parameters, but not to other
+/// modulators, since modulators can't read each other's values
+/// while they're being updated.
+/// [`AudioManager::add_envelope`](crate::manager::AudioManager::add_envelope)
+/// returns an error if they are.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+	feature = "serde_support",
+	derive(serde::Serialize, serde::Deserialize)
+)]
+#[cfg_attr(feature = "serde_support", serde(default))]
+pub struct EnvelopeSettings {
+	/// How long it takes the envelope to rise from 0 to 1
+	/// after it's triggered (in seconds).
+	pub attack: Value,
+	/// How long it takes the envelope to fall from 1 to the
+	/// sustain level (in seconds).
+	pub decay: Value,
+	/// The level the envelope holds until it's released.
+	pub sustain: Value,
+	/// How long it takes the envelope to fall to 0 after
+	/// it's released (in seconds).
+	pub release: Value,
+}
+
+impl EnvelopeSettings {
+	/// Creates a new [`EnvelopeSettings`] with the default settings.
+	pub fn new() -> Self {
+		Self {
+			attack: Value::Fixed(0.01),
+			decay: Value::Fixed(0.1),
+			sustain: Value::Fixed(1.0),
+			release: Value::Fixed(0.1),
+		}
+	}
+
+	/// Sets how long it takes the envelope to rise from 0 to 1
+	/// after it's triggered (in seconds).
+	pub fn attack(self, attack: impl Into<Value>) -> Self {
+		Self {
+			attack: attack.into(),
+			..self
+		}
+	}
+
+	/// Sets how long it takes the envelope to fall from 1 to the
+	/// sustain level (in seconds).
+	pub fn decay(self, decay: impl Into<Value>) -> Self {
+		Self {
+			decay: decay.into(),
+			..self
+		}
+	}
+
+	/// Sets the level the envelope holds until it's released.
+	pub fn sustain(self, sustain: impl Into<Value>) -> Self {
+		Self {
+			sustain: sustain.into(),
+			..self
+		}
+	}
+
+	/// Sets how long it takes the envelope to fall to 0 after
+	/// it's released (in seconds).
+	pub fn release(self, release: impl Into<Value>) -> Self {
+		Self {
+			release: release.into(),
+			..self
+		}
+	}
+
+	/// Returns `true` if any of the settings are linked
+	/// to a modulator.
+	pub(crate) fn is_linked_to_modulator(&self) -> bool {
+		self.attack.is_modulator()
+			|| self.decay.is_modulator()
+			|| self.sustain.is_modulator()
+			|| self.release.is_modulator()
+	}
+}
+
+impl Default for EnvelopeSettings {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum EnvelopeStage {
+	Idle,
+	Attack,
+	Decay,
+	Sustain,
+	Release { start_value: f64 },
+}
+
+pub(crate) struct Envelope {
+	attack: CachedValue,
+	decay: CachedValue,
+	sustain: CachedValue,
+	release: CachedValue,
+	stage: EnvelopeStage,
+	value: f64,
+}
+
+impl Envelope {
+	pub fn new(settings: EnvelopeSettings) -> Self {
+		Self {
+			attack: CachedValue::new(0.0.., settings.attack, 0.01),
+			decay: CachedValue::new(0.0.., settings.decay, 0.1),
+			sustain: CachedValue::new(0.0..=1.0, settings.sustain, 1.0),
+			release: CachedValue::new(0.0.., settings.release, 0.1),
+			stage: EnvelopeStage::Idle,
+			value: 0.0,
+		}
+	}
+
+	pub fn value(&self) -> f64 {
+		self.value
+	}
+
+	pub fn trigger(&mut self) {
+		self.stage = EnvelopeStage::Attack;
+	}
+
+	pub fn release(&mut self) {
+		if self.stage != EnvelopeStage::Idle {
+			self.stage = EnvelopeStage::Release {
+				start_value: self.value,
+			};
+		}
+	}
+
+	pub fn update(&mut self, dt: f64, parameters: &Arena<Parameter>) {
+		self.attack.update_from_parameters(parameters);
+		self.decay.update_from_parameters(parameters);
+		self.sustain.update_from_parameters(parameters);
+		self.release.update_from_parameters(parameters);
+		let sustain = self.sustain.get();
+		match self.stage {
+			EnvelopeStage::Idle => {}
+			// zero-length stages end immediately instead of dividing by zero
+			EnvelopeStage::Attack => {
+				let attack = self.attack.get();
+				self.value = if attack > 0.0 {
+					self.value + dt / attack
+				} else {
+					1.0
+				};
+				if self.value >= 1.0 {
+					self.value = 1.0;
+					self.stage = EnvelopeStage::Decay;
+				}
+			}
+			EnvelopeStage::Decay => {
+				let decay = self.decay.get();
+				self.value = if decay > 0.0 {
+					self.value - (1.0 - sustain) * dt / decay
+				} else {
+					sustain
+				};
+				if self.value <= sustain {
+					self.value = sustain;
+					self.stage = EnvelopeStage::Sustain;
+				}
+			}
+			EnvelopeStage::Sustain => {
+				self.value = sustain;
+			}
+			EnvelopeStage::Release { start_value } => {
+				let release = self.release.get();
+				self.value = if release > 0.0 {
+					self.value - start_value * dt / release
+				} else {
+					0.0
+				};
+				if self.value <= 0.0 {
+					self.value = 0.0;
+					self.stage = EnvelopeStage::Idle;
+				}
+			}
+		}
+	}
+}
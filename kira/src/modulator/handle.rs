@@ -0,0 +1,188 @@
+use std::{
+	error::Error,
+	fmt::{Display, Formatter},
+	sync::Arc,
+};
+
+use crate::{
+	error::CommandError,
+	manager::command::{producer::CommandProducer, Command, ModulatorCommand},
+	value::Value,
+};
+
+use super::{ModulatorId, ModulatorShared};
+
+/// An error that can occur when changing a modulator's settings.
+#[derive(Debug)]
+pub enum SetModulatorSettingError {
+	/// Could not change the setting because the new value is
+	/// linked to another modulator.
+	LinkedToModulator,
+	/// An error occured when sending a command to the renderer.
+	CommandError(CommandError),
+}
+
+impl Display for SetModulatorSettingError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			SetModulatorSettingError::LinkedToModulator => f.write_str(
+				"Could not change the setting because the new value is linked to another modulator",
+			),
+			SetModulatorSettingError::CommandError(error) => error.fmt(f),
+		}
+	}
+}
+
+impl Error for SetModulatorSettingError {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		match self {
+			SetModulatorSettingError::CommandError(error) => Some(error),
+			_ => None,
+		}
+	}
+}
+
+impl From<CommandError> for SetModulatorSettingError {
+	fn from(v: CommandError) -> Self {
+		Self::CommandError(v)
+	}
+}
+
+/// Controls a low frequency oscillator.
+///
+/// When an [`LfoHandle`] is dropped, the corresponding
+/// oscillator will be removed.
+pub struct LfoHandle {
+	pub(crate) id: ModulatorId,
+	pub(crate) shared: Arc<ModulatorShared>,
+	pub(crate) command_producer: CommandProducer,
+}
+
+impl LfoHandle {
+	/// Returns the unique identifier for the oscillator.
+	pub fn id(&self) -> ModulatorId {
+		self.id
+	}
+
+	/// Returns the current output of the oscillator.
+	pub fn value(&self) -> f64 {
+		self.shared.value()
+	}
+
+	/// Sets how many times the oscillator repeats per second,
+	/// or per tick if the oscillator is synced to a clock.
+	///
+	/// Returns an error if the rate is linked to a modulator.
+	pub fn set_rate(&mut self, rate: impl Into<Value>) -> Result<(), SetModulatorSettingError> {
+		let rate = rate.into();
+		if rate.is_modulator() {
+			return Err(SetModulatorSettingError::LinkedToModulator);
+		}
+		self.command_producer
+			.push(Command::Modulator(ModulatorCommand::SetLfoRate(
+				self.id, rate,
+			)))?;
+		Ok(())
+	}
+
+	/// Sets the amplitude of the oscillation.
+	///
+	/// Returns an error if the depth is linked to a modulator.
+	pub fn set_depth(&mut self, depth: impl Into<Value>) -> Result<(), SetModulatorSettingError> {
+		let depth = depth.into();
+		if depth.is_modulator() {
+			return Err(SetModulatorSettingError::LinkedToModulator);
+		}
+		self.command_producer
+			.push(Command::Modulator(ModulatorCommand::SetLfoDepth(
+				self.id, depth,
+			)))?;
+		Ok(())
+	}
+}
+
+impl Drop for LfoHandle {
+	fn drop(&mut self) {
+		self.shared.mark_for_removal();
+	}
+}
+
+/// Controls an ADSR envelope.
+///
+/// When an [`EnvelopeHandle`] is dropped, the corresponding
+/// envelope will be removed.
+pub struct EnvelopeHandle {
+	pub(crate) id: ModulatorId,
+	pub(crate) shared: Arc<ModulatorShared>,
+	pub(crate) command_producer: CommandProducer,
+}
+
+impl EnvelopeHandle {
+	/// Returns the unique identifier for the envelope.
+	pub fn id(&self) -> ModulatorId {
+		self.id
+	}
+
+	/// Returns the current output of the envelope.
+	pub fn value(&self) -> f64 {
+		self.shared.value()
+	}
+
+	/// Starts the attack stage of the envelope.
+	///
+	/// If the envelope is already active, it will rise from
+	/// its current level rather than restarting from 0.
+	pub fn trigger(&mut self) -> Result<(), CommandError> {
+		self.command_producer
+			.push(Command::Modulator(ModulatorCommand::TriggerEnvelope(
+				self.id,
+			)))
+	}
+
+	/// Starts the release stage of the envelope.
+	pub fn release(&mut self) -> Result<(), CommandError> {
+		self.command_producer
+			.push(Command::Modulator(ModulatorCommand::ReleaseEnvelope(
+				self.id,
+			)))
+	}
+}
+
+impl Drop for EnvelopeHandle {
+	fn drop(&mut self) {
+		self.shared.mark_for_removal();
+	}
+}
+
+/// Reads the value of an effect output.
+///
+/// When an [`EffectOutputHandle`] is dropped, the corresponding
+/// effect output will be removed.
+pub struct EffectOutputHandle {
+	pub(crate) id: ModulatorId,
+	pub(crate) shared: Arc<ModulatorShared>,
+}
+
+impl EffectOutputHandle {
+	/// Returns the unique identifier for the effect output.
+	pub fn id(&self) -> ModulatorId {
+		self.id
+	}
+
+	/// Returns the most recent value published to the effect output.
+	pub fn value(&self) -> f64 {
+		self.shared.value()
+	}
+}
+
+impl Drop for EffectOutputHandle {
+	fn drop(&mut self) {
+		self.shared.mark_for_removal();
+	}
+}
+
+impl From<&EffectOutputHandle> for ModulatorId {
+	fn from(handle: &EffectOutputHandle) -> Self {
+		handle.id()
+	}
+}
@@ -0,0 +1,218 @@
+use std::{
+	collections::hash_map::RandomState,
+	f64::consts::TAU,
+	hash::{BuildHasher, Hasher},
+};
+
+use atomic_arena::Arena;
+
+use crate::{
+	clock::ClockId,
+	manager::resources::clocks::Clocks,
+	parameter::Parameter,
+	value::{cached::CachedValue, Value},
+};
+
+/// The shape of a low frequency oscillator's output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+	feature = "serde_support",
+	derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum Waveform {
+	/// A smooth oscillation between -1 and 1.
+	Sine,
+	/// A linear rise from -1 to 1 and fall back to -1.
+	Triangle,
+	/// Alternates between 1 for the first half of each
+	/// cycle and -1 for the second half.
+	Square,
+	/// A linear rise from -1 to 1 followed by a jump
+	/// back down to -1.
+	Saw,
+	/// Picks a random value between -1 and 1 at the start
+	/// of each cycle and holds it until the next cycle.
+	RandomHold,
+}
+
+impl Default for Waveform {
+	fn default() -> Self {
+		Self::Sine
+	}
+}
+
+/// Settings for a low frequency oscillator.
+///
+/// The rate and depth can be linked to parameters, but not to
+/// other modulators, since modulators can't read each other's
+/// values while they're being updated.
+/// [`AudioManager::add_lfo`](crate::manager::AudioManager::add_lfo)
+/// returns an error if they are.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+	feature = "serde_support",
+	derive(serde::Serialize, serde::Deserialize)
+)]
+#[cfg_attr(feature = "serde_support", serde(default))]
+pub struct LfoSettings {
+	/// The shape of the oscillation.
+	pub waveform: Waveform,
+	/// How many times the oscillator repeats per second,
+	/// or per tick if the oscillator is synced to a clock.
+	pub rate: Value,
+	/// The amplitude of the oscillation. The output of the
+	/// oscillator will be between `-depth` and `depth`.
+	pub depth: Value,
+	/// The clock the oscillator should be synced to, if any.
+	///
+	/// If set, the phase of the oscillator is derived from the
+	/// time of the clock, so it follows tempo changes and only
+	/// moves while the clock is ticking.
+	#[cfg_attr(feature = "serde_support", serde(skip))]
+	pub clock: Option<ClockId>,
+}
+
+impl LfoSettings {
+	/// Creates a new [`LfoSettings`] with the default settings.
+	pub fn new() -> Self {
+		Self {
+			waveform: Waveform::default(),
+			rate: Value::Fixed(1.0),
+			depth: Value::Fixed(1.0),
+			clock: None,
+		}
+	}
+
+	/// Sets the shape of the oscillation.
+	pub fn waveform(self, waveform: Waveform) -> Self {
+		Self { waveform, ..self }
+	}
+
+	/// Sets how many times the oscillator repeats per second,
+	/// or per tick if the oscillator is synced to a clock.
+	pub fn rate(self, rate: impl Into<Value>) -> Self {
+		Self {
+			rate: rate.into(),
+			..self
+		}
+	}
+
+	/// Sets the amplitude of the oscillation.
+	pub fn depth(self, depth: impl Into<Value>) -> Self {
+		Self {
+			depth: depth.into(),
+			..self
+		}
+	}
+
+	/// Sets the clock the oscillator should be synced to, if any.
+	pub fn clock(self, clock: impl Into<Option<ClockId>>) -> Self {
+		Self {
+			clock: clock.into(),
+			..self
+		}
+	}
+
+	/// Returns `true` if any of the settings are linked
+	/// to a modulator.
+	pub(crate) fn is_linked_to_modulator(&self) -> bool {
+		self.rate.is_modulator() || self.depth.is_modulator()
+	}
+}
+
+impl Default for LfoSettings {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+pub(crate) struct Lfo {
+	waveform: Waveform,
+	rate: CachedValue,
+	depth: CachedValue,
+	clock: Option<ClockId>,
+	phase: f64,
+	cycle: i64,
+	random_state: u64,
+	held_value: f64,
+}
+
+impl Lfo {
+	pub fn new(settings: LfoSettings) -> Self {
+		let mut lfo = Self {
+			waveform: settings.waveform,
+			rate: CachedValue::new(.., settings.rate, 1.0),
+			depth: CachedValue::new(.., settings.depth, 1.0),
+			clock: settings.clock,
+			phase: 0.0,
+			cycle: 0,
+			random_state: RandomState::new().build_hasher().finish() | 1,
+			held_value: 0.0,
+		};
+		lfo.held_value = lfo.next_random_value();
+		lfo
+	}
+
+	pub fn set_rate(&mut self, rate: Value) {
+		self.rate.set(rate, None);
+	}
+
+	pub fn set_depth(&mut self, depth: Value) {
+		self.depth.set(depth, None);
+	}
+
+	pub fn value(&self) -> f64 {
+		let phase = self.phase;
+		let x = match self.waveform {
+			Waveform::Sine => (phase * TAU).sin(),
+			Waveform::Triangle => 4.0 * (((phase + 0.75) % 1.0) - 0.5).abs() - 1.0,
+			Waveform::Square => {
+				if phase < 0.5 {
+					1.0
+				} else {
+					-1.0
+				}
+			}
+			Waveform::Saw => 2.0 * phase - 1.0,
+			Waveform::RandomHold => self.held_value,
+		};
+		x * self.depth.get()
+	}
+
+	pub fn update(&mut self, dt: f64, parameters: &Arena<Parameter>, clocks: &Clocks) {
+		self.rate.update_from_parameters(parameters);
+		self.depth.update_from_parameters(parameters);
+		let started_new_cycle;
+		if let Some(clock) = self.clock {
+			let clock = match clocks.get(clock) {
+				Some(clock) => clock,
+				None => return,
+			};
+			if !clock.ticking() {
+				return;
+			}
+			let position = clock.fractional_position() * self.rate.get();
+			let cycle = position.floor() as i64;
+			started_new_cycle = cycle != self.cycle;
+			self.cycle = cycle;
+			self.phase = position.rem_euclid(1.0);
+		} else {
+			let position = self.phase + self.rate.get() * dt;
+			started_new_cycle = !(0.0..1.0).contains(&position);
+			self.phase = position.rem_euclid(1.0);
+		}
+		if started_new_cycle && self.waveform == Waveform::RandomHold {
+			self.held_value = self.next_random_value();
+		}
+	}
+
+	// xorshift64* - good enough for picking modulation values and
+	// doesn't require any allocation on the audio thread
+	fn next_random_value(&mut self) -> f64 {
+		self.random_state ^= self.random_state >> 12;
+		self.random_state ^= self.random_state << 25;
+		self.random_state ^= self.random_state >> 27;
+		let x = self.random_state.wrapping_mul(0x2545_F491_4F6C_DD1D);
+		(x >> 11) as f64 / (1u64 << 53) as f64 * 2.0 - 1.0
+	}
+}
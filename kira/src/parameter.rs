@@ -1,5 +1,6 @@
 //! Tweenable values for controlling settings.
 
+mod automation;
 mod handle;
 mod tween;
 
@@ -15,6 +16,7 @@ use atomic_arena::Key;
 
 use crate::{clock::ClockTime, manager::resources::clocks::Clocks, start_time::StartTime};
 
+pub use automation::*;
 pub use handle::*;
 pub use tween::*;
 
@@ -64,11 +66,19 @@ enum ParameterState {
 		tween: Tween,
 		waiting_to_start: bool,
 	},
+	Automating {
+		automation: Automation,
+		start_value: f64,
+		time: f64,
+		clock_start_position: Option<f64>,
+		waiting_to_start: bool,
+	},
 }
 
 pub(crate) struct Parameter {
 	state: ParameterState,
 	paused: bool,
+	finished_automation: Option<Automation>,
 	value: f64,
 	shared: Arc<ParameterShared>,
 }
@@ -78,6 +88,7 @@ impl Parameter {
 		Self {
 			state: ParameterState::Idle,
 			paused: false,
+			finished_automation: None,
 			value,
 			shared: Arc::new(ParameterShared::new(value)),
 		}
@@ -101,13 +112,47 @@ impl Parameter {
 		self.shared.paused.store(false, Ordering::SeqCst);
 	}
 
-	pub fn set(&mut self, target: f64, tween: Tween) {
-		self.state = ParameterState::Tweening {
+	/// Starts a tween to the target value. Returns the automation
+	/// that was replaced, if any.
+	pub fn set(&mut self, target: f64, tween: Tween) -> Option<Automation> {
+		self.set_state(ParameterState::Tweening {
 			values: self.value..=target,
 			time: 0.0,
 			tween,
 			waiting_to_start: matches!(tween.start_time, StartTime::ClockTime(..)),
-		};
+		})
+	}
+
+	/// Starts following an automation. Returns the automation
+	/// that was replaced, if any.
+	pub fn set_automation(&mut self, automation: Automation) -> Option<Automation> {
+		let waiting_to_start = matches!(automation.start_time, StartTime::ClockTime(..));
+		self.set_state(ParameterState::Automating {
+			automation,
+			start_value: self.value,
+			time: 0.0,
+			clock_start_position: None,
+			waiting_to_start,
+		})
+	}
+
+	/// Stops the current automation and returns it.
+	pub fn cancel_automation(&mut self) -> Option<Automation> {
+		if let ParameterState::Automating { .. } = &self.state {
+			self.set_state(ParameterState::Idle)
+		} else {
+			None
+		}
+	}
+
+	/// Replaces the current state and returns the automation
+	/// that was running, so it can be deallocated off of the
+	/// audio thread.
+	fn set_state(&mut self, state: ParameterState) -> Option<Automation> {
+		match std::mem::replace(&mut self.state, state) {
+			ParameterState::Automating { automation, .. } => Some(automation),
+			_ => None,
+		}
 	}
 
 	pub fn on_start_processing(&self) {
@@ -116,42 +161,91 @@ impl Parameter {
 			.store(self.value.to_bits(), Ordering::SeqCst);
 	}
 
+	/// Takes the automation that finished during the last update
+	/// so it can be deallocated off of the audio thread.
+	pub fn take_finished_automation(&mut self) -> Option<Automation> {
+		self.finished_automation.take()
+	}
+
 	pub fn update(&mut self, dt: f64, clocks: &Clocks) -> JustFinishedTween {
 		if self.paused {
 			return false;
 		}
-		if let ParameterState::Tweening {
-			values,
-			time,
-			tween,
-			waiting_to_start,
-		} = &mut self.state
-		{
-			if *waiting_to_start {
-				if let StartTime::ClockTime(ClockTime { clock, ticks }) = tween.start_time {
-					if let Some(clock) = clocks.get(clock) {
-						if clock.ticking() && clock.ticks() >= ticks {
-							*waiting_to_start = false;
-						}
+		let mut finished = false;
+		match &mut self.state {
+			ParameterState::Idle => {}
+			ParameterState::Tweening {
+				values,
+				time,
+				tween,
+				waiting_to_start,
+			} => {
+				if *waiting_to_start {
+					if !start_time_reached(tween.start_time, clocks) {
+						return false;
 					}
+					*waiting_to_start = false;
+				}
+				*time += dt;
+				if *time >= tween.duration.as_secs_f64() {
+					self.value = *values.end();
+					finished = true;
 				} else {
-					panic!(
-						"waiting_to_start should always be false if the start_time is Immediate"
-					);
+					self.value =
+						values.start() + (values.end() - values.start()) * tween.value(*time);
 				}
 			}
-			if *waiting_to_start {
-				return false;
-			}
-			*time += dt;
-			if *time >= tween.duration.as_secs_f64() {
-				self.value = *values.end();
-				self.state = ParameterState::Idle;
-				return true;
-			} else {
-				self.value = values.start() + (values.end() - values.start()) * tween.value(*time);
+			ParameterState::Automating {
+				automation,
+				start_value,
+				time,
+				clock_start_position,
+				waiting_to_start,
+			} => {
+				if *waiting_to_start {
+					if !start_time_reached(automation.start_time, clocks) {
+						return false;
+					}
+					*waiting_to_start = false;
+				}
+				if let Some(clock_id) = automation.clock {
+					let clock = match clocks.get(clock_id) {
+						Some(clock) if clock.ticking() => clock,
+						_ => return false,
+					};
+					let position = clock.fractional_position();
+					// if the automation was scheduled on the same clock that
+					// times it, count from the exact scheduled tick
+					let start_position =
+						*clock_start_position.get_or_insert(match automation.start_time {
+							StartTime::ClockTime(ClockTime { clock, ticks })
+								if clock == clock_id =>
+							{
+								ticks as f64
+							}
+							_ => position,
+						});
+					*time = (position - start_position).max(0.0);
+				} else {
+					*time += dt;
+				}
+				self.value = automation.value(*time, *start_value);
+				finished = automation.finished(*time);
 			}
 		}
-		false
+		if finished {
+			self.finished_automation = self.set_state(ParameterState::Idle);
+		}
+		finished
+	}
+}
+
+fn start_time_reached(start_time: StartTime, clocks: &Clocks) -> bool {
+	match start_time {
+		StartTime::Immediate => true,
+		StartTime::ClockTime(ClockTime { clock, ticks }) => match clocks.get(clock) {
+			Some(clock) => clock.ticking() && clock.ticks() >= ticks,
+			None => false,
+		},
 	}
 }
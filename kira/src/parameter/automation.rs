@@ -0,0 +1,174 @@
+use crate::{clock::ClockId, start_time::StartTime};
+
+use super::Easing;
+
+/// A point in a parameter's [`Automation`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Keyframe {
+	/// When the parameter should reach the value (in seconds,
+	/// or in ticks if the automation is timed by a clock).
+	pub time: f64,
+	/// The value the parameter should reach.
+	pub value: f64,
+	/// The curve of the motion from the previous keyframe
+	/// to this one.
+	pub easing: Easing,
+}
+
+/// What an [`Automation`] should do after it reaches the
+/// last keyframe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AutomationLoopBehavior {
+	/// Hold the value of the last keyframe and finish.
+	None,
+	/// Jump back to the first keyframe and play the
+	/// keyframes again.
+	Loop,
+	/// Play the keyframes backwards until the first keyframe
+	/// is reached, then play them forwards again.
+	PingPong,
+}
+
+impl Default for AutomationLoopBehavior {
+	fn default() -> Self {
+		Self::None
+	}
+}
+
+/// A sequence of keyframes that a parameter follows over time.
+///
+/// The parameter moves from its current value to the value of the
+/// first keyframe, and then from keyframe to keyframe. When looping,
+/// only the section between the first and last keyframes repeats.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Automation {
+	keyframes: Vec<Keyframe>,
+	pub(super) start_time: StartTime,
+	loop_behavior: AutomationLoopBehavior,
+	pub(super) clock: Option<ClockId>,
+}
+
+impl Automation {
+	/// Creates a new [`Automation`] with no keyframes.
+	pub fn new() -> Self {
+		Self {
+			keyframes: vec![],
+			start_time: StartTime::default(),
+			loop_behavior: AutomationLoopBehavior::default(),
+			clock: None,
+		}
+	}
+
+	/// Adds a keyframe to the automation.
+	///
+	/// Keyframes can be added in any order.
+	pub fn with_keyframe(mut self, time: f64, value: f64, easing: Easing) -> Self {
+		let index = self
+			.keyframes
+			.iter()
+			.position(|keyframe| keyframe.time > time)
+			.unwrap_or(self.keyframes.len());
+		self.keyframes.insert(
+			index,
+			Keyframe {
+				time,
+				value,
+				easing,
+			},
+		);
+		self
+	}
+
+	/// Sets when the automation should start.
+	pub fn start_time(self, start_time: impl Into<StartTime>) -> Self {
+		Self {
+			start_time: start_time.into(),
+			..self
+		}
+	}
+
+	/// Sets what the automation should do after it reaches the
+	/// last keyframe.
+	pub fn loop_behavior(self, loop_behavior: AutomationLoopBehavior) -> Self {
+		Self {
+			loop_behavior,
+			..self
+		}
+	}
+
+	/// Sets the clock the automation should be timed by, if any.
+	///
+	/// If set, keyframe times are measured in ticks of the clock,
+	/// and the automation only advances while the clock is ticking.
+	pub fn clock(self, clock: impl Into<Option<ClockId>>) -> Self {
+		Self {
+			clock: clock.into(),
+			..self
+		}
+	}
+
+	/// Returns the keyframes of the automation, sorted by time.
+	pub fn keyframes(&self) -> &[Keyframe] {
+		&self.keyframes
+	}
+
+	/// Returns `true` if the automation has reached its last keyframe
+	/// and won't change any further.
+	pub(super) fn finished(&self, time: f64) -> bool {
+		match self.keyframes.last() {
+			Some(last) => self.loop_behavior == AutomationLoopBehavior::None && time >= last.time,
+			None => true,
+		}
+	}
+
+	/// Returns the value of the automation at the given time.
+	pub(super) fn value(&self, time: f64, start_value: f64) -> f64 {
+		let time = self.looped_time(time);
+		let mut previous_time = 0.0;
+		let mut previous_value = start_value;
+		for keyframe in &self.keyframes {
+			if time < keyframe.time {
+				let segment_duration = keyframe.time - previous_time;
+				let progress = if segment_duration > 0.0 {
+					(time - previous_time) / segment_duration
+				} else {
+					1.0
+				};
+				return previous_value
+					+ (keyframe.value - previous_value) * keyframe.easing.apply(progress);
+			}
+			previous_time = keyframe.time;
+			previous_value = keyframe.value;
+		}
+		previous_value
+	}
+
+	fn looped_time(&self, time: f64) -> f64 {
+		let (first, last) = match (self.keyframes.first(), self.keyframes.last()) {
+			(Some(first), Some(last)) => (first.time, last.time),
+			_ => return time,
+		};
+		let loop_duration = last - first;
+		if time <= last || loop_duration <= 0.0 {
+			return time;
+		}
+		match self.loop_behavior {
+			AutomationLoopBehavior::None => time,
+			AutomationLoopBehavior::Loop => first + (time - first) % loop_duration,
+			AutomationLoopBehavior::PingPong => {
+				let position = (time - first) % (loop_duration * 2.0);
+				if position < loop_duration {
+					first + position
+				} else {
+					last - (position - loop_duration)
+				}
+			}
+		}
+	}
+}
+
+impl Default for Automation {
+	fn default() -> Self {
+		Self::new()
+	}
+}
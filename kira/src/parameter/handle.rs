@@ -5,7 +5,7 @@ use crate::{
 	manager::command::{producer::CommandProducer, Command, ParameterCommand},
 };
 
-use super::{ParameterId, ParameterShared, Tween};
+use super::{Automation, ParameterId, ParameterShared, Tween};
 
 /// Controls a parameter.
 ///
@@ -56,6 +56,28 @@ impl ParameterHandle {
 				tween,
 			}))
 	}
+
+	/// Makes the parameter follow the keyframes of an [`Automation`].
+	///
+	/// This replaces any tween or automation the parameter is
+	/// currently following. Calling [`set`](ParameterHandle::set)
+	/// will likewise replace the automation.
+	pub fn set_automation(&mut self, automation: Automation) -> Result<(), CommandError> {
+		self.command_producer
+			.push(Command::Parameter(ParameterCommand::SetAutomation {
+				id: self.id,
+				automation,
+			}))
+	}
+
+	/// Stops the automation the parameter is currently following,
+	/// if any, and holds the parameter at its current value.
+	pub fn cancel_automation(&mut self) -> Result<(), CommandError> {
+		self.command_producer
+			.push(Command::Parameter(ParameterCommand::CancelAutomation(
+				self.id,
+			)))
+	}
 }
 
 impl Drop for ParameterHandle {
@@ -1,9 +1,19 @@
-use std::time::Duration;
+use std::{
+	f64::consts::{FRAC_PI_2, PI, TAU},
+	time::Duration,
+};
 
 use crate::start_time::StartTime;
 
+#[cfg(test)]
+mod test;
+
 /// Curves the motion of a [`Tween`].
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(
+	feature = "serde_support",
+	derive(serde::Serialize, serde::Deserialize)
+)]
 pub enum Easing {
 	/// Maintains a constant speed for the duration of the [`Tween`].
 	Linear,
@@ -39,10 +49,79 @@ pub enum Easing {
 	/// for float intensity values at the cost of being more
 	/// CPU intensive.
 	InOutPowf(f64),
+	/// Follows the first quarter of a sine wave, starting slow
+	/// and ending at full speed.
+	InSine,
+	/// Follows the second quarter of a sine wave, starting at
+	/// full speed and slowing down.
+	///
+	/// When used to fade in audio, this keeps the perceived
+	/// loudness of a crossfade constant (an "equal-power" fade).
+	OutSine,
+	/// Follows half of a cosine wave, starting slow, speeding up,
+	/// and then slowing back down.
+	InOutSine,
+	/// Starts very slow and speeds up exponentially.
+	///
+	/// When used to fade in audio, this sounds like a linear
+	/// fade on a decibel scale (covering roughly 60dB).
+	InExpo,
+	/// Starts very fast and slows down exponentially.
+	///
+	/// When used to fade out audio, this sounds like a linear
+	/// fade on a decibel scale (covering roughly 60dB).
+	OutExpo,
+	/// Starts slow, speeds up exponentially, and then slows
+	/// back down exponentially.
+	InOutExpo,
+	/// Winds up with a growing oscillation before snapping
+	/// to the end value.
+	InElastic,
+	/// Overshoots the end value and settles onto it with
+	/// a decaying oscillation.
+	OutElastic,
+	/// Combines [`InElastic`](Easing::InElastic) and
+	/// [`OutElastic`](Easing::OutElastic).
+	InOutElastic,
+	/// Bounces off of the start value with increasing height
+	/// before reaching the end value.
+	InBounce,
+	/// Bounces off of the end value with decreasing height
+	/// until it settles.
+	OutBounce,
+	/// Combines [`InBounce`](Easing::InBounce) and
+	/// [`OutBounce`](Easing::OutBounce).
+	InOutBounce,
+	/// A cubic bezier curve from `(0, 0)` to `(1, 1)` with the
+	/// control points `(x1, y1)` and `(x2, y2)`, in the same
+	/// format as CSS's `cubic-bezier` timing function.
+	///
+	/// `x1` and `x2` should be between 0 and 1.
+	CubicBezier {
+		/// The x coordinate of the first control point.
+		x1: f64,
+		/// The y coordinate of the first control point.
+		y1: f64,
+		/// The x coordinate of the second control point.
+		x2: f64,
+		/// The y coordinate of the second control point.
+		y2: f64,
+	},
+	/// Uses a custom function to curve the motion.
+	///
+	/// The function receives the progress of the [`Tween`]
+	/// from 0 to 1 and should return the eased progress,
+	/// which should be 0 at the start and 1 at the end.
+	/// It will be called on the audio thread, so it should
+	/// not allocate or block.
+	///
+	/// Custom easings can't be serialized.
+	#[cfg_attr(feature = "serde_support", serde(skip))]
+	Custom(fn(f64) -> f64),
 }
 
 impl Easing {
-	fn apply(&self, mut x: f64) -> f64 {
+	pub(crate) fn apply(&self, mut x: f64) -> f64 {
 		match self {
 			Easing::Linear => x,
 			Easing::InPowi(power) => x.powi(*power),
@@ -67,8 +146,162 @@ impl Easing {
 					0.5 * (1.0 - Self::InPowf(*power).apply(x)) + 0.5
 				}
 			}
+			Easing::InSine => 1.0 - (x * FRAC_PI_2).cos(),
+			Easing::OutSine => (x * FRAC_PI_2).sin(),
+			Easing::InOutSine => 0.5 - 0.5 * (x * PI).cos(),
+			Easing::InExpo => {
+				if x <= 0.0 {
+					0.0
+				} else {
+					2.0f64.powf(10.0 * x - 10.0)
+				}
+			}
+			Easing::OutExpo => 1.0 - Self::InExpo.apply(1.0 - x),
+			Easing::InOutExpo => {
+				x *= 2.0;
+				if x < 1.0 {
+					0.5 * Self::InExpo.apply(x)
+				} else {
+					0.5 * Self::OutExpo.apply(x - 1.0) + 0.5
+				}
+			}
+			Easing::InElastic => {
+				if x <= 0.0 {
+					0.0
+				} else if x >= 1.0 {
+					1.0
+				} else {
+					-(2.0f64.powf(10.0 * x - 10.0)) * ((x * 10.0 - 10.75) * TAU / 3.0).sin()
+				}
+			}
+			Easing::OutElastic => 1.0 - Self::InElastic.apply(1.0 - x),
+			Easing::InOutElastic => {
+				x *= 2.0;
+				if x < 1.0 {
+					0.5 * Self::InElastic.apply(x)
+				} else {
+					0.5 * Self::OutElastic.apply(x - 1.0) + 0.5
+				}
+			}
+			Easing::InBounce => 1.0 - Self::OutBounce.apply(1.0 - x),
+			Easing::OutBounce => {
+				const N: f64 = 7.5625;
+				const D: f64 = 2.75;
+				if x < 1.0 / D {
+					N * x * x
+				} else if x < 2.0 / D {
+					x -= 1.5 / D;
+					N * x * x + 0.75
+				} else if x < 2.5 / D {
+					x -= 2.25 / D;
+					N * x * x + 0.9375
+				} else {
+					x -= 2.625 / D;
+					N * x * x + 0.984375
+				}
+			}
+			Easing::InOutBounce => {
+				x *= 2.0;
+				if x < 1.0 {
+					0.5 * Self::InBounce.apply(x)
+				} else {
+					0.5 * Self::OutBounce.apply(x - 1.0) + 0.5
+				}
+			}
+			Easing::CubicBezier { x1, y1, x2, y2 } => {
+				cubic_bezier(bezier_parameter_for_x(x, *x1, *x2), *y1, *y2)
+			}
+			Easing::Custom(function) => function(x),
+		}
+	}
+}
+
+impl PartialEq for Easing {
+	fn eq(&self, other: &Self) -> bool {
+		match (self, other) {
+			(Self::Linear, Self::Linear) => true,
+			(Self::InPowi(a), Self::InPowi(b)) => a == b,
+			(Self::OutPowi(a), Self::OutPowi(b)) => a == b,
+			(Self::InOutPowi(a), Self::InOutPowi(b)) => a == b,
+			(Self::InPowf(a), Self::InPowf(b)) => a == b,
+			(Self::OutPowf(a), Self::OutPowf(b)) => a == b,
+			(Self::InOutPowf(a), Self::InOutPowf(b)) => a == b,
+			(Self::InSine, Self::InSine) => true,
+			(Self::OutSine, Self::OutSine) => true,
+			(Self::InOutSine, Self::InOutSine) => true,
+			(Self::InExpo, Self::InExpo) => true,
+			(Self::OutExpo, Self::OutExpo) => true,
+			(Self::InOutExpo, Self::InOutExpo) => true,
+			(Self::InElastic, Self::InElastic) => true,
+			(Self::OutElastic, Self::OutElastic) => true,
+			(Self::InOutElastic, Self::InOutElastic) => true,
+			(Self::InBounce, Self::InBounce) => true,
+			(Self::OutBounce, Self::OutBounce) => true,
+			(Self::InOutBounce, Self::InOutBounce) => true,
+			(
+				Self::CubicBezier { x1, y1, x2, y2 },
+				Self::CubicBezier {
+					x1: other_x1,
+					y1: other_y1,
+					x2: other_x2,
+					y2: other_y2,
+				},
+			) => x1 == other_x1 && y1 == other_y1 && x2 == other_x2 && y2 == other_y2,
+			// function pointers are compared by address, which isn't
+			// perfectly reliable since the compiler may merge identical
+			// functions or duplicate a function across codegen units
+			(Self::Custom(a), Self::Custom(b)) => *a as usize == *b as usize,
+			_ => false,
+		}
+	}
+}
+
+/// Evaluates one coordinate of a cubic bezier curve from 0 to 1
+/// with the given control points at the parameter `t`.
+fn cubic_bezier(t: f64, p1: f64, p2: f64) -> f64 {
+	let u = 1.0 - t;
+	3.0 * u * u * t * p1 + 3.0 * u * t * t * p2 + t * t * t
+}
+
+/// Finds the parameter `t` at which the x coordinate of a cubic
+/// bezier curve equals `x`.
+///
+/// This uses a few iterations of Newton's method, falling back
+/// to bisection if the slope is too flat to converge.
+fn bezier_parameter_for_x(x: f64, x1: f64, x2: f64) -> f64 {
+	const NEWTON_ITERATIONS: usize = 8;
+	const BISECTION_ITERATIONS: usize = 32;
+	const EPSILON: f64 = 1.0e-7;
+
+	let x = x.clamp(0.0, 1.0);
+	let mut t = x;
+	for _ in 0..NEWTON_ITERATIONS {
+		let error = cubic_bezier(t, x1, x2) - x;
+		if error.abs() < EPSILON {
+			return t;
+		}
+		let u = 1.0 - t;
+		let slope = 3.0 * u * u * x1 + 6.0 * u * t * (x2 - x1) + 3.0 * t * t * (1.0 - x2);
+		if slope.abs() < EPSILON {
+			break;
+		}
+		t -= error / slope;
+	}
+	let (mut lower, mut upper) = (0.0, 1.0);
+	t = x;
+	for _ in 0..BISECTION_ITERATIONS {
+		let error = cubic_bezier(t, x1, x2) - x;
+		if error.abs() < EPSILON {
+			break;
+		}
+		if error > 0.0 {
+			upper = t;
+		} else {
+			lower = t;
 		}
+		t = (lower + upper) * 0.5;
 	}
+	t
 }
 
 impl Default for Easing {
@@ -79,6 +312,11 @@ impl Default for Easing {
 
 /// A movement of one value to another over time.
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+	feature = "serde_support",
+	derive(serde::Serialize, serde::Deserialize)
+)]
+#[cfg_attr(feature = "serde_support", serde(default))]
 pub struct Tween {
 	/// When the motion starts.
 	pub start_time: StartTime,
@@ -0,0 +1,62 @@
+use super::Easing;
+
+fn assert_approximate_eq(a: f64, b: f64) {
+	const ERROR_THRESHOLD: f64 = 1.0e-6;
+	if (a - b).abs() > ERROR_THRESHOLD {
+		assert_eq!(a, b);
+	}
+}
+
+#[test]
+fn easings_start_at_0_and_end_at_1() {
+	for easing in [
+		Easing::Linear,
+		Easing::InPowi(3),
+		Easing::OutPowi(3),
+		Easing::InOutPowi(3),
+		Easing::InPowf(2.5),
+		Easing::OutPowf(2.5),
+		Easing::InOutPowf(2.5),
+		Easing::InSine,
+		Easing::OutSine,
+		Easing::InOutSine,
+		Easing::InExpo,
+		Easing::OutExpo,
+		Easing::InOutExpo,
+		Easing::InElastic,
+		Easing::OutElastic,
+		Easing::InOutElastic,
+		Easing::InBounce,
+		Easing::OutBounce,
+		Easing::InOutBounce,
+		Easing::CubicBezier {
+			x1: 0.42,
+			y1: 0.0,
+			x2: 0.58,
+			y2: 1.0,
+		},
+	] {
+		assert_approximate_eq(easing.apply(0.0), 0.0);
+		assert_approximate_eq(easing.apply(1.0), 1.0);
+	}
+}
+
+#[test]
+fn linear_cubic_bezier_matches_linear_easing() {
+	let easing = Easing::CubicBezier {
+		x1: 0.25,
+		y1: 0.25,
+		x2: 0.75,
+		y2: 0.75,
+	};
+	for i in 0..=10 {
+		let x = i as f64 / 10.0;
+		assert_approximate_eq(easing.apply(x), x);
+	}
+}
+
+#[test]
+fn custom_easing_uses_function() {
+	let easing = Easing::Custom(|x| x * x);
+	assert_approximate_eq(easing.apply(0.5), 0.25);
+}
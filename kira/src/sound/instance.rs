@@ -119,9 +119,11 @@ impl Instance {
 			track: settings.track,
 			start_time: settings.start_time,
 			waiting_to_start: matches!(settings.start_time, StartTime::ClockTime(..)),
-			volume: CachedValue::new(.., settings.volume, 1.0),
-			playback_rate: CachedValue::new(.., settings.playback_rate, 1.0),
-			panning: CachedValue::new(0.0..=1.0, settings.panning, 0.5),
+			volume: CachedValue::new(.., settings.volume, 1.0).with_smoothing(settings.smoothing),
+			playback_rate: CachedValue::new(.., settings.playback_rate, 1.0)
+				.with_smoothing(settings.smoothing),
+			panning: CachedValue::new(0.0..=1.0, settings.panning, 0.5)
+				.with_smoothing(settings.smoothing),
 			reverse: settings.reverse,
 			loop_behavior: settings
 				.loop_behavior
@@ -155,16 +157,16 @@ impl Instance {
 		self.shared.state.store(state as u8, Ordering::SeqCst);
 	}
 
-	pub fn set_volume(&mut self, volume: Value) {
-		self.volume.set(volume);
+	pub fn set_volume(&mut self, volume: Value, tween: Option<Tween>) {
+		self.volume.set(volume, tween);
 	}
 
-	pub fn set_playback_rate(&mut self, playback_rate: Value) {
-		self.playback_rate.set(playback_rate);
+	pub fn set_playback_rate(&mut self, playback_rate: Value, tween: Option<Tween>) {
+		self.playback_rate.set(playback_rate, tween);
 	}
 
-	pub fn set_panning(&mut self, panning: Value) {
-		self.panning.set(panning);
+	pub fn set_panning(&mut self, panning: Value, tween: Option<Tween>) {
+		self.panning.set(panning, tween);
 	}
 
 	pub fn pause(&mut self, fade_out_tween: Tween) {
@@ -251,9 +253,9 @@ impl Instance {
 		};
 		if let Some(frame) = sound.sound.frame_at_position(self.position) {
 			if self.state.is_playing() {
-				self.volume.update(parameters);
-				self.playback_rate.update(parameters);
-				self.panning.update(parameters);
+				self.volume.update_with_clocks(parameters, clocks);
+				self.playback_rate.update_with_clocks(parameters, clocks);
+				self.panning.update_with_clocks(parameters, clocks);
 				let just_finished_fade = self.fade_volume.update(dt, clocks);
 				let out = frame.panned(self.panning.get() as f32)
 					* self.volume.get() as f32
@@ -71,7 +71,15 @@ impl InstanceHandle {
 	}
 
 	/// Sets the volume of the instance.
-	pub fn set_volume(&mut self, volume: impl Into<Value>) -> Result<(), InstanceHandleError> {
+	///
+	/// If a tween is provided, the volume will move to the new
+	/// value following the tween. Otherwise, the instance's
+	/// smoothing will be used.
+	pub fn set_volume(
+		&mut self,
+		volume: impl Into<Value>,
+		tween: impl Into<Option<Tween>>,
+	) -> Result<(), InstanceHandleError> {
 		if self.state() == InstanceState::Stopped {
 			return Err(InstanceHandleError::InstanceStopped);
 		}
@@ -79,6 +87,7 @@ impl InstanceHandle {
 			.push(Command::Instance(InstanceCommand::SetVolume(
 				self.id,
 				volume.into(),
+				tween.into(),
 			)))?;
 		Ok(())
 	}
@@ -88,9 +97,14 @@ impl InstanceHandle {
 	///
 	/// Changing the playback rate will change both the speed
 	/// and the pitch of the sound.
+	///
+	/// If a tween is provided, the playback rate will move to the
+	/// new value following the tween. Otherwise, the instance's
+	/// smoothing will be used.
 	pub fn set_playback_rate(
 		&mut self,
 		playback_rate: impl Into<Value>,
+		tween: impl Into<Option<Tween>>,
 	) -> Result<(), InstanceHandleError> {
 		if self.state() == InstanceState::Stopped {
 			return Err(InstanceHandleError::InstanceStopped);
@@ -99,13 +113,22 @@ impl InstanceHandle {
 			.push(Command::Instance(InstanceCommand::SetPlaybackRate(
 				self.id,
 				playback_rate.into(),
+				tween.into(),
 			)))?;
 		Ok(())
 	}
 
 	/// Sets the panning of the instance, where 0 is hard left
 	/// and 1 is hard right.
-	pub fn set_panning(&mut self, panning: impl Into<Value>) -> Result<(), InstanceHandleError> {
+	///
+	/// If a tween is provided, the panning will move to the new
+	/// value following the tween. Otherwise, the instance's
+	/// smoothing will be used.
+	pub fn set_panning(
+		&mut self,
+		panning: impl Into<Value>,
+		tween: impl Into<Option<Tween>>,
+	) -> Result<(), InstanceHandleError> {
 		if self.state() == InstanceState::Stopped {
 			return Err(InstanceHandleError::InstanceStopped);
 		}
@@ -113,6 +136,7 @@ impl InstanceHandle {
 			.push(Command::Instance(InstanceCommand::SetPanning(
 				self.id,
 				panning.into(),
+				tween.into(),
 			)))?;
 		Ok(())
 	}
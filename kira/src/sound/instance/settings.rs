@@ -1,10 +1,14 @@
 use crate::{
 	loop_behavior::LoopBehavior, parameter::Tween, start_time::StartTime,
-	track::TrackId, value::Value,
+	track::TrackId, value::{Smoothing, Value},
 };
 
 /// The loop behavior for an instance.
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+	feature = "serde_support",
+	derive(serde::Serialize, serde::Deserialize)
+)]
 pub enum InstanceLoopBehavior {
 	/// Use the default loop behavior defined by the sound.
 	DefaultForSound,
@@ -41,6 +45,11 @@ impl Default for InstanceLoopBehavior {
 
 /// Settings for an instance of a sound.
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+	feature = "serde_support",
+	derive(serde::Serialize, serde::Deserialize)
+)]
+#[cfg_attr(feature = "serde_support", serde(default))]
 pub struct InstanceSettings {
 	/// When the instance should start playing.
 	pub start_time: StartTime,
@@ -68,6 +77,13 @@ pub struct InstanceSettings {
 	pub track: TrackId,
 	/// An optional fade-in from silence.
 	pub fade_in_tween: Option<Tween>,
+	/// How the volume, playback rate, and panning move to new
+	/// values after they're changed.
+	///
+	/// If `None`, the default smoothing from the
+	/// [`AudioManagerSettings`](crate::manager::AudioManagerSettings)
+	/// will be used.
+	pub smoothing: Option<Smoothing>,
 }
 
 impl InstanceSettings {
@@ -83,6 +99,7 @@ impl InstanceSettings {
 			loop_behavior: InstanceLoopBehavior::default(),
 			track: TrackId::Main,
 			fade_in_tween: None,
+			smoothing: None,
 		}
 	}
 
@@ -162,6 +179,15 @@ impl InstanceSettings {
 			..self
 		}
 	}
+
+	/// Sets how the volume, playback rate, and panning move to new
+	/// values after they're changed.
+	pub fn smoothing(self, smoothing: impl Into<Option<Smoothing>>) -> Self {
+		Self {
+			smoothing: smoothing.into(),
+			..self
+		}
+	}
 }
 
 impl Default for InstanceSettings {
@@ -1,6 +1,11 @@
 use crate::loop_behavior::LoopBehavior;
 
 /// Settings for a [`StaticSound`](super::StaticSound).
+#[cfg_attr(
+	feature = "serde_support",
+	derive(serde::Serialize, serde::Deserialize)
+)]
+#[cfg_attr(feature = "serde_support", serde(default))]
 pub struct StaticSoundSettings {
 	/// The default loop behavior for the sound, if any.
 	pub default_loop_behavior: Option<LoopBehavior>,
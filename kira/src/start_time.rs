@@ -1,12 +1,20 @@
 use crate::clock::ClockTime;
 
 /// Describes when an action should occur.
+///
+/// With the `serde_support` feature enabled, clock times
+/// can't be serialized.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+	feature = "serde_support",
+	derive(serde::Serialize, serde::Deserialize)
+)]
 pub enum StartTime {
 	/// The action should occur immediately.
 	Immediate,
 	/// The action should occur when a clock reaches a
 	/// specific time.
+	#[cfg_attr(feature = "serde_support", serde(skip))]
 	ClockTime(ClockTime),
 }
 
@@ -1,14 +1,24 @@
 //! Organizes and applies effects to audio.
 
+mod ducking;
 mod effect;
+mod graph;
 mod handle;
+mod latency;
+mod limiter;
+mod meter;
 mod routes;
 mod settings;
+mod vca;
 
+pub use ducking::DuckingSettings;
 pub use effect::*;
 pub use handle::*;
+pub use limiter::MainTrackLimiterSettings;
+pub use meter::TrackMeter;
 pub use routes::*;
 pub use settings::*;
+pub use vca::{VcaHandle, VcaId};
 
 use std::sync::{
 	atomic::{AtomicBool, Ordering},
@@ -17,22 +27,46 @@ use std::sync::{
 
 use atomic_arena::Key;
 
+pub(crate) use ducking::Ducking;
+pub(crate) use graph::TrackGraph;
+pub(crate) use limiter::MainTrackLimiter;
+pub(crate) use routes::{Route, SendPoint};
+pub(crate) use vca::Vca;
+
+use latency::CompensationDelay;
+use meter::{Meter, SharedMeter};
+
 use crate::{
 	frame::Frame,
-	manager::{context::Context, resources::Parameters},
+	manager::{
+		context::Context,
+		resources::{clocks::Clocks, Parameters},
+	},
+	parameter::Tween,
 	value::{cached::CachedValue, Value},
 };
 
+/// The amplitude below which a track's input is considered silent.
+const SILENCE_THRESHOLD: f32 = 1.0e-6;
+
 /// A unique identifier for a mixer sub-track.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct SubTrackId(pub(crate) Key);
 
 /// A unique identifier for a track.
+///
+/// With the `serde_support` feature enabled, only the main
+/// track ID can be serialized.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+	feature = "serde_support",
+	derive(serde::Serialize, serde::Deserialize)
+)]
 pub enum TrackId {
 	/// The main mixer track.
 	Main,
 	/// A sub-track.
+	#[cfg_attr(feature = "serde_support", serde(skip))]
 	Sub(SubTrackId),
 }
 
@@ -50,12 +84,14 @@ impl From<&TrackHandle> for TrackId {
 
 pub(crate) struct TrackShared {
 	removed: AtomicBool,
+	meter: SharedMeter,
 }
 
 impl TrackShared {
 	pub fn new() -> Self {
 		Self {
 			removed: AtomicBool::new(false),
+			meter: SharedMeter::new(),
 		}
 	}
 
@@ -66,15 +102,71 @@ impl TrackShared {
 	pub fn mark_for_removal(&self) {
 		self.removed.store(true, Ordering::SeqCst);
 	}
+
+	/// Returns the latest levels of the track's output.
+	pub fn meter(&self) -> TrackMeter {
+		self.meter.read()
+	}
+}
+
+/// How a track is affected by other tracks being soloed.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct SoloState {
+	pub soloed: bool,
+	pub solo_safe: bool,
+	/// Whether audio from a soloed track is routed into this track.
+	pub receives_solo: bool,
+	/// Whether this track is routed into a soloed track.
+	pub feeds_solo: bool,
+}
+
+impl SoloState {
+	/// Returns `true` if the track should stay audible while
+	/// any track is soloed.
+	pub fn audible_while_soloing(&self) -> bool {
+		self.soloed || self.solo_safe || self.receives_solo || self.feeds_solo
+	}
 }
 
 pub(crate) struct Track {
 	shared: Arc<TrackShared>,
 	volume: CachedValue,
 	panning: CachedValue,
-	routes: Vec<(TrackId, CachedValue)>,
+	routes: Vec<Route>,
 	effects: Vec<Box<dyn Effect>>,
+	ducking: Option<Ducking>,
+	muted: bool,
+	solo: SoloState,
+	/// Whether the track is silenced because other tracks are soloed.
+	solo_muted: bool,
+	/// Fades the track out when it's muted or silenced by
+	/// another track being soloed.
+	mute_volume: CachedValue,
+	vca: Option<VcaId>,
+	/// The latest volume of the track's VCA.
+	vca_volume: f64,
+	/// The total latency (in frames) of the track's effects.
+	effects_latency: usize,
+	/// The latency (in frames) of the audio routed into this
+	/// track from other tracks.
+	input_latency: usize,
+	/// Audio sent directly to this track, like from instances
+	/// or audio streams.
 	input: Frame,
+	/// Delays the direct input to line up with the routed input.
+	input_compensation: CompensationDelay,
+	/// Audio routed into this track from other tracks.
+	routed_input: Frame,
+	/// The output of the track before the volume, ducking,
+	/// and panning are applied.
+	pre_fader_output: Frame,
+	output: Frame,
+	meter: Meter,
+	/// How long (in seconds) the track's input has been silent.
+	silence_duration: f64,
+	/// The number of tracks routed to this track that haven't been
+	/// placed yet while the mixer is sorting its tracks.
+	unsorted_inputs: usize,
 }
 
 impl Track {
@@ -82,13 +174,42 @@ impl Track {
 		for effect in &mut settings.effects {
 			effect.init(context.sample_rate());
 		}
+		let effects_latency = settings.effects.iter().map(|effect| effect.latency()).sum();
+		let latency_compensation_capacity = context.latency_compensation_capacity();
 		Self {
 			shared: Arc::new(TrackShared::new()),
-			volume: CachedValue::new(.., settings.volume, 1.0),
-			panning: CachedValue::new(0.0..=1.0, settings.panning, 0.5),
-			routes: settings.routes.into_vec(),
+			volume: CachedValue::new(.., settings.volume, 1.0).with_smoothing(settings.smoothing),
+			panning: CachedValue::new(0.0..=1.0, settings.panning, 0.5)
+				.with_smoothing(settings.smoothing),
+			routes: settings
+				.routes
+				.into_vec(settings.smoothing, latency_compensation_capacity),
 			effects: settings.effects,
+			ducking: settings.ducking.map(Ducking::new),
+			muted: settings.muted,
+			solo: SoloState {
+				solo_safe: settings.solo_safe,
+				..Default::default()
+			},
+			solo_muted: false,
+			mute_volume: CachedValue::new(
+				0.0..=1.0,
+				Value::Fixed(if settings.muted { 0.0 } else { 1.0 }),
+				1.0,
+			)
+			.with_smoothing(settings.smoothing),
+			vca: settings.vca,
+			vca_volume: 1.0,
+			effects_latency,
+			input_latency: 0,
 			input: Frame::ZERO,
+			input_compensation: CompensationDelay::new(latency_compensation_capacity),
+			routed_input: Frame::ZERO,
+			pre_fader_output: Frame::ZERO,
+			output: Frame::ZERO,
+			meter: Meter::new(context.sample_rate()),
+			silence_duration: 0.0,
+			unsorted_inputs: 0,
 		}
 	}
 
@@ -96,34 +217,180 @@ impl Track {
 		self.shared.clone()
 	}
 
-	pub fn routes_mut(&mut self) -> &mut Vec<(TrackId, CachedValue)> {
+	pub fn routes(&self) -> &[Route] {
+		&self.routes
+	}
+
+	pub fn routes_mut(&mut self) -> &mut Vec<Route> {
 		&mut self.routes
 	}
 
-	pub fn set_volume(&mut self, volume: Value) {
-		self.volume.set(volume);
+	pub fn effects(&self) -> &[Box<dyn Effect>] {
+		&self.effects
+	}
+
+	pub fn effects_mut(&mut self) -> &mut [Box<dyn Effect>] {
+		&mut self.effects
+	}
+
+	pub fn unsorted_inputs(&self) -> usize {
+		self.unsorted_inputs
+	}
+
+	pub fn set_unsorted_inputs(&mut self, unsorted_inputs: usize) {
+		self.unsorted_inputs = unsorted_inputs;
+	}
+
+	/// Returns the track whose output decides when this
+	/// track is turned down, if any.
+	pub fn ducking_source(&self) -> Option<TrackId> {
+		self.ducking.as_ref().map(|ducking| ducking.source())
+	}
+
+	/// Sends the latest output of the ducking source track
+	/// to this track.
+	pub fn receive_ducking_source_output(&mut self, output: Frame) {
+		if let Some(ducking) = &mut self.ducking {
+			ducking.receive_source_output(output);
+		}
+	}
+
+	/// Returns the most recent output of the track before the
+	/// volume, ducking, and panning were applied.
+	pub fn pre_fader_output(&self) -> Frame {
+		self.pre_fader_output
+	}
+
+	/// Returns the most recent output of the track.
+	pub fn output(&self) -> Frame {
+		self.output
+	}
+
+	pub fn set_volume(&mut self, volume: Value, tween: Option<Tween>) {
+		self.volume.set(volume, tween);
 	}
 
-	pub fn set_panning(&mut self, panning: Value) {
-		self.panning.set(panning);
+	pub fn set_panning(&mut self, panning: Value, tween: Option<Tween>) {
+		self.panning.set(panning, tween);
 	}
 
+	pub fn set_muted(&mut self, muted: bool) {
+		let was_silenced = self.silenced();
+		self.muted = muted;
+		self.fade_if_silenced_changed(was_silenced);
+	}
+
+	pub fn solo(&self) -> SoloState {
+		self.solo
+	}
+
+	pub fn solo_mut(&mut self) -> &mut SoloState {
+		&mut self.solo
+	}
+
+	pub fn set_solo_muted(&mut self, solo_muted: bool) {
+		let was_silenced = self.silenced();
+		self.solo_muted = solo_muted;
+		self.fade_if_silenced_changed(was_silenced);
+	}
+
+	/// Returns `true` if the track is muted or silenced by
+	/// another track being soloed.
+	fn silenced(&self) -> bool {
+		self.muted || self.solo_muted
+	}
+
+	fn fade_if_silenced_changed(&mut self, was_silenced: bool) {
+		if self.silenced() != was_silenced {
+			let target = if self.silenced() { 0.0 } else { 1.0 };
+			self.mute_volume.set(Value::Fixed(target), None);
+		}
+	}
+
+	pub fn vca(&self) -> Option<VcaId> {
+		self.vca
+	}
+
+	pub fn set_vca(&mut self, vca: Option<VcaId>) {
+		self.vca = vca;
+	}
+
+	pub fn set_vca_volume(&mut self, volume: f64) {
+		self.vca_volume = volume;
+	}
+
+	/// Returns the latency (in frames) of the audio routed
+	/// into this track.
+	pub fn input_latency(&self) -> usize {
+		self.input_latency
+	}
+
+	/// Sets the latency of the audio routed into this track
+	/// and delays the direct input to match.
+	pub fn set_input_latency(&mut self, latency: usize) {
+		self.input_latency = latency;
+		self.input_compensation.set_delay(latency);
+	}
+
+	/// Returns the latency (in frames) of the track's output.
+	pub fn output_latency(&self) -> usize {
+		self.input_latency + self.effects_latency
+	}
+
+	/// Adds audio from an instance or audio stream to the
+	/// track's input.
 	pub fn add_input(&mut self, input: Frame) {
 		self.input += input;
 	}
 
-	pub fn process(&mut self, dt: f64, parameters: &Parameters) -> Frame {
-		self.volume.update(parameters);
-		self.panning.update(parameters);
-		for (_, amount) in &mut self.routes {
-			amount.update(parameters);
+	/// Adds audio from another mixer track to the track's input.
+	pub fn add_routed_input(&mut self, input: Frame) {
+		self.routed_input += input;
+	}
+
+	pub fn process(&mut self, dt: f64, parameters: &Parameters, clocks: &Clocks) -> Frame {
+		self.volume.update_with_clocks(parameters, clocks);
+		self.panning.update_with_clocks(parameters, clocks);
+		self.mute_volume.update(parameters);
+		for route in &mut self.routes {
+			route.amount.update_with_clocks(parameters, clocks);
+		}
+		let mut output = self
+			.input_compensation
+			.process(std::mem::replace(&mut self.input, Frame::ZERO))
+			+ std::mem::replace(&mut self.routed_input, Frame::ZERO);
+		if output.left.abs() > SILENCE_THRESHOLD || output.right.abs() > SILENCE_THRESHOLD {
+			self.silence_duration = 0.0;
+		} else {
+			self.silence_duration += dt;
 		}
-		let mut output = std::mem::replace(&mut self.input, Frame::ZERO);
+		// the total tail length of the current effect and all of
+		// the effects before it
+		let mut tail_length = 0.0;
 		for effect in &mut self.effects {
+			if self.silence_duration > 0.0 {
+				match effect.tail_length() {
+					Some(effect_tail_length) => tail_length += effect_tail_length,
+					None => tail_length = f64::INFINITY,
+				}
+				// the effect's input has been silent for long enough
+				// that it won't produce any more sound
+				if self.silence_duration > tail_length {
+					output = Frame::ZERO;
+					continue;
+				}
+			}
 			output = effect.process(output, dt, parameters);
 		}
-		output *= self.volume.get() as f32;
+		output *= self.mute_volume.get() as f32;
+		self.pre_fader_output = output;
+		output *= (self.volume.get() * self.vca_volume) as f32;
+		if let Some(ducking) = &mut self.ducking {
+			output *= ducking.process(dt) as f32;
+		}
 		output = output.panned(self.panning.get() as f32);
+		self.output = output;
+		self.meter.process(output, &self.shared.meter);
 		output
 	}
 }
@@ -0,0 +1,120 @@
+use std::time::Duration;
+
+use crate::frame::Frame;
+
+use super::TrackId;
+
+/// Settings for automatically turning down a mixer track
+/// while another track is loud.
+///
+/// For example, a music track can be turned down whenever
+/// the dialog track is playing something.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DuckingSettings {
+	/// The track whose output decides when to turn down
+	/// this track.
+	pub source: TrackId,
+	/// The volume (in decibels) the source track has to exceed
+	/// for this track to be turned down.
+	pub threshold: f64,
+	/// How much (in decibels) to turn down this track.
+	pub amount: f64,
+	/// How long it takes to turn down the track once the
+	/// source track exceeds the threshold.
+	pub attack: Duration,
+	/// How long it takes to turn the track back up once the
+	/// source track falls below the threshold.
+	pub release: Duration,
+}
+
+impl DuckingSettings {
+	/// Creates a new [`DuckingSettings`] with the default settings
+	/// that turns down a track while the `source` track is loud.
+	pub fn new(source: impl Into<TrackId>) -> Self {
+		Self {
+			source: source.into(),
+			threshold: -30.0,
+			amount: 8.0,
+			attack: Duration::from_millis(50),
+			release: Duration::from_millis(400),
+		}
+	}
+
+	/// Sets the volume (in decibels) the source track has to exceed
+	/// for this track to be turned down.
+	pub fn threshold(self, threshold: f64) -> Self {
+		Self { threshold, ..self }
+	}
+
+	/// Sets how much (in decibels) to turn down this track.
+	pub fn amount(self, amount: f64) -> Self {
+		Self { amount, ..self }
+	}
+
+	/// Sets how long it takes to turn down the track once the
+	/// source track exceeds the threshold.
+	pub fn attack(self, attack: Duration) -> Self {
+		Self { attack, ..self }
+	}
+
+	/// Sets how long it takes to turn the track back up once the
+	/// source track falls below the threshold.
+	pub fn release(self, release: Duration) -> Self {
+		Self { release, ..self }
+	}
+}
+
+/// Turns down a track based on the output of another track.
+pub(crate) struct Ducking {
+	settings: DuckingSettings,
+	/// The latest output of the source track.
+	source_output: Frame,
+	/// The current amount (in decibels) the track is turned down by.
+	gain_reduction: f64,
+}
+
+impl Ducking {
+	pub fn new(settings: DuckingSettings) -> Self {
+		Self {
+			settings,
+			source_output: Frame::ZERO,
+			gain_reduction: 0.0,
+		}
+	}
+
+	pub fn source(&self) -> TrackId {
+		self.settings.source
+	}
+
+	pub fn receive_source_output(&mut self, output: Frame) {
+		self.source_output = output;
+	}
+
+	/// Updates the gain reduction and returns the amplitude
+	/// to multiply the track's output by.
+	pub fn process(&mut self, dt: f64) -> f64 {
+		let peak = self
+			.source_output
+			.left
+			.abs()
+			.max(self.source_output.right.abs()) as f64;
+		let level = 20.0 * peak.log10();
+		let target = if level > self.settings.threshold {
+			-self.settings.amount.abs()
+		} else {
+			0.0
+		};
+		let response_time = if target < self.gain_reduction {
+			self.settings.attack
+		} else {
+			self.settings.release
+		}
+		.as_secs_f64();
+		self.gain_reduction = if response_time > 0.0 {
+			target + (self.gain_reduction - target) * (-dt / response_time).exp()
+		} else {
+			target
+		};
+		10.0f64.powf(self.gain_reduction / 20.0)
+	}
+}
@@ -1,11 +1,71 @@
 use crate::{frame::Frame, manager::resources::Parameters};
 
+use super::TrackId;
+
 /// Receives input audio from a mixer track and outputs modified audio.
 #[allow(unused_variables)]
 pub trait Effect: Send + Sync {
 	/// Called when the effect is first sent to the renderer.
 	fn init(&mut self, sample_rate: u32) {}
 
+	/// Returns the mixer track whose output should be sent to
+	/// this effect as a sidechain input, if any.
+	///
+	/// This is checked every time before the effect is processed.
+	fn sidechain(&self) -> Option<TrackId> {
+		None
+	}
+
+	/// Receives the most recent output of the track returned by
+	/// [`Effect::sidechain`]. Called before each call to
+	/// [`Effect::process`] if the effect has a sidechain track.
+	///
+	/// Mixer tracks are processed one at a time, so if the
+	/// sidechain track hasn't been processed yet in the current
+	/// round of processing, this will be the output from the
+	/// previous round.
+	fn receive_sidechain_input(&mut self, input: Frame) {}
+
+	/// Returns how long (in seconds) the effect can keep producing
+	/// sound after its input becomes silent, or `None` if the effect
+	/// should always be processed.
+	///
+	/// Once a track's input has been silent for longer than the
+	/// tail lengths of an effect and all of the effects before it,
+	/// that effect stops being processed until the track receives
+	/// input again. An effect whose tail is still ringing out, like
+	/// a reverb, will keep being processed.
+	///
+	/// This is checked every time the track's input is silent.
+	/// The default implementation returns `None`, so effects that
+	/// don't implement this method are always processed.
+	fn tail_length(&self) -> Option<f64> {
+		None
+	}
+
+	/// Returns how many frames the effect delays its input by.
+	///
+	/// Effects that need to look ahead at their input, like
+	/// look-ahead limiters or effects that process audio in blocks,
+	/// should report their latency here. When audio from a track
+	/// with latency is mixed with audio that didn't pass through
+	/// the same effects, the mixer delays the other audio to keep
+	/// everything in sync.
+	///
+	/// This is checked once after [`Effect::init`] is called.
+	fn latency(&self) -> usize {
+		0
+	}
+
+	/// Called on the audio thread when the effect's track is
+	/// removed, right before the effect is sent back to the
+	/// gameplay thread to be dropped.
+	///
+	/// Effects that have to release resources from the audio
+	/// thread, like plugins that need to stop processing,
+	/// should do that here.
+	fn shutdown(&mut self) {}
+
 	/// Transforms an input [`Frame`].
 	/// - `input` is the input audio
 	/// - `dt` is the time that's elapsed since the previous round of
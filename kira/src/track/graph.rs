@@ -0,0 +1,97 @@
+use std::{
+	collections::{HashMap, HashSet},
+	sync::Arc,
+};
+
+use super::{TrackId, TrackShared};
+
+struct Node {
+	shared: Arc<TrackShared>,
+	destinations: Vec<TrackId>,
+}
+
+/// Keeps track of how mixer sub-tracks are routed to each other
+/// on the gameplay thread, so routes that would create a cycle
+/// can be rejected before they're sent to the renderer.
+pub(crate) struct TrackGraph {
+	nodes: HashMap<TrackId, Node>,
+}
+
+impl TrackGraph {
+	pub fn new() -> Self {
+		Self {
+			nodes: HashMap::new(),
+		}
+	}
+
+	/// Forgets about tracks whose handles have been dropped.
+	fn remove_unused_tracks(&mut self) {
+		self.nodes
+			.retain(|_, node| !node.shared.is_marked_for_removal());
+	}
+
+	/// Returns `true` if the track exists. The main track
+	/// always exists.
+	pub fn contains(&mut self, id: TrackId) -> bool {
+		self.remove_unused_tracks();
+		id == TrackId::Main || self.nodes.contains_key(&id)
+	}
+
+	/// Returns `true` if audio sent to the `from` track can
+	/// reach the `to` track by following routes.
+	fn is_reachable(&self, from: TrackId, to: TrackId) -> bool {
+		let mut stack = vec![from];
+		let mut visited = HashSet::new();
+		while let Some(id) = stack.pop() {
+			if id == to {
+				return true;
+			}
+			if !visited.insert(id) {
+				continue;
+			}
+			if let Some(node) = self.nodes.get(&id) {
+				stack.extend(node.destinations.iter().copied());
+			}
+		}
+		false
+	}
+
+	/// Returns `true` if routing the `source` track to the
+	/// given destinations would create a cycle.
+	pub fn creates_cycle(&mut self, source: TrackId, destinations: &[TrackId]) -> bool {
+		self.remove_unused_tracks();
+		destinations
+			.iter()
+			.any(|destination| self.is_reachable(*destination, source))
+	}
+
+	/// Returns the tracks the given track is routed to.
+	pub fn destinations(&self, id: TrackId) -> &[TrackId] {
+		self.nodes
+			.get(&id)
+			.map(|node| node.destinations.as_slice())
+			.unwrap_or_default()
+	}
+
+	pub fn add_destination(&mut self, id: TrackId, destination: TrackId) {
+		if let Some(node) = self.nodes.get_mut(&id) {
+			node.destinations.push(destination);
+		}
+	}
+
+	pub fn remove_destination(&mut self, id: TrackId, destination: TrackId) {
+		if let Some(node) = self.nodes.get_mut(&id) {
+			node.destinations.retain(|id| *id != destination);
+		}
+	}
+
+	pub fn add_track(&mut self, id: TrackId, shared: Arc<TrackShared>, destinations: Vec<TrackId>) {
+		self.nodes.insert(
+			id,
+			Node {
+				shared,
+				destinations,
+			},
+		);
+	}
+}
@@ -1,12 +1,59 @@
-use std::sync::Arc;
+use std::{
+	error::Error,
+	fmt::Display,
+	sync::{Arc, Mutex},
+};
 
 use crate::{
 	error::CommandError,
 	manager::command::{producer::CommandProducer, Command, MixerCommand},
-	value::Value,
+	parameter::Tween,
+	value::{Smoothing, Value},
 };
 
-use super::{TrackId, TrackShared};
+use super::{Route, SendPoint, TrackGraph, TrackId, TrackMeter, TrackShared, VcaId};
+
+/// An error that can occur when changing a mixer track's routes.
+#[derive(Debug)]
+pub enum SetRouteError {
+	/// Could not set the route because it would create a cycle
+	/// in the mixer.
+	RoutingCycle,
+	/// Could not set the route because the destination
+	/// track doesn't exist.
+	NonexistentTrack,
+	/// An error occured when sending a command to the renderer.
+	CommandError(CommandError),
+}
+
+impl Display for SetRouteError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			SetRouteError::RoutingCycle => {
+				f.write_str("Could not set the route because it would create a cycle in the mixer")
+			}
+			SetRouteError::NonexistentTrack => {
+				f.write_str("Could not set the route because the destination track doesn't exist")
+			}
+			SetRouteError::CommandError(error) => error.fmt(f),
+		}
+	}
+}
+
+impl Error for SetRouteError {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		match self {
+			SetRouteError::CommandError(error) => Some(error),
+			_ => None,
+		}
+	}
+}
+
+impl From<CommandError> for SetRouteError {
+	fn from(v: CommandError) -> Self {
+		Self::CommandError(v)
+	}
+}
 
 /// Controls a mixer track.
 ///
@@ -16,6 +63,9 @@ pub struct TrackHandle {
 	pub(crate) id: TrackId,
 	pub(crate) shared: Arc<TrackShared>,
 	pub(crate) command_producer: CommandProducer,
+	pub(crate) track_graph: Arc<Mutex<TrackGraph>>,
+	pub(crate) smoothing: Option<Smoothing>,
+	pub(crate) latency_compensation_capacity: usize,
 }
 
 impl TrackHandle {
@@ -24,24 +74,205 @@ impl TrackHandle {
 		self.id
 	}
 
+	/// Returns the latest levels of the mixer track's output.
+	///
+	/// The meter is updated every 100 milliseconds.
+	pub fn meter(&self) -> TrackMeter {
+		self.shared.meter()
+	}
+
 	/// Sets the (post-effects) volume of the mixer track.
-	pub fn set_volume(&mut self, volume: impl Into<Value>) -> Result<(), CommandError> {
+	///
+	/// If a tween is provided, the volume will move to the new
+	/// value following the tween. Otherwise, the track's
+	/// smoothing will be used.
+	pub fn set_volume(
+		&mut self,
+		volume: impl Into<Value>,
+		tween: impl Into<Option<Tween>>,
+	) -> Result<(), CommandError> {
 		self.command_producer
 			.push(Command::Mixer(MixerCommand::SetTrackVolume(
 				self.id,
 				volume.into(),
+				tween.into(),
 			)))
 	}
 
 	/// Sets the (post-effects) panning of the mixer track, where
 	/// 0.0 is hard left and 1.0 is hard right.
-	pub fn set_panning(&mut self, panning: impl Into<Value>) -> Result<(), CommandError> {
+	///
+	/// If a tween is provided, the panning will move to the new
+	/// value following the tween. Otherwise, the track's
+	/// smoothing will be used.
+	pub fn set_panning(
+		&mut self,
+		panning: impl Into<Value>,
+		tween: impl Into<Option<Tween>>,
+	) -> Result<(), CommandError> {
 		self.command_producer
 			.push(Command::Mixer(MixerCommand::SetTrackPanning(
 				self.id,
 				panning.into(),
+				tween.into(),
+			)))
+	}
+
+	/// Mutes or unmutes the mixer track.
+	///
+	/// A muted track doesn't send any audio to other tracks,
+	/// including through pre-fader routes. The track fades in
+	/// and out following its smoothing.
+	pub fn set_muted(&mut self, muted: bool) -> Result<(), CommandError> {
+		self.command_producer
+			.push(Command::Mixer(MixerCommand::SetTrackMuted(self.id, muted)))
+	}
+
+	/// Solos or unsolos the mixer track.
+	///
+	/// While any track is soloed, every track is silenced except
+	/// for soloed tracks, tracks that are routed into or receive
+	/// audio from soloed tracks, and solo-safe tracks.
+	pub fn set_soloed(&mut self, soloed: bool) -> Result<(), CommandError> {
+		self.command_producer
+			.push(Command::Mixer(MixerCommand::SetTrackSoloed(
+				self.id, soloed,
+			)))
+	}
+
+	/// Sets whether the mixer track stays audible when other
+	/// tracks are soloed. This is useful for buses like reverb
+	/// returns.
+	pub fn set_solo_safe(&mut self, solo_safe: bool) -> Result<(), CommandError> {
+		self.command_producer
+			.push(Command::Mixer(MixerCommand::SetTrackSoloSafe(
+				self.id, solo_safe,
 			)))
 	}
+
+	/// Assigns the mixer track to a VCA, or removes it from
+	/// its VCA if `None` is passed.
+	pub fn set_vca(&mut self, vca: Option<VcaId>) -> Result<(), CommandError> {
+		self.command_producer
+			.push(Command::Mixer(MixerCommand::SetTrackVca(self.id, vca)))
+	}
+
+	/// Sends the output of the mixer track to another track.
+	///
+	/// If the track is already routed to the destination, the
+	/// amount will move to the new value following the tween
+	/// (or the track's smoothing if no tween is provided).
+	/// Otherwise, a new route is created whose amount moves
+	/// up from 0.
+	pub fn set_route(
+		&mut self,
+		destination: impl Into<TrackId>,
+		amount: impl Into<Value>,
+		tween: impl Into<Option<Tween>>,
+	) -> Result<(), SetRouteError> {
+		self.set_route_with_send_point(
+			destination.into(),
+			amount.into(),
+			tween.into(),
+			SendPoint::PostFader,
+		)
+	}
+
+	/// Sends the output of the mixer track to another track,
+	/// taking the signal from before the track's volume and
+	/// panning are applied.
+	///
+	/// If the track is already routed to the destination, the
+	/// route will switch to sending the pre-fader signal, and the
+	/// amount will move to the new value following the tween
+	/// (or the track's smoothing if no tween is provided).
+	/// Otherwise, a new route is created whose amount moves
+	/// up from 0.
+	pub fn set_pre_fader_route(
+		&mut self,
+		destination: impl Into<TrackId>,
+		amount: impl Into<Value>,
+		tween: impl Into<Option<Tween>>,
+	) -> Result<(), SetRouteError> {
+		self.set_route_with_send_point(
+			destination.into(),
+			amount.into(),
+			tween.into(),
+			SendPoint::PreFader,
+		)
+	}
+
+	fn set_route_with_send_point(
+		&mut self,
+		destination: TrackId,
+		amount: Value,
+		tween: Option<Tween>,
+		send_point: SendPoint,
+	) -> Result<(), SetRouteError> {
+		let mut track_graph = self
+			.track_graph
+			.lock()
+			.map_err(|_| CommandError::MutexPoisoned)?;
+		let destinations = track_graph.destinations(self.id);
+		if destinations.contains(&destination) {
+			self.command_producer
+				.push(Command::Mixer(MixerCommand::SetRoute {
+					track: self.id,
+					destination,
+					amount,
+					tween,
+					send_point,
+				}))?;
+			return Ok(());
+		}
+		// the new list of routes is allocated here so the
+		// renderer doesn't have to
+		let routes = Vec::with_capacity(destinations.len() + 1);
+		if !track_graph.contains(destination) {
+			return Err(SetRouteError::NonexistentTrack);
+		}
+		if track_graph.creates_cycle(self.id, &[destination]) {
+			return Err(SetRouteError::RoutingCycle);
+		}
+		self.command_producer
+			.push(Command::Mixer(MixerCommand::AddRoute {
+				track: self.id,
+				route: Route::new(
+					destination,
+					amount,
+					tween,
+					send_point,
+					self.smoothing,
+					self.latency_compensation_capacity,
+				),
+				routes,
+			}))?;
+		track_graph.add_destination(self.id, destination);
+		Ok(())
+	}
+
+	/// Stops sending the output of the mixer track to
+	/// another track.
+	pub fn remove_route(&mut self, destination: impl Into<TrackId>) -> Result<(), CommandError> {
+		let destination = destination.into();
+		let mut track_graph = self
+			.track_graph
+			.lock()
+			.map_err(|_| CommandError::MutexPoisoned)?;
+		let destinations = track_graph.destinations(self.id);
+		if !destinations.contains(&destination) {
+			return Ok(());
+		}
+		let routes = Vec::with_capacity(destinations.len() - 1);
+		self.command_producer
+			.push(Command::Mixer(MixerCommand::RemoveRoute {
+				track: self.id,
+				destination,
+				routes,
+			}))?;
+		track_graph.remove_destination(self.id, destination);
+		Ok(())
+	}
 }
 
 impl Drop for TrackHandle {
@@ -0,0 +1,39 @@
+use crate::frame::Frame;
+
+/// Delays a signal by a number of frames so that it lines up
+/// with other signals that passed through effects with latency.
+pub(crate) struct CompensationDelay {
+	buffer: Vec<Frame>,
+	position: usize,
+	delay: usize,
+}
+
+impl CompensationDelay {
+	/// Creates a new [`CompensationDelay`] that can delay a
+	/// signal by up to `capacity` frames.
+	pub fn new(capacity: usize) -> Self {
+		Self {
+			buffer: vec![Frame::ZERO; capacity + 1],
+			position: 0,
+			delay: 0,
+		}
+	}
+
+	pub fn delay(&self) -> usize {
+		self.delay
+	}
+
+	/// Sets how many frames the signal should be delayed by.
+	///
+	/// The delay is clamped to the capacity of the buffer.
+	pub fn set_delay(&mut self, delay: usize) {
+		self.delay = delay.min(self.buffer.len() - 1);
+	}
+
+	pub fn process(&mut self, input: Frame) -> Frame {
+		self.buffer[self.position] = input;
+		let read_position = (self.position + self.buffer.len() - self.delay) % self.buffer.len();
+		self.position = (self.position + 1) % self.buffer.len();
+		self.buffer[read_position]
+	}
+}
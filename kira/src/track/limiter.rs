@@ -0,0 +1,192 @@
+use std::{collections::VecDeque, sync::Arc, time::Duration};
+
+use crate::{frame::Frame, manager::context::Context, util::interpolate_frame};
+
+/// How many points to check between each pair of frames
+/// when looking for peaks.
+const TRUE_PEAK_OVERSAMPLING: usize = 4;
+
+/// Settings for the limiter on the main mixer track.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+	feature = "serde_support",
+	derive(serde::Serialize, serde::Deserialize)
+)]
+#[cfg_attr(feature = "serde_support", serde(default))]
+pub struct MainTrackLimiterSettings {
+	/// The maximum amplitude of the output.
+	pub ceiling: f64,
+	/// How far ahead the limiter looks for peaks. The output
+	/// is delayed by this amount of time.
+	pub look_ahead: Duration,
+	/// How long it takes the limiter to stop turning down
+	/// the output after a peak.
+	pub release: Duration,
+}
+
+impl MainTrackLimiterSettings {
+	/// Creates a new [`MainTrackLimiterSettings`] with the default settings.
+	pub fn new() -> Self {
+		Self {
+			ceiling: 1.0,
+			look_ahead: Duration::from_millis(5),
+			release: Duration::from_millis(100),
+		}
+	}
+
+	/// Sets the maximum amplitude of the output.
+	pub fn ceiling(self, ceiling: f64) -> Self {
+		Self { ceiling, ..self }
+	}
+
+	/// Sets how far ahead the limiter looks for peaks.
+	pub fn look_ahead(self, look_ahead: Duration) -> Self {
+		Self { look_ahead, ..self }
+	}
+
+	/// Sets how long it takes the limiter to stop turning down
+	/// the output after a peak.
+	pub fn release(self, release: Duration) -> Self {
+		Self { release, ..self }
+	}
+}
+
+impl Default for MainTrackLimiterSettings {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+/// Keeps the output of the main track under a ceiling by delaying
+/// the output and turning it down ahead of any peaks, including
+/// peaks between frames.
+pub(crate) struct MainTrackLimiter {
+	context: Arc<Context>,
+	ceiling: f64,
+	attack_coefficient: f64,
+	release_coefficient: f64,
+	/// Recent input frames and the gain each of them needs
+	/// to stay under the ceiling.
+	buffer: Vec<(Frame, f64)>,
+	look_ahead_frames: usize,
+	write_position: usize,
+	/// The number of frames processed so far.
+	frame_index: u64,
+	/// The gains needed by frames that haven't been output yet,
+	/// along with the index of the newest frame each gain applies
+	/// to, in increasing order of both. The front of the queue
+	/// is the lowest gain needed by any of those frames.
+	required_gains: VecDeque<(u64, f64)>,
+	gain: f64,
+}
+
+impl MainTrackLimiter {
+	pub fn new(settings: MainTrackLimiterSettings, context: &Arc<Context>) -> Self {
+		let sample_rate = context.sample_rate() as f64;
+		let look_ahead_frames = (settings.look_ahead.as_secs_f64() * sample_rate).round() as usize;
+		Self {
+			context: context.clone(),
+			ceiling: settings.ceiling,
+			// the attack should be mostly finished by the time
+			// a peak reaches the output
+			attack_coefficient: one_pole_coefficient(
+				settings.look_ahead.as_secs_f64() / 3.0,
+				sample_rate,
+			),
+			release_coefficient: one_pole_coefficient(settings.release.as_secs_f64(), sample_rate),
+			// the extra frames are needed to interpolate between
+			// frames when looking for peaks
+			buffer: vec![(Frame::ZERO, 1.0); look_ahead_frames + 4],
+			look_ahead_frames,
+			write_position: 0,
+			frame_index: 0,
+			// each frame adds up to two gains to the queue, and they're
+			// removed after the frame is output
+			required_gains: VecDeque::with_capacity(2 * (look_ahead_frames + 2)),
+			gain: 1.0,
+		}
+	}
+
+	fn index(&self, frames_ago: usize) -> usize {
+		(self.write_position + self.buffer.len() - frames_ago) % self.buffer.len()
+	}
+
+	fn required_gain(&self, peak: f64) -> f64 {
+		if peak > self.ceiling {
+			self.ceiling / peak
+		} else {
+			1.0
+		}
+	}
+
+	/// Adds a gain needed by the frame with the given index
+	/// (and possibly some frames before it) to the queue.
+	fn push_required_gain(&mut self, frame_index: u64, gain: f64) {
+		// gains that aren't lower than this one can't be the lowest
+		// gain anymore, since they'll leave the queue first
+		while matches!(self.required_gains.back(), Some((_, back)) if *back >= gain) {
+			self.required_gains.pop_back();
+		}
+		self.required_gains.push_back((frame_index, gain));
+	}
+
+	pub fn process(&mut self, input: Frame) -> Frame {
+		self.write_position = (self.write_position + 1) % self.buffer.len();
+		self.frame_index += 1;
+		let input_required_gain = self.required_gain(frame_peak(input));
+		self.buffer[self.write_position] = (input, input_required_gain);
+		// look for peaks between the two frames before the newest one
+		let previous = self.buffer[self.index(3)].0;
+		let current = self.buffer[self.index(2)].0;
+		let next_1 = self.buffer[self.index(1)].0;
+		let next_2 = self.buffer[self.index(0)].0;
+		let mut peak = 0.0f64;
+		for i in 1..TRUE_PEAK_OVERSAMPLING {
+			let fraction = i as f32 / TRUE_PEAK_OVERSAMPLING as f32;
+			peak = peak.max(frame_peak(interpolate_frame(
+				previous, current, next_1, next_2, fraction,
+			)));
+		}
+		let required_gain = self.required_gain(peak);
+		for frames_ago in 1..=2 {
+			let index = self.index(frames_ago);
+			self.buffer[index].1 = self.buffer[index].1.min(required_gain);
+		}
+		self.push_required_gain(self.frame_index - 1, required_gain);
+		self.push_required_gain(self.frame_index, input_required_gain);
+		// forget gains for frames that have already been output
+		while let Some((frame_index, _)) = self.required_gains.front() {
+			if frame_index + self.look_ahead_frames as u64 >= self.frame_index {
+				break;
+			}
+			self.required_gains.pop_front();
+		}
+		// move towards the lowest gain needed by any frame
+		// that hasn't been output yet
+		let target_gain = self.required_gains.front().map_or(1.0, |(_, gain)| *gain);
+		let coefficient = if target_gain < self.gain {
+			self.attack_coefficient
+		} else {
+			self.release_coefficient
+		};
+		self.gain = target_gain + (self.gain - target_gain) * coefficient;
+		let (output, output_required_gain) = self.buffer[self.index(self.look_ahead_frames)];
+		let gain = self.gain.min(output_required_gain);
+		if gain < 1.0 {
+			self.context.set_main_track_limiter_engaged();
+		}
+		output * gain as f32
+	}
+}
+
+fn frame_peak(frame: Frame) -> f64 {
+	frame.left.abs().max(frame.right.abs()) as f64
+}
+
+fn one_pole_coefficient(time: f64, sample_rate: f64) -> f64 {
+	if time > 0.0 {
+		(-1.0 / (time * sample_rate)).exp()
+	} else {
+		0.0
+	}
+}
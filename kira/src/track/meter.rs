@@ -0,0 +1,253 @@
+use std::{
+	f64::consts::PI,
+	sync::atomic::{AtomicU64, Ordering},
+};
+
+use crate::frame::Frame;
+
+/// The length (in seconds) of the blocks the meter measures
+/// audio in.
+const BLOCK_DURATION: f64 = 0.1;
+/// The number of blocks the RMS level is measured over.
+const RMS_BLOCKS: usize = 3;
+/// The number of blocks the momentary loudness is measured over.
+const MOMENTARY_BLOCKS: usize = 4;
+/// The number of blocks the short-term loudness is measured over.
+const SHORT_TERM_BLOCKS: usize = 30;
+/// How long (in seconds) the peak level is held before it
+/// falls back to the current level.
+const PEAK_HOLD_DURATION: f64 = 1.0;
+
+/// The levels of a mixer track's output.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrackMeter {
+	/// The highest amplitude of either channel in the last second.
+	pub peak: f64,
+	/// The RMS amplitude over the last 300 milliseconds.
+	pub rms: f64,
+	/// The loudness (in LUFS) over the last 400 milliseconds,
+	/// as defined by ITU-R BS.1770.
+	pub momentary_loudness: f64,
+	/// The loudness (in LUFS) over the last 3 seconds,
+	/// as defined by ITU-R BS.1770.
+	pub short_term_loudness: f64,
+}
+
+impl TrackMeter {
+	fn silent() -> Self {
+		Self {
+			peak: 0.0,
+			rms: 0.0,
+			momentary_loudness: f64::NEG_INFINITY,
+			short_term_loudness: f64::NEG_INFINITY,
+		}
+	}
+}
+
+/// The latest meter readings of a track, written by the
+/// renderer and read by the track's handle.
+pub(crate) struct SharedMeter {
+	peak: AtomicU64,
+	rms: AtomicU64,
+	momentary_loudness: AtomicU64,
+	short_term_loudness: AtomicU64,
+}
+
+impl SharedMeter {
+	pub fn new() -> Self {
+		let meter = TrackMeter::silent();
+		Self {
+			peak: AtomicU64::new(meter.peak.to_bits()),
+			rms: AtomicU64::new(meter.rms.to_bits()),
+			momentary_loudness: AtomicU64::new(meter.momentary_loudness.to_bits()),
+			short_term_loudness: AtomicU64::new(meter.short_term_loudness.to_bits()),
+		}
+	}
+
+	pub fn read(&self) -> TrackMeter {
+		TrackMeter {
+			peak: f64::from_bits(self.peak.load(Ordering::Relaxed)),
+			rms: f64::from_bits(self.rms.load(Ordering::Relaxed)),
+			momentary_loudness: f64::from_bits(self.momentary_loudness.load(Ordering::Relaxed)),
+			short_term_loudness: f64::from_bits(self.short_term_loudness.load(Ordering::Relaxed)),
+		}
+	}
+
+	fn write(&self, meter: TrackMeter) {
+		self.peak.store(meter.peak.to_bits(), Ordering::Relaxed);
+		self.rms.store(meter.rms.to_bits(), Ordering::Relaxed);
+		self.momentary_loudness
+			.store(meter.momentary_loudness.to_bits(), Ordering::Relaxed);
+		self.short_term_loudness
+			.store(meter.short_term_loudness.to_bits(), Ordering::Relaxed);
+	}
+}
+
+/// A second order filter in transposed direct form II.
+#[derive(Clone, Copy)]
+struct Biquad {
+	b0: f64,
+	b1: f64,
+	b2: f64,
+	a1: f64,
+	a2: f64,
+	z1: f64,
+	z2: f64,
+}
+
+impl Biquad {
+	fn new(b0: f64, b1: f64, b2: f64, a1: f64, a2: f64) -> Self {
+		Self {
+			b0,
+			b1,
+			b2,
+			a1,
+			a2,
+			z1: 0.0,
+			z2: 0.0,
+		}
+	}
+
+	fn process(&mut self, input: f64) -> f64 {
+		let output = self.b0 * input + self.z1;
+		self.z1 = self.b1 * input - self.a1 * output + self.z2;
+		self.z2 = self.b2 * input - self.a2 * output;
+		output
+	}
+}
+
+/// The K-weighting filter from ITU-R BS.1770 for one channel.
+///
+/// The standard only gives coefficients for 48kHz, so the filters
+/// are recreated from their analog prototypes for other sample
+/// rates. At very low sample rates, the filter frequencies are
+/// kept below the Nyquist frequency so the filters stay stable.
+#[derive(Clone, Copy)]
+struct KWeighting {
+	shelf: Biquad,
+	high_pass: Biquad,
+}
+
+impl KWeighting {
+	fn new(sample_rate: f64) -> Self {
+		let shelf = {
+			let frequency = 1681.974450955533f64.min(sample_rate * 0.45);
+			let gain = 3.999843853973347;
+			let q = 0.7071752369554196;
+			let k = (PI * frequency / sample_rate).tan();
+			let vh = 10.0f64.powf(gain / 20.0);
+			let vb = vh.powf(0.4996667741545416);
+			let a0 = 1.0 + k / q + k * k;
+			Biquad::new(
+				(vh + vb * k / q + k * k) / a0,
+				2.0 * (k * k - vh) / a0,
+				(vh - vb * k / q + k * k) / a0,
+				2.0 * (k * k - 1.0) / a0,
+				(1.0 - k / q + k * k) / a0,
+			)
+		};
+		let high_pass = {
+			let frequency = 38.13547087602444f64.min(sample_rate * 0.45);
+			let q = 0.5003270373238773;
+			let k = (PI * frequency / sample_rate).tan();
+			let a0 = 1.0 + k / q + k * k;
+			Biquad::new(
+				1.0,
+				-2.0,
+				1.0,
+				2.0 * (k * k - 1.0) / a0,
+				(1.0 - k / q + k * k) / a0,
+			)
+		};
+		Self { shelf, high_pass }
+	}
+
+	fn process(&mut self, input: f64) -> f64 {
+		self.high_pass.process(self.shelf.process(input))
+	}
+}
+
+/// Measures the levels of a track's output.
+///
+/// The audio is measured in blocks of 100 milliseconds, and the
+/// readings are published once per block.
+pub(crate) struct Meter {
+	k_weighting: [KWeighting; 2],
+	block_frames: usize,
+	block_position: usize,
+	/// The sum of the squared samples in the current block.
+	square_sum: f64,
+	/// The sum of the squared K-weighted samples in the current block.
+	weighted_square_sum: f64,
+	/// The square sums of the most recent blocks.
+	square_sums: [f64; RMS_BLOCKS],
+	/// The K-weighted square sums of the most recent blocks.
+	weighted_square_sums: [f64; SHORT_TERM_BLOCKS],
+	/// The number of blocks that have been measured.
+	blocks: usize,
+	peak: f64,
+	/// How long (in frames) the current peak has been held.
+	peak_age: usize,
+	peak_hold_frames: usize,
+}
+
+impl Meter {
+	pub fn new(sample_rate: u32) -> Self {
+		let sample_rate = sample_rate as f64;
+		Self {
+			k_weighting: [KWeighting::new(sample_rate); 2],
+			block_frames: ((BLOCK_DURATION * sample_rate).round() as usize).max(1),
+			block_position: 0,
+			square_sum: 0.0,
+			weighted_square_sum: 0.0,
+			square_sums: [0.0; RMS_BLOCKS],
+			weighted_square_sums: [0.0; SHORT_TERM_BLOCKS],
+			blocks: 0,
+			peak: 0.0,
+			peak_age: 0,
+			peak_hold_frames: (PEAK_HOLD_DURATION * sample_rate).round() as usize,
+		}
+	}
+
+	pub fn process(&mut self, frame: Frame, shared: &SharedMeter) {
+		let left = frame.left as f64;
+		let right = frame.right as f64;
+		let peak = left.abs().max(right.abs());
+		if peak >= self.peak || self.peak_age >= self.peak_hold_frames {
+			self.peak = peak;
+			self.peak_age = 0;
+		} else {
+			self.peak_age += 1;
+		}
+		self.square_sum += (left * left + right * right) / 2.0;
+		let weighted_left = self.k_weighting[0].process(left);
+		let weighted_right = self.k_weighting[1].process(right);
+		self.weighted_square_sum += weighted_left * weighted_left + weighted_right * weighted_right;
+		self.block_position += 1;
+		if self.block_position < self.block_frames {
+			return;
+		}
+		self.square_sums[self.blocks % RMS_BLOCKS] = std::mem::take(&mut self.square_sum);
+		self.weighted_square_sums[self.blocks % SHORT_TERM_BLOCKS] =
+			std::mem::take(&mut self.weighted_square_sum);
+		self.blocks += 1;
+		self.block_position = 0;
+		shared.write(TrackMeter {
+			peak: self.peak,
+			rms: (self.square_sums.iter().sum::<f64>() / (RMS_BLOCKS * self.block_frames) as f64)
+				.sqrt(),
+			momentary_loudness: self.loudness(MOMENTARY_BLOCKS),
+			short_term_loudness: self.loudness(SHORT_TERM_BLOCKS),
+		});
+	}
+
+	/// Returns the loudness (in LUFS) of the most recent blocks.
+	fn loudness(&self, blocks: usize) -> f64 {
+		let sum = (1..=blocks)
+			.map(|i| {
+				self.weighted_square_sums[(self.blocks + SHORT_TERM_BLOCKS - i) % SHORT_TERM_BLOCKS]
+			})
+			.sum::<f64>();
+		-0.691 + 10.0 * (sum / (blocks * self.block_frames) as f64).log10()
+	}
+}
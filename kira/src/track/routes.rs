@@ -1,12 +1,25 @@
 use std::collections::HashMap;
 
-use crate::value::{cached::CachedValue, Value};
+use crate::{
+	parameter::Tween,
+	value::{cached::CachedValue, Smoothing, Value},
+};
 
-use super::TrackId;
+use super::{latency::CompensationDelay, TrackId};
+
+/// Where in a mixer track the audio sent along a route is taken from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SendPoint {
+	/// After the track's effects, but before its volume,
+	/// ducking, and panning are applied.
+	PreFader,
+	/// After the track's volume, ducking, and panning are applied.
+	PostFader,
+}
 
 /// Defines how the output of a mixer sub-track will be
 /// fed into the input of other mixer tracks.
-pub struct TrackRoutes(HashMap<TrackId, Value>);
+pub struct TrackRoutes(HashMap<TrackId, (Value, SendPoint)>);
 
 impl TrackRoutes {
 	/// Creates a new [`TrackRoutes`] with the default settings.
@@ -16,7 +29,7 @@ impl TrackRoutes {
 	pub fn new() -> Self {
 		Self({
 			let mut routes = HashMap::new();
-			routes.insert(TrackId::Main, Value::Fixed(1.0));
+			routes.insert(TrackId::Main, (Value::Fixed(1.0), SendPoint::PostFader));
 			routes
 		})
 	}
@@ -24,14 +37,42 @@ impl TrackRoutes {
 	/// Sets how much of the current track's signal will be sent
 	/// to the specified destination track.
 	pub fn with_route(mut self, track: impl Into<TrackId>, volume: impl Into<Value>) -> Self {
-		self.0.insert(track.into(), volume.into());
+		self.0
+			.insert(track.into(), (volume.into(), SendPoint::PostFader));
 		self
 	}
 
-	pub(crate) fn into_vec(self) -> Vec<(TrackId, CachedValue)> {
+	/// Sets how much of the current track's signal will be sent
+	/// to the specified destination track, taking the signal
+	/// from before the track's volume and panning are applied.
+	pub fn with_pre_fader_route(
+		mut self,
+		track: impl Into<TrackId>,
+		volume: impl Into<Value>,
+	) -> Self {
+		self.0
+			.insert(track.into(), (volume.into(), SendPoint::PreFader));
+		self
+	}
+
+	/// Returns the tracks this track's output will be sent to.
+	pub(crate) fn destinations(&self) -> impl Iterator<Item = TrackId> + '_ {
+		self.0.keys().copied()
+	}
+
+	pub(crate) fn into_vec(
+		self,
+		smoothing: Option<Smoothing>,
+		latency_compensation_capacity: usize,
+	) -> Vec<Route> {
 		self.0
 			.iter()
-			.map(|(id, value)| (*id, CachedValue::new(.., *value, 0.0)))
+			.map(|(id, (value, send_point))| Route {
+				destination: *id,
+				amount: CachedValue::new(.., *value, 0.0).with_smoothing(smoothing),
+				send_point: *send_point,
+				compensation: CompensationDelay::new(latency_compensation_capacity),
+			})
 			.collect()
 	}
 }
@@ -41,3 +82,38 @@ impl Default for TrackRoutes {
 		Self::new()
 	}
 }
+
+/// A connection from the output of one mixer track to the
+/// input of another.
+pub(crate) struct Route {
+	pub destination: TrackId,
+	pub amount: CachedValue,
+	pub send_point: SendPoint,
+	/// Delays the signal sent along this route so it lines up
+	/// with other signals arriving at the destination track
+	/// through effects with more latency.
+	pub compensation: CompensationDelay,
+}
+
+impl Route {
+	/// Creates a route for a track that already exists. The
+	/// amount starts at 0 and moves to the given value.
+	pub fn new(
+		destination: TrackId,
+		amount: Value,
+		tween: Option<Tween>,
+		send_point: SendPoint,
+		smoothing: Option<Smoothing>,
+		latency_compensation_capacity: usize,
+	) -> Self {
+		let mut cached_amount =
+			CachedValue::new(.., Value::Fixed(0.0), 0.0).with_smoothing(smoothing);
+		cached_amount.set(amount, tween);
+		Self {
+			destination,
+			amount: cached_amount,
+			send_point,
+			compensation: CompensationDelay::new(latency_compensation_capacity),
+		}
+	}
+}
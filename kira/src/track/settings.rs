@@ -1,8 +1,16 @@
-use crate::value::Value;
+use crate::value::{Smoothing, Value};
 
-use super::{routes::TrackRoutes, Effect};
+use super::{routes::TrackRoutes, DuckingSettings, Effect, VcaId};
 
 /// Settings for a mixer track.
+///
+/// With the `serde_support` feature enabled, the routes,
+/// effects, ducking settings, and VCA are not serialized.
+#[cfg_attr(
+	feature = "serde_support",
+	derive(serde::Serialize, serde::Deserialize)
+)]
+#[cfg_attr(feature = "serde_support", serde(default))]
 pub struct TrackSettings {
 	/// The volume of the track.
 	pub volume: Value,
@@ -11,10 +19,31 @@ pub struct TrackSettings {
 	pub panning: Value,
 	/// How the output of this track should be routed
 	/// to other mixer tracks.
+	#[cfg_attr(feature = "serde_support", serde(skip))]
 	pub routes: TrackRoutes,
 	/// The effects that should be applied to the input audio
 	/// for this track.
+	#[cfg_attr(feature = "serde_support", serde(skip))]
 	pub effects: Vec<Box<dyn Effect>>,
+	/// Turns this track down automatically while another
+	/// track is loud.
+	#[cfg_attr(feature = "serde_support", serde(skip))]
+	pub ducking: Option<DuckingSettings>,
+	/// Whether the track starts muted.
+	pub muted: bool,
+	/// Whether the track stays audible when other tracks
+	/// are soloed.
+	pub solo_safe: bool,
+	/// The VCA whose volume should be applied to this track.
+	#[cfg_attr(feature = "serde_support", serde(skip))]
+	pub vca: Option<VcaId>,
+	/// How the volume, panning, and route amounts move to new
+	/// values after they're changed.
+	///
+	/// If `None`, the default smoothing from the
+	/// [`AudioManagerSettings`](crate::manager::AudioManagerSettings)
+	/// will be used.
+	pub smoothing: Option<Smoothing>,
 }
 
 impl TrackSettings {
@@ -25,6 +54,11 @@ impl TrackSettings {
 			panning: Value::Fixed(0.5),
 			routes: TrackRoutes::new(),
 			effects: vec![],
+			ducking: None,
+			muted: false,
+			solo_safe: false,
+			vca: None,
+			smoothing: None,
 		}
 	}
 
@@ -56,6 +90,44 @@ impl TrackSettings {
 		self.effects.push(Box::new(effect));
 		self
 	}
+
+	/// Turns this track down automatically while another
+	/// track is loud.
+	pub fn ducking(self, ducking: DuckingSettings) -> Self {
+		Self {
+			ducking: Some(ducking),
+			..self
+		}
+	}
+
+	/// Sets whether the track starts muted.
+	pub fn muted(self, muted: bool) -> Self {
+		Self { muted, ..self }
+	}
+
+	/// Sets whether the track stays audible when other tracks
+	/// are soloed. This is useful for buses like reverb returns.
+	pub fn solo_safe(self, solo_safe: bool) -> Self {
+		Self { solo_safe, ..self }
+	}
+
+	/// Assigns the track to a VCA, whose volume will be
+	/// applied to this track.
+	pub fn vca(self, vca: impl Into<VcaId>) -> Self {
+		Self {
+			vca: Some(vca.into()),
+			..self
+		}
+	}
+
+	/// Sets how the volume, panning, and route amounts move to new
+	/// values after they're changed.
+	pub fn smoothing(self, smoothing: impl Into<Option<Smoothing>>) -> Self {
+		Self {
+			smoothing: smoothing.into(),
+			..self
+		}
+	}
 }
 
 impl Default for TrackSettings {
@@ -0,0 +1,121 @@
+use std::sync::{
+	atomic::{AtomicBool, Ordering},
+	Arc,
+};
+
+use atomic_arena::Key;
+
+use crate::{
+	error::CommandError,
+	manager::{
+		command::{producer::CommandProducer, Command, MixerCommand},
+		resources::{clocks::Clocks, Parameters},
+	},
+	parameter::Tween,
+	value::{cached::CachedValue, Value},
+};
+
+/// A unique identifier for a VCA.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct VcaId(pub(crate) Key);
+
+impl From<&VcaHandle> for VcaId {
+	fn from(handle: &VcaHandle) -> Self {
+		handle.id()
+	}
+}
+
+pub(crate) struct VcaShared {
+	removed: AtomicBool,
+}
+
+impl VcaShared {
+	pub fn new() -> Self {
+		Self {
+			removed: AtomicBool::new(false),
+		}
+	}
+
+	pub fn is_marked_for_removal(&self) -> bool {
+		self.removed.load(Ordering::SeqCst)
+	}
+
+	pub fn mark_for_removal(&self) {
+		self.removed.store(true, Ordering::SeqCst);
+	}
+}
+
+/// Scales the volume of every mixer track assigned to it
+/// without mixing their audio together.
+pub(crate) struct Vca {
+	shared: Arc<VcaShared>,
+	volume: CachedValue,
+}
+
+impl Vca {
+	pub fn new(volume: Value) -> Self {
+		Self {
+			shared: Arc::new(VcaShared::new()),
+			volume: CachedValue::new(.., volume, 1.0),
+		}
+	}
+
+	pub fn shared(&self) -> Arc<VcaShared> {
+		self.shared.clone()
+	}
+
+	pub fn volume(&self) -> f64 {
+		self.volume.get()
+	}
+
+	pub fn set_volume(&mut self, volume: Value, tween: Option<Tween>) {
+		self.volume.set(volume, tween);
+	}
+
+	pub fn update(&mut self, parameters: &Parameters, clocks: &Clocks) {
+		self.volume.update_with_clocks(parameters, clocks);
+	}
+}
+
+/// Controls a VCA.
+///
+/// When a [`VcaHandle`] is dropped, the corresponding VCA
+/// will be removed, and the tracks assigned to it will
+/// go back to their own volume.
+pub struct VcaHandle {
+	pub(crate) id: VcaId,
+	pub(crate) shared: Arc<VcaShared>,
+	pub(crate) command_producer: CommandProducer,
+}
+
+impl VcaHandle {
+	/// Returns the unique identifier for the VCA.
+	pub fn id(&self) -> VcaId {
+		self.id
+	}
+
+	/// Sets the volume of the VCA, which is multiplied with
+	/// the volume of each track assigned to it.
+	///
+	/// If a tween is provided, the volume will move to the new
+	/// value following the tween. Otherwise, the default smoothing
+	/// will be used.
+	pub fn set_volume(
+		&mut self,
+		volume: impl Into<Value>,
+		tween: impl Into<Option<Tween>>,
+	) -> Result<(), CommandError> {
+		self.command_producer
+			.push(Command::Mixer(MixerCommand::SetVcaVolume(
+				self.id,
+				volume.into(),
+				tween.into(),
+			)))
+	}
+}
+
+impl Drop for VcaHandle {
+	fn drop(&mut self) {
+		self.shared.mark_for_removal();
+	}
+}
@@ -3,24 +3,53 @@
 
 pub mod cached;
 mod mapping;
+mod smoothing;
 
-use crate::parameter::{ParameterHandle, ParameterId};
+use crate::{
+	modulator::{EffectOutputHandle, EnvelopeHandle, LfoHandle, ModulatorId},
+	parameter::{ParameterHandle, ParameterId},
+};
 
 pub use mapping::*;
+pub use smoothing::*;
 
 /// The possible values for a setting.
+///
+/// With the `serde_support` feature enabled, only fixed values
+/// can be serialized, since parameter and modulator IDs are only
+/// valid while the program is running.
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+	feature = "serde_support",
+	derive(serde::Serialize, serde::Deserialize)
+)]
 pub enum Value {
 	/// The setting is fixed to the specified value.
 	Fixed(f64),
 	/// The setting is linked to a parameter with the
 	/// given mapping.
+	#[cfg_attr(feature = "serde_support", serde(skip))]
 	Parameter {
 		/// The parameter the setting is linked to.
 		id: ParameterId,
 		/// The mapping of parameter values to setting values.
 		mapping: Mapping,
 	},
+	/// The setting is linked to a modulator with the
+	/// given mapping.
+	#[cfg_attr(feature = "serde_support", serde(skip))]
+	Modulator {
+		/// The modulator the setting is linked to.
+		id: ModulatorId,
+		/// The mapping of modulator values to setting values.
+		mapping: Mapping,
+	},
+}
+
+impl Value {
+	pub(crate) fn is_modulator(&self) -> bool {
+		matches!(self, Value::Modulator { .. })
+	}
 }
 
 impl From<f64> for Value {
@@ -46,3 +75,39 @@ impl From<&ParameterHandle> for Value {
 		}
 	}
 }
+
+impl From<ModulatorId> for Value {
+	fn from(id: ModulatorId) -> Self {
+		Self::Modulator {
+			id,
+			mapping: Default::default(),
+		}
+	}
+}
+
+impl From<&LfoHandle> for Value {
+	fn from(handle: &LfoHandle) -> Self {
+		Self::Modulator {
+			id: handle.id(),
+			mapping: Default::default(),
+		}
+	}
+}
+
+impl From<&EnvelopeHandle> for Value {
+	fn from(handle: &EnvelopeHandle) -> Self {
+		Self::Modulator {
+			id: handle.id(),
+			mapping: Default::default(),
+		}
+	}
+}
+
+impl From<&EffectOutputHandle> for Value {
+	fn from(handle: &EffectOutputHandle) -> Self {
+		Self::Modulator {
+			id: handle.id(),
+			mapping: Default::default(),
+		}
+	}
+}
@@ -4,9 +4,16 @@
 
 use std::ops::{RangeFrom, RangeFull, RangeInclusive, RangeToInclusive};
 
-use crate::manager::resources::Parameters;
+use atomic_arena::Arena;
 
-use super::Value;
+use crate::{
+	clock::ClockTime,
+	manager::resources::{clocks::Clocks, Parameters},
+	parameter::{Easing, Parameter, Tween},
+	start_time::StartTime,
+};
+
+use super::{Smoothing, Value};
 
 /// The valid range of raw values for a [`CachedValue`].
 ///
@@ -67,23 +74,59 @@ impl From<RangeFull> for ValidRange {
 	}
 }
 
+/// A movement from the raw value a [`CachedValue`] had when it
+/// was set towards its new target.
+struct Transition {
+	start_value: f64,
+	time: f64,
+	/// The tween to follow, or `None` to use the smoothing
+	/// of the [`CachedValue`].
+	tween: Option<Tween>,
+	waiting_to_start: bool,
+}
+
 /// Holds a [`Value`] and remembers the last valid raw value.
+///
+/// When the [`Value`] changes, the raw value moves to the new
+/// value according to the [`CachedValue`]'s [`Smoothing`].
 pub struct CachedValue {
 	valid_range: ValidRange,
 	value: Value,
+	smoothing: Option<Smoothing>,
+	target: f64,
 	raw_value: f64,
+	transition: Option<Transition>,
+	resolved: bool,
 }
 
 impl CachedValue {
 	/// Creates a new [`CachedValue`].
 	pub fn new(valid_range: impl Into<ValidRange>, value: Value, default: f64) -> Self {
+		let mut valid_range = valid_range.into();
+		let raw_value = match value {
+			Value::Fixed(value) => valid_range.clamp(value),
+			Value::Parameter { .. } | Value::Modulator { .. } => default,
+		};
 		Self {
-			valid_range: valid_range.into(),
+			valid_range,
 			value,
-			raw_value: match value {
-				Value::Fixed(value) => value,
-				Value::Parameter { .. } => default,
-			},
+			smoothing: None,
+			target: raw_value,
+			raw_value,
+			transition: None,
+			resolved: matches!(value, Value::Fixed(_)),
+		}
+	}
+
+	/// Sets the [`Smoothing`] used when the value changes.
+	///
+	/// If `None`, the default smoothing from the
+	/// [`AudioManagerSettings`](crate::manager::AudioManagerSettings)
+	/// will be used.
+	pub fn with_smoothing(self, smoothing: impl Into<Option<Smoothing>>) -> Self {
+		Self {
+			smoothing: smoothing.into(),
+			..self
 		}
 	}
 
@@ -92,19 +135,150 @@ impl CachedValue {
 		self.raw_value
 	}
 
-	pub(crate) fn set(&mut self, value: Value) {
+	/// Sets a new [`Value`]. The raw value will move to the new value
+	/// with the given tween, or with the [`CachedValue`]'s smoothing
+	/// if no tween is given.
+	pub(crate) fn set(&mut self, value: Value, tween: Option<Tween>) {
 		self.value = value;
 		if let Value::Fixed(raw_value) = self.value {
-			self.raw_value = self.valid_range.clamp(raw_value);
+			self.target = self.valid_range.clamp(raw_value);
 		}
+		self.transition = Some(Transition {
+			start_value: self.raw_value,
+			time: 0.0,
+			tween,
+			waiting_to_start: matches!(
+				tween,
+				Some(Tween {
+					start_time: StartTime::ClockTime(..),
+					..
+				})
+			),
+		});
 	}
 
-	/// Updates the [`CachedValue`] with the current values of parameters.
+	/// Returns the clock time the current tween is waiting for, if any.
+	pub(crate) fn waiting_for(&self) -> Option<ClockTime> {
+		match &self.transition {
+			Some(Transition {
+				waiting_to_start: true,
+				tween: Some(Tween {
+					start_time: StartTime::ClockTime(time),
+					..
+				}),
+				..
+			}) => Some(*time),
+			_ => None,
+		}
+	}
+
+	/// Starts the current tween if it's waiting for a clock time.
+	pub(crate) fn start_waiting_tween(&mut self) {
+		if let Some(transition) = &mut self.transition {
+			transition.waiting_to_start = false;
+		}
+	}
+
+	/// Updates the [`CachedValue`] with the current values of parameters
+	/// and modulators.
 	pub fn update(&mut self, parameters: &Parameters) {
+		match self.value {
+			Value::Fixed(_) => {}
+			Value::Parameter { id, mapping } => {
+				if let Some(parameter) = parameters.get(id) {
+					self.set_target(mapping.map(parameter.value()));
+				}
+			}
+			Value::Modulator { id, mapping } => {
+				if let Some(modulator) = parameters.modulator(id) {
+					self.set_target(mapping.map(modulator.value()));
+				}
+			}
+		}
+		self.advance(
+			parameters.dt(),
+			self.smoothing
+				.unwrap_or_else(|| parameters.default_smoothing()),
+		);
+	}
+
+	/// Updates the [`CachedValue`] with the current values of parameters
+	/// and modulators, starting a tween that's waiting for a clock time
+	/// if the clock has reached that time.
+	pub(crate) fn update_with_clocks(&mut self, parameters: &Parameters, clocks: &Clocks) {
+		if let Some(ClockTime { clock, ticks }) = self.waiting_for() {
+			if let Some(clock) = clocks.get(clock) {
+				if clock.ticking() && clock.ticks() >= ticks {
+					self.start_waiting_tween();
+				}
+			}
+		}
+		self.update(parameters);
+	}
+
+	/// Updates the [`CachedValue`] with the current values of parameters
+	/// only. Modulators use this to update their own settings, since they
+	/// can't read each other's values while they're being updated
+	/// (the audio manager doesn't let their settings link to modulators).
+	///
+	/// Values updated this way are not smoothed.
+	pub(crate) fn update_from_parameters(&mut self, parameters: &Arena<Parameter>) {
 		if let Value::Parameter { id, mapping } = self.value {
-			if let Some(parameter) = parameters.get(id) {
-				self.raw_value = self.valid_range.clamp(mapping.map(parameter.value()));
+			if let Some(parameter) = parameters.get(id.0) {
+				self.set_target(mapping.map(parameter.value()));
+			}
+		}
+		self.transition = None;
+		self.raw_value = self.target;
+	}
+
+	fn set_target(&mut self, target: f64) {
+		self.target = self.valid_range.clamp(target);
+		// the first time a parameter or modulator is read, jump straight
+		// to its value instead of gliding from the default value
+		if !self.resolved {
+			self.resolved = true;
+			self.raw_value = self.target;
+			self.transition = None;
+		}
+	}
+
+	fn advance(&mut self, dt: f64, smoothing: Smoothing) {
+		if let Some(transition) = &mut self.transition {
+			if transition.waiting_to_start {
+				self.raw_value = transition.start_value;
+				return;
+			}
+			let (duration, easing) = match (transition.tween, smoothing) {
+				(Some(tween), _) => (tween.duration.as_secs_f64(), tween.easing),
+				(None, Smoothing::Linear(duration)) => (duration.as_secs_f64(), Easing::Linear),
+				(None, Smoothing::OnePole(_)) => {
+					// one-pole smoothing doesn't need to remember where
+					// it started from, so it's handled below
+					self.transition = None;
+					(0.0, Easing::Linear)
+				}
+				(None, Smoothing::None) => (0.0, Easing::Linear),
+			};
+			if let Some(transition) = &mut self.transition {
+				transition.time += dt;
+				if transition.time < duration {
+					let progress = easing.apply(transition.time / duration);
+					self.raw_value =
+						transition.start_value + (self.target - transition.start_value) * progress;
+					return;
+				}
+				self.transition = None;
+				self.raw_value = self.target;
+				return;
+			}
+		}
+		match smoothing {
+			Smoothing::OnePole(time_constant) if time_constant.as_secs_f64() > 0.0 => {
+				let coefficient = 1.0 - (-dt / time_constant.as_secs_f64()).exp();
+				self.raw_value += (self.target - self.raw_value) * coefficient;
 			}
+			_ => self.raw_value = self.target,
 		}
 	}
 }
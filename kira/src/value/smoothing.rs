@@ -0,0 +1,34 @@
+use std::time::Duration;
+
+/// How a setting moves to a new value after it's changed.
+///
+/// Abruptly changing settings like volume or panning can cause
+/// audible clicks and "zipper" noise, so settings are smoothed
+/// by default.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+	feature = "serde_support",
+	derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum Smoothing {
+	/// The setting jumps to the new value immediately.
+	None,
+	/// The setting moves linearly to the new value over the
+	/// given duration whenever it's set.
+	///
+	/// Changes caused by parameters and modulators are not smoothed,
+	/// since those already change gradually.
+	Linear(Duration),
+	/// The setting continuously approaches its target value
+	/// using a one-pole low-pass filter with the given time constant.
+	///
+	/// Unlike [`Linear`](Smoothing::Linear), this also smooths
+	/// changes caused by parameters and modulators.
+	OnePole(Duration),
+}
+
+impl Default for Smoothing {
+	fn default() -> Self {
+		Self::Linear(Duration::from_millis(10))
+	}
+}
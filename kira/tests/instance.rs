@@ -1,12 +1,13 @@
 use std::{error::Error, time::Duration};
 
 use kira::{
-	manager::{AudioManager, MockBackend},
+	manager::{AudioManager, AudioManagerSettings, MockBackend},
 	parameter::Tween,
 	sound::{
 		instance::{InstanceSettings, InstanceState},
 		static_sound::StaticSound,
 	},
+	value::Smoothing,
 	Frame, LoopBehavior,
 };
 
@@ -19,7 +20,14 @@ fn assert_frame_approximate_eq(a: Frame, b: Frame) {
 
 #[test]
 fn plays_all_samples_of_a_sound() -> Result<(), Box<dyn Error>> {
-	let mut manager = AudioManager::new(Default::default(), MockBackend::new(1)).unwrap();
+	let mut manager = AudioManager::new(
+		AudioManagerSettings {
+			main_track_limiter: None,
+			..Default::default()
+		},
+		MockBackend::new(1),
+	)
+	.unwrap();
 	let mut sound = manager.add_sound(StaticSound::from_frames(
 		1,
 		vec![
@@ -195,7 +203,14 @@ fn loops_when_playing_backward() -> Result<(), Box<dyn Error>> {
 
 #[test]
 fn stops_with_fade_out() -> Result<(), Box<dyn Error>> {
-	let mut manager = AudioManager::new(Default::default(), MockBackend::new(1)).unwrap();
+	let mut manager = AudioManager::new(
+		AudioManagerSettings {
+			main_track_limiter: None,
+			..Default::default()
+		},
+		MockBackend::new(1),
+	)
+	.unwrap();
 	let mut sound = manager.add_sound(StaticSound::from_frames(
 		1,
 		vec![Frame::from_mono(4.0); 10],
@@ -222,7 +237,14 @@ fn stops_with_fade_out() -> Result<(), Box<dyn Error>> {
 #[test]
 #[allow(clippy::float_cmp)]
 fn pauses_and_resumes_with_fade() -> Result<(), Box<dyn Error>> {
-	let mut manager = AudioManager::new(Default::default(), MockBackend::new(1)).unwrap();
+	let mut manager = AudioManager::new(
+		AudioManagerSettings {
+			main_track_limiter: None,
+			..Default::default()
+		},
+		MockBackend::new(1),
+	)
+	.unwrap();
 	let mut sound = manager.add_sound(StaticSound::from_frames(
 		1,
 		vec![Frame::from_mono(4.0); 10],
@@ -297,3 +319,88 @@ fn pauses_and_resumes_with_fade() -> Result<(), Box<dyn Error>> {
 
 	Ok(())
 }
+
+#[test]
+fn smooths_volume_changes() -> Result<(), Box<dyn Error>> {
+	let mut manager = AudioManager::new(
+		AudioManagerSettings {
+			default_smoothing: Smoothing::Linear(Duration::from_secs(4)),
+			..Default::default()
+		},
+		MockBackend::new(1),
+	)
+	.unwrap();
+	let mut sound = manager.add_sound(StaticSound::from_frames(
+		1,
+		vec![Frame::from_mono(1.0); 10],
+		Default::default(),
+	))?;
+	let mut instance = sound.play(Default::default())?;
+	manager.backend_mut().on_start_processing(0.0);
+	assert_frame_approximate_eq(manager.backend_mut().process(), Frame::from_mono(1.0));
+	instance.set_volume(0.0, None)?;
+	manager.backend_mut().on_start_processing(0.0);
+	for expected in [0.75, 0.5, 0.25, 0.0, 0.0] {
+		assert_frame_approximate_eq(manager.backend_mut().process(), Frame::from_mono(expected));
+	}
+	// instances can opt out of smoothing
+	let mut instance = sound.play(InstanceSettings::new().smoothing(Smoothing::None))?;
+	manager.backend_mut().on_start_processing(0.0);
+	instance.set_volume(0.5, None)?;
+	manager.backend_mut().on_start_processing(0.0);
+	assert_frame_approximate_eq(manager.backend_mut().process(), Frame::from_mono(0.5));
+	Ok(())
+}
+
+#[test]
+fn smooths_volume_changes_with_one_pole_smoothing() -> Result<(), Box<dyn Error>> {
+	let mut manager = AudioManager::new(
+		AudioManagerSettings {
+			default_smoothing: Smoothing::OnePole(Duration::from_secs(1)),
+			main_track_limiter: None,
+			..Default::default()
+		},
+		MockBackend::new(1),
+	)
+	.unwrap();
+	let mut sound = manager.add_sound(StaticSound::from_frames(
+		1,
+		vec![Frame::from_mono(1.0); 10],
+		Default::default(),
+	))?;
+	let mut instance = sound.play(Default::default())?;
+	manager.backend_mut().on_start_processing(0.0);
+	assert_frame_approximate_eq(manager.backend_mut().process(), Frame::from_mono(1.0));
+	instance.set_volume(0.0, None)?;
+	manager.backend_mut().on_start_processing(0.0);
+	// the volume should glide towards the new value instead of jumping to it
+	let output = manager.backend_mut().process();
+	assert!(output.left > 0.0 && output.left < 1.0);
+	Ok(())
+}
+
+#[test]
+fn tweens_volume_changes() -> Result<(), Box<dyn Error>> {
+	let mut manager = AudioManager::new(Default::default(), MockBackend::new(1)).unwrap();
+	let mut sound = manager.add_sound(StaticSound::from_frames(
+		1,
+		vec![Frame::from_mono(1.0); 10],
+		Default::default(),
+	))?;
+	let mut instance = sound.play(InstanceSettings::new().smoothing(Smoothing::None))?;
+	manager.backend_mut().on_start_processing(0.0);
+	assert_frame_approximate_eq(manager.backend_mut().process(), Frame::from_mono(1.0));
+	// tweens are used even if smoothing is disabled
+	instance.set_volume(
+		0.0,
+		Tween {
+			duration: Duration::from_secs(4),
+			..Default::default()
+		},
+	)?;
+	manager.backend_mut().on_start_processing(0.0);
+	for expected in [0.75, 0.5, 0.25, 0.0, 0.0] {
+		assert_frame_approximate_eq(manager.backend_mut().process(), Frame::from_mono(expected));
+	}
+	Ok(())
+}
@@ -0,0 +1,163 @@
+use std::error::Error;
+
+use kira::{
+	manager::{error::AddModulatorError, resources::Parameters, AudioManager, MockBackend},
+	modulator::{EnvelopeSettings, LfoSettings, ModulatorId, SetModulatorSettingError, Waveform},
+	sound::{instance::InstanceSettings, static_sound::StaticSound},
+	track::{Effect, TrackSettings},
+	Frame,
+};
+
+fn assert_approximate_eq(a: f64, b: f64) {
+	const ERROR_THRESHOLD: f64 = 1.0e-6;
+	if (a - b).abs() > ERROR_THRESHOLD {
+		assert_eq!(a, b);
+	}
+}
+
+fn assert_frame_approximate_eq(a: Frame, b: Frame) {
+	const ERROR_THRESHOLD: f32 = 1.0e-6;
+	if (a.left - b.left).abs() > ERROR_THRESHOLD || (a.right - b.right).abs() > ERROR_THRESHOLD {
+		assert_eq!(a, b);
+	}
+}
+
+/// An effect that publishes the left channel of its input.
+struct PublishInput {
+	output: ModulatorId,
+}
+
+impl Effect for PublishInput {
+	fn process(&mut self, input: Frame, _dt: f64, parameters: &Parameters) -> Frame {
+		parameters.publish(self.output, input.left as f64);
+		input
+	}
+}
+
+#[test]
+fn lfo_modulates_instance_volume() -> Result<(), Box<dyn Error>> {
+	let mut manager = AudioManager::new(Default::default(), MockBackend::new(4)).unwrap();
+	let lfo = manager.add_lfo(LfoSettings::new().waveform(Waveform::Square))?;
+	let mut sound = manager.add_sound(StaticSound::from_frames(
+		4,
+		vec![Frame::from_mono(1.0); 100],
+		Default::default(),
+	))?;
+	sound.play(InstanceSettings::new().volume(&lfo))?;
+	manager.backend_mut().on_start_processing(0.0);
+	for expected in [1.0, -1.0, -1.0, 1.0, 1.0, -1.0] {
+		assert_frame_approximate_eq(manager.backend_mut().process(), Frame::from_mono(expected));
+	}
+	Ok(())
+}
+
+#[test]
+fn envelope_follows_adsr_stages() -> Result<(), Box<dyn Error>> {
+	let mut manager = AudioManager::new(Default::default(), MockBackend::new(10)).unwrap();
+	let mut envelope = manager.add_envelope(
+		EnvelopeSettings::new()
+			.attack(0.2)
+			.decay(0.2)
+			.sustain(0.5)
+			.release(0.2),
+	)?;
+	manager.backend_mut().on_start_processing(0.0);
+	manager.backend_mut().process();
+	manager.backend_mut().on_start_processing(0.0);
+	assert_approximate_eq(envelope.value(), 0.0);
+	envelope.trigger()?;
+	manager.backend_mut().on_start_processing(0.0);
+	for expected in [0.5, 1.0, 0.75, 0.5, 0.5] {
+		manager.backend_mut().process();
+		manager.backend_mut().on_start_processing(0.0);
+		assert_approximate_eq(envelope.value(), expected);
+	}
+	envelope.release()?;
+	manager.backend_mut().on_start_processing(0.0);
+	for expected in [0.25, 0.0, 0.0] {
+		manager.backend_mut().process();
+		manager.backend_mut().on_start_processing(0.0);
+		assert_approximate_eq(envelope.value(), expected);
+	}
+	Ok(())
+}
+
+#[test]
+fn envelope_skips_zero_length_stages() -> Result<(), Box<dyn Error>> {
+	let mut manager = AudioManager::new(Default::default(), MockBackend::new(10)).unwrap();
+	let mut envelope = manager.add_envelope(
+		EnvelopeSettings::new()
+			.attack(0.0)
+			.decay(0.0)
+			.sustain(0.5)
+			.release(0.0),
+	)?;
+	manager.backend_mut().on_start_processing(0.0);
+	envelope.trigger()?;
+	manager.backend_mut().on_start_processing(0.0);
+	// attack jumps straight to full volume
+	manager.backend_mut().process();
+	manager.backend_mut().on_start_processing(0.0);
+	assert_approximate_eq(envelope.value(), 1.0);
+	// decay jumps straight to the sustain level
+	manager.backend_mut().process();
+	manager.backend_mut().on_start_processing(0.0);
+	assert_approximate_eq(envelope.value(), 0.5);
+	// release jumps straight to silence
+	envelope.release()?;
+	manager.backend_mut().on_start_processing(0.0);
+	manager.backend_mut().process();
+	manager.backend_mut().on_start_processing(0.0);
+	assert_approximate_eq(envelope.value(), 0.0);
+	Ok(())
+}
+
+#[test]
+fn effects_publish_to_effect_outputs() -> Result<(), Box<dyn Error>> {
+	let mut manager = AudioManager::new(Default::default(), MockBackend::new(1)).unwrap();
+	let effect_output = manager.add_effect_output()?;
+	let track = manager.add_sub_track(TrackSettings::new().with_effect(PublishInput {
+		output: effect_output.id(),
+	}))?;
+	let mut sound = manager.add_sound(StaticSound::from_frames(
+		1,
+		vec![
+			Frame::from_mono(0.25),
+			Frame::from_mono(0.5),
+			Frame::from_mono(0.75),
+		],
+		Default::default(),
+	))?;
+	sound.play(InstanceSettings::new().track(&track))?;
+	manager.backend_mut().on_start_processing(0.0);
+	for expected in [0.25, 0.5, 0.75] {
+		manager.backend_mut().process();
+		manager.backend_mut().on_start_processing(0.0);
+		assert_approximate_eq(effect_output.value(), expected);
+	}
+	Ok(())
+}
+
+#[test]
+fn modulator_settings_cannot_link_to_modulators() -> Result<(), Box<dyn Error>> {
+	let mut manager = AudioManager::new(Default::default(), MockBackend::new(1)).unwrap();
+	let mut lfo = manager.add_lfo(LfoSettings::new())?;
+	assert!(matches!(
+		manager.add_lfo(LfoSettings::new().rate(&lfo)),
+		Err(AddModulatorError::LinkedToModulator)
+	));
+	assert!(matches!(
+		manager.add_envelope(EnvelopeSettings::new().sustain(&lfo)),
+		Err(AddModulatorError::LinkedToModulator)
+	));
+	let lfo_id = lfo.id();
+	assert!(matches!(
+		lfo.set_depth(lfo_id),
+		Err(SetModulatorSettingError::LinkedToModulator)
+	));
+	// parameters can still be linked
+	let parameter = manager.add_parameter(2.0)?;
+	manager.add_lfo(LfoSettings::new().rate(&parameter))?;
+	lfo.set_depth(&parameter)?;
+	Ok(())
+}
@@ -0,0 +1,82 @@
+use std::error::Error;
+
+use kira::{
+	manager::{AudioManager, MockBackend},
+	parameter::{Automation, AutomationLoopBehavior, Easing},
+};
+
+fn assert_approximate_eq(a: f64, b: f64) {
+	const ERROR_THRESHOLD: f64 = 1.0e-6;
+	if (a - b).abs() > ERROR_THRESHOLD {
+		assert_eq!(a, b);
+	}
+}
+
+#[test]
+fn follows_automation_keyframes() -> Result<(), Box<dyn Error>> {
+	let mut manager = AudioManager::new(Default::default(), MockBackend::new(2)).unwrap();
+	let mut parameter = manager.add_parameter(0.0)?;
+	parameter.set_automation(
+		Automation::new()
+			.with_keyframe(2.0, 3.0, Easing::Linear)
+			.with_keyframe(1.0, 1.0, Easing::Linear),
+	)?;
+	manager.backend_mut().on_start_processing(0.0);
+	for expected in [0.5, 1.0, 2.0, 3.0, 3.0] {
+		manager.backend_mut().process();
+		manager.backend_mut().on_start_processing(0.0);
+		assert_approximate_eq(parameter.value(), expected);
+	}
+	Ok(())
+}
+
+#[test]
+fn loops_and_ping_pongs_automation() -> Result<(), Box<dyn Error>> {
+	let mut manager = AudioManager::new(Default::default(), MockBackend::new(2)).unwrap();
+	let automation = Automation::new()
+		.with_keyframe(1.0, 1.0, Easing::Linear)
+		.with_keyframe(2.0, 3.0, Easing::Linear);
+	let mut looping = manager.add_parameter(0.0)?;
+	looping.set_automation(
+		automation
+			.clone()
+			.loop_behavior(AutomationLoopBehavior::Loop),
+	)?;
+	let mut ping_pong = manager.add_parameter(0.0)?;
+	ping_pong.set_automation(automation.loop_behavior(AutomationLoopBehavior::PingPong))?;
+	manager.backend_mut().on_start_processing(0.0);
+	for (expected_looping, expected_ping_pong) in [
+		(0.5, 0.5),
+		(1.0, 1.0),
+		(2.0, 2.0),
+		(3.0, 3.0),
+		(2.0, 2.0),
+		(1.0, 1.0),
+		(2.0, 2.0),
+		(1.0, 3.0),
+	] {
+		manager.backend_mut().process();
+		manager.backend_mut().on_start_processing(0.0);
+		assert_approximate_eq(looping.value(), expected_looping);
+		assert_approximate_eq(ping_pong.value(), expected_ping_pong);
+	}
+	Ok(())
+}
+
+#[test]
+fn cancels_automation() -> Result<(), Box<dyn Error>> {
+	let mut manager = AudioManager::new(Default::default(), MockBackend::new(1)).unwrap();
+	let mut parameter = manager.add_parameter(0.0)?;
+	parameter.set_automation(Automation::new().with_keyframe(10.0, 10.0, Easing::Linear))?;
+	manager.backend_mut().on_start_processing(0.0);
+	manager.backend_mut().process();
+	manager.backend_mut().process();
+	parameter.cancel_automation()?;
+	manager.backend_mut().on_start_processing(0.0);
+	for _ in 0..3 {
+		manager.backend_mut().process();
+		manager.backend_mut().on_start_processing(0.0);
+		assert_approximate_eq(parameter.value(), 2.0);
+	}
+	Ok(())
+}
@@ -0,0 +1,658 @@
+use std::{
+	collections::VecDeque,
+	error::Error,
+	sync::{
+		atomic::{AtomicBool, AtomicUsize, Ordering},
+		Arc,
+	},
+	time::Duration,
+};
+
+use kira::{
+	clock::ClockId,
+	manager::{
+		error::AddSubTrackError, resources::Parameters, AudioManager, AudioManagerSettings,
+		MockBackend,
+	},
+	parameter::Tween,
+	sound::{instance::InstanceSettings, static_sound::StaticSound},
+	track::{
+		DuckingSettings, Effect, MainTrackLimiterSettings, SetRouteError, TrackId, TrackRoutes,
+		TrackSettings,
+	},
+	value::Smoothing,
+	Frame,
+};
+
+fn assert_frame_approximate_eq(a: Frame, b: Frame) {
+	const ERROR_THRESHOLD: f32 = 1.0e-6;
+	if (a.left - b.left).abs() > ERROR_THRESHOLD || (a.right - b.right).abs() > ERROR_THRESHOLD {
+		assert_eq!(a, b);
+	}
+}
+
+/// An effect that outputs its sidechain input.
+struct SidechainPassthrough {
+	sidechain: TrackId,
+	sidechain_input: Frame,
+}
+
+impl Effect for SidechainPassthrough {
+	fn sidechain(&self) -> Option<TrackId> {
+		Some(self.sidechain)
+	}
+
+	fn receive_sidechain_input(&mut self, input: Frame) {
+		self.sidechain_input = input;
+	}
+
+	fn process(&mut self, _input: Frame, _dt: f64, _parameters: &Parameters) -> Frame {
+		self.sidechain_input
+	}
+}
+
+/// An effect that outputs the position of a clock,
+/// or -1 if the clock isn't ticking.
+struct ClockPosition {
+	clock: ClockId,
+}
+
+impl Effect for ClockPosition {
+	fn process(&mut self, _input: Frame, _dt: f64, parameters: &Parameters) -> Frame {
+		Frame::from_mono(parameters.clock_position(self.clock).unwrap_or(-1.0) as f32)
+	}
+}
+
+/// An effect that counts how many times it's been processed.
+struct ProcessCounter {
+	tail_length: Option<f64>,
+	count: Arc<AtomicUsize>,
+}
+
+impl Effect for ProcessCounter {
+	fn tail_length(&self) -> Option<f64> {
+		self.tail_length
+	}
+
+	fn process(&mut self, input: Frame, _dt: f64, _parameters: &Parameters) -> Frame {
+		self.count.fetch_add(1, Ordering::SeqCst);
+		input
+	}
+}
+
+/// An effect that records whether it's been shut down.
+struct ShutdownFlag {
+	shut_down: Arc<AtomicBool>,
+}
+
+impl Effect for ShutdownFlag {
+	fn shutdown(&mut self) {
+		self.shut_down.store(true, Ordering::SeqCst);
+	}
+
+	fn process(&mut self, input: Frame, _dt: f64, _parameters: &Parameters) -> Frame {
+		input
+	}
+}
+
+/// An effect that delays its input by a number of frames
+/// and reports that delay as its latency.
+struct FrameDelay {
+	buffer: VecDeque<Frame>,
+}
+
+impl FrameDelay {
+	fn new(frames: usize) -> Self {
+		Self {
+			buffer: vec![Frame::ZERO; frames].into(),
+		}
+	}
+}
+
+impl Effect for FrameDelay {
+	fn latency(&self) -> usize {
+		self.buffer.len()
+	}
+
+	fn process(&mut self, input: Frame, _dt: f64, _parameters: &Parameters) -> Frame {
+		self.buffer.push_back(input);
+		self.buffer.pop_front().unwrap()
+	}
+}
+
+#[test]
+fn effects_receive_sidechain_input() -> Result<(), Box<dyn Error>> {
+	let mut manager = AudioManager::new(
+		AudioManagerSettings {
+			main_track_limiter: None,
+			..Default::default()
+		},
+		MockBackend::new(1),
+	)
+	.unwrap();
+	let dialog_track = manager.add_sub_track(
+		TrackSettings::new().routes(TrackRoutes::new().with_route(TrackId::Main, 0.0)),
+	)?;
+	let _music_track =
+		manager.add_sub_track(TrackSettings::new().with_effect(SidechainPassthrough {
+			sidechain: dialog_track.id(),
+			sidechain_input: Frame::ZERO,
+		}))?;
+	let mut sound = manager.add_sound(StaticSound::from_frames(
+		1,
+		vec![
+			Frame::from_mono(1.0),
+			Frame::from_mono(2.0),
+			Frame::from_mono(3.0),
+		],
+		Default::default(),
+	))?;
+	sound.play(InstanceSettings::new().track(&dialog_track))?;
+	manager.backend_mut().on_start_processing(0.0);
+	// the music track is processed before the dialog track, so it
+	// receives the dialog track's output from the previous frame
+	for expected in [0.0, 1.0, 2.0, 3.0] {
+		assert_frame_approximate_eq(manager.backend_mut().process(), Frame::from_mono(expected));
+	}
+	Ok(())
+}
+
+#[test]
+fn main_track_limiter_keeps_output_under_ceiling() -> Result<(), Box<dyn Error>> {
+	let mut manager = AudioManager::new(
+		AudioManagerSettings {
+			main_track_limiter: Some(
+				MainTrackLimiterSettings::new()
+					.look_ahead(Duration::from_secs(2))
+					.release(Duration::from_secs(0)),
+			),
+			..Default::default()
+		},
+		MockBackend::new(1),
+	)
+	.unwrap();
+	let mut sound = manager.add_sound(StaticSound::from_frames(
+		1,
+		vec![
+			Frame::from_mono(0.5),
+			Frame::from_mono(0.5),
+			Frame::from_mono(2.0),
+			Frame::from_mono(0.5),
+		],
+		Default::default(),
+	))?;
+	sound.play(Default::default())?;
+	manager.backend_mut().on_start_processing(0.0);
+	assert!(!manager.main_track_limiter_engaged());
+	// the output is delayed by the look-ahead time
+	for _ in 0..2 {
+		assert_frame_approximate_eq(manager.backend_mut().process(), Frame::ZERO);
+	}
+	for _ in 0..6 {
+		let frame = manager.backend_mut().process();
+		assert!(frame.left.abs() <= 1.0 && frame.right.abs() <= 1.0);
+	}
+	assert!(manager.main_track_limiter_engaged());
+	assert!(!manager.main_track_limiter_engaged());
+	Ok(())
+}
+
+#[test]
+fn effects_can_read_clock_positions() -> Result<(), Box<dyn Error>> {
+	let mut manager = AudioManager::new(
+		AudioManagerSettings {
+			main_track_limiter: None,
+			..Default::default()
+		},
+		MockBackend::new(4),
+	)
+	.unwrap();
+	let mut clock = manager.add_clock(1.0)?;
+	let _track = manager
+		.add_sub_track(TrackSettings::new().with_effect(ClockPosition { clock: clock.id() }))?;
+	manager.backend_mut().on_start_processing(0.0);
+	assert_frame_approximate_eq(manager.backend_mut().process(), Frame::from_mono(-1.0));
+	clock.start()?;
+	manager.backend_mut().on_start_processing(0.0);
+	for expected in [0.25, 0.5, 0.75, 1.0, 1.25] {
+		assert_frame_approximate_eq(manager.backend_mut().process(), Frame::from_mono(expected));
+	}
+	clock.pause()?;
+	manager.backend_mut().on_start_processing(0.0);
+	assert_frame_approximate_eq(manager.backend_mut().process(), Frame::from_mono(-1.0));
+	Ok(())
+}
+
+#[test]
+fn skips_effects_after_their_tails_end() -> Result<(), Box<dyn Error>> {
+	let mut manager = AudioManager::new(Default::default(), MockBackend::new(4)).unwrap();
+	let tail_count = Arc::new(AtomicUsize::new(0));
+	let no_tail_count = Arc::new(AtomicUsize::new(0));
+	let track = manager.add_sub_track(
+		TrackSettings::new()
+			.with_effect(ProcessCounter {
+				tail_length: Some(0.5),
+				count: tail_count.clone(),
+			})
+			.with_effect(ProcessCounter {
+				tail_length: None,
+				count: no_tail_count.clone(),
+			}),
+	)?;
+	let mut sound = manager.add_sound(StaticSound::from_frames(
+		4,
+		vec![Frame::from_mono(1.0)],
+		Default::default(),
+	))?;
+	sound.play(InstanceSettings::new().track(&track))?;
+	manager.backend_mut().on_start_processing(0.0);
+	for _ in 0..6 {
+		manager.backend_mut().process();
+	}
+	// the first effect is processed for the frame with input
+	// and the two frames of its tail
+	assert_eq!(tail_count.load(Ordering::SeqCst), 3);
+	// effects without a tail length are always processed
+	assert_eq!(no_tail_count.load(Ordering::SeqCst), 6);
+	Ok(())
+}
+
+#[test]
+fn compensates_for_effect_latency() -> Result<(), Box<dyn Error>> {
+	let mut manager = AudioManager::new(
+		AudioManagerSettings {
+			main_track_limiter: None,
+			..Default::default()
+		},
+		MockBackend::new(1),
+	)
+	.unwrap();
+	let wet_track = manager.add_sub_track(TrackSettings::new().with_effect(FrameDelay::new(2)))?;
+	let dry_track = manager.add_sub_track(TrackSettings::new())?;
+	// sends audio to the main track directly and through
+	// the wet and dry tracks
+	let send_track = manager.add_sub_track(
+		TrackSettings::new().routes(
+			TrackRoutes::new()
+				.with_route(&wet_track, 1.0)
+				.with_route(&dry_track, 1.0),
+		),
+	)?;
+	let mut sound = manager.add_sound(StaticSound::from_frames(
+		1,
+		vec![Frame::from_mono(1.0)],
+		Default::default(),
+	))?;
+	sound.play(InstanceSettings::new().track(&send_track))?;
+	manager.backend_mut().on_start_processing(0.0);
+	let outputs = (0..5)
+		.map(|_| manager.backend_mut().process())
+		.collect::<Vec<_>>();
+	// every path is delayed to line up with the wet track
+	assert_frame_approximate_eq(outputs[0], Frame::ZERO);
+	assert_frame_approximate_eq(outputs[1], Frame::ZERO);
+	assert_ne!(outputs[2], Frame::ZERO);
+	assert_frame_approximate_eq(outputs[3], Frame::ZERO);
+	assert_frame_approximate_eq(outputs[4], Frame::ZERO);
+	Ok(())
+}
+
+#[test]
+fn ducks_tracks_while_source_is_loud() -> Result<(), Box<dyn Error>> {
+	let mut manager = AudioManager::new(
+		AudioManagerSettings {
+			main_track_limiter: None,
+			..Default::default()
+		},
+		MockBackend::new(1),
+	)
+	.unwrap();
+	let dialog_track = manager.add_sub_track(
+		TrackSettings::new().routes(TrackRoutes::new().with_route(TrackId::Main, 0.0)),
+	)?;
+	let music_track = manager.add_sub_track(
+		TrackSettings::new().ducking(
+			DuckingSettings::new(&dialog_track)
+				.threshold(-30.0)
+				.amount(20.0)
+				.attack(Duration::ZERO)
+				.release(Duration::ZERO),
+		),
+	)?;
+	let mut music = manager.add_sound(StaticSound::from_frames(
+		1,
+		vec![Frame::from_mono(1.0); 4],
+		Default::default(),
+	))?;
+	let mut dialog = manager.add_sound(StaticSound::from_frames(
+		1,
+		vec![
+			Frame::from_mono(0.0),
+			Frame::from_mono(1.0),
+			Frame::from_mono(0.0),
+			Frame::from_mono(0.0),
+		],
+		Default::default(),
+	))?;
+	music.play(InstanceSettings::new().track(&music_track))?;
+	dialog.play(InstanceSettings::new().track(&dialog_track))?;
+	manager.backend_mut().on_start_processing(0.0);
+	// the music track is processed before the dialog track, so it
+	// reacts to the dialog track's output from the previous frame
+	for expected in [1.0, 1.0, 0.1, 1.0] {
+		assert_frame_approximate_eq(manager.backend_mut().process(), Frame::from_mono(expected));
+	}
+	Ok(())
+}
+
+#[test]
+fn routed_audio_arrives_in_the_same_frame() -> Result<(), Box<dyn Error>> {
+	let mut manager = AudioManager::new(
+		AudioManagerSettings {
+			main_track_limiter: None,
+			..Default::default()
+		},
+		MockBackend::new(1),
+	)
+	.unwrap();
+	let bus_track = manager.add_sub_track(TrackSettings::new())?;
+	let group_track = manager.add_sub_track(
+		TrackSettings::new().routes(
+			TrackRoutes::new()
+				.with_route(TrackId::Main, 0.0)
+				.with_route(&bus_track, 1.0),
+		),
+	)?;
+	let source_track = manager.add_sub_track(
+		TrackSettings::new().routes(
+			TrackRoutes::new()
+				.with_route(TrackId::Main, 0.0)
+				.with_route(&group_track, 1.0)
+				.with_route(&bus_track, 1.0),
+		),
+	)?;
+	let mut sound = manager.add_sound(StaticSound::from_frames(
+		1,
+		vec![Frame::from_mono(1.0), Frame::from_mono(0.0)],
+		Default::default(),
+	))?;
+	sound.play(InstanceSettings::new().track(&source_track))?;
+	manager.backend_mut().on_start_processing(0.0);
+	// the bus track receives the source track's audio directly
+	// and through the group track
+	assert_frame_approximate_eq(manager.backend_mut().process(), Frame::from_mono(2.0));
+	assert_frame_approximate_eq(manager.backend_mut().process(), Frame::ZERO);
+	Ok(())
+}
+
+#[test]
+fn routes_can_be_changed_at_runtime() -> Result<(), Box<dyn Error>> {
+	let mut manager = AudioManager::new(
+		AudioManagerSettings {
+			main_track_limiter: None,
+			..Default::default()
+		},
+		MockBackend::new(1),
+	)
+	.unwrap();
+	let mut source_track = manager.add_sub_track(TrackSettings::new())?;
+	// the bus track is newer than the source track, so the mixer
+	// has to reorder the tracks once the source track is routed to it
+	let bus_track = manager.add_sub_track(TrackSettings::new().volume(0.5))?;
+	let mut sound = manager.add_sound(StaticSound::from_frames(
+		1,
+		vec![Frame::from_mono(1.0); 4],
+		Default::default(),
+	))?;
+	sound.play(InstanceSettings::new().track(&source_track))?;
+	manager.backend_mut().on_start_processing(0.0);
+	assert_frame_approximate_eq(manager.backend_mut().process(), Frame::from_mono(1.0));
+	source_track.set_route(TrackId::Main, 0.0, Tween::default())?;
+	source_track.set_route(&bus_track, 1.0, Tween::default())?;
+	manager.backend_mut().on_start_processing(0.0);
+	assert_frame_approximate_eq(manager.backend_mut().process(), Frame::from_mono(0.5));
+	source_track.remove_route(&bus_track)?;
+	manager.backend_mut().on_start_processing(0.0);
+	assert_frame_approximate_eq(manager.backend_mut().process(), Frame::ZERO);
+	Ok(())
+}
+
+#[test]
+fn pre_fader_routes_ignore_track_volume() -> Result<(), Box<dyn Error>> {
+	let mut manager = AudioManager::new(
+		AudioManagerSettings {
+			main_track_limiter: None,
+			..Default::default()
+		},
+		MockBackend::new(1),
+	)
+	.unwrap();
+	let mut source_track = manager.add_sub_track(TrackSettings::new().volume(0.0))?;
+	let bus_track = manager.add_sub_track(TrackSettings::new())?;
+	source_track.set_pre_fader_route(&bus_track, 0.5, Tween::default())?;
+	let mut sound = manager.add_sound(StaticSound::from_frames(
+		1,
+		vec![Frame::from_mono(1.0)],
+		Default::default(),
+	))?;
+	sound.play(InstanceSettings::new().track(&source_track))?;
+	manager.backend_mut().on_start_processing(0.0);
+	assert_frame_approximate_eq(manager.backend_mut().process(), Frame::from_mono(0.5));
+	Ok(())
+}
+
+#[test]
+fn rejects_routing_cycles() -> Result<(), Box<dyn Error>> {
+	let mut manager = AudioManager::new(Default::default(), MockBackend::new(1)).unwrap();
+	let mut first_track = manager.add_sub_track(TrackSettings::new())?;
+	let mut second_track = manager.add_sub_track(
+		TrackSettings::new().routes(TrackRoutes::new().with_route(&first_track, 1.0)),
+	)?;
+	let third_track = manager.add_sub_track(TrackSettings::new())?;
+	second_track.set_route(&third_track, 1.0, None)?;
+	assert!(matches!(
+		first_track.set_route(&second_track, 1.0, None),
+		Err(SetRouteError::RoutingCycle)
+	));
+	let first_track_id = first_track.id();
+	assert!(matches!(
+		first_track.set_route(first_track_id, 1.0, None),
+		Err(SetRouteError::RoutingCycle)
+	));
+	first_track.set_route(&third_track, 1.0, None)?;
+	Ok(())
+}
+
+#[test]
+fn meters_track_levels() -> Result<(), Box<dyn Error>> {
+	const SAMPLE_RATE: u32 = 48000;
+	let mut manager = AudioManager::new(
+		AudioManagerSettings {
+			main_track_limiter: None,
+			..Default::default()
+		},
+		MockBackend::new(SAMPLE_RATE),
+	)
+	.unwrap();
+	let track = manager.add_sub_track(TrackSettings::new())?;
+	assert_eq!(track.meter().peak, 0.0);
+	assert_eq!(track.meter().momentary_loudness, f64::NEG_INFINITY);
+	// one second of a 1kHz sine wave at -20dBFS
+	let mut sound = manager.add_sound(StaticSound::from_frames(
+		SAMPLE_RATE,
+		(0..SAMPLE_RATE)
+			.map(|i| {
+				let phase = i as f32 * 1000.0 / SAMPLE_RATE as f32;
+				Frame::from_mono(0.1 * (phase * std::f32::consts::TAU).sin())
+			})
+			.collect(),
+		Default::default(),
+	))?;
+	sound.play(InstanceSettings::new().track(&track))?;
+	manager.backend_mut().on_start_processing(0.0);
+	for _ in 0..SAMPLE_RATE {
+		manager.backend_mut().process();
+	}
+	let meter = track.meter();
+	assert!((meter.peak - 0.1).abs() < 1.0e-3);
+	assert!((meter.rms - 0.1 / 2.0f64.sqrt()).abs() < 1.0e-3);
+	// K-weighting turns up 1kHz by about as much as the
+	// loudness formula turns it down
+	assert!((meter.momentary_loudness - -20.0).abs() < 0.1);
+	// the short-term loudness is measured over 3 seconds,
+	// 2 of which were silent
+	assert!((meter.short_term_loudness - (-20.0 - 10.0 * 3.0f64.log10())).abs() < 0.1);
+	Ok(())
+}
+
+#[test]
+fn mutes_and_solos_tracks() -> Result<(), Box<dyn Error>> {
+	let mut manager = AudioManager::new(
+		AudioManagerSettings {
+			main_track_limiter: None,
+			..Default::default()
+		},
+		MockBackend::new(1),
+	)
+	.unwrap();
+	let mut reverb_track = manager.add_sub_track(TrackSettings::new().solo_safe(true))?;
+	let group_track = manager.add_sub_track(TrackSettings::new())?;
+	let mut soloed_track = manager.add_sub_track(
+		TrackSettings::new().routes(
+			TrackRoutes::new()
+				.with_route(TrackId::Main, 0.0)
+				.with_route(&group_track, 1.0),
+		),
+	)?;
+	let other_track = manager.add_sub_track(TrackSettings::new())?;
+	let mut sounds = vec![];
+	for (track, amplitude) in [
+		(&soloed_track, 1.0),
+		(&other_track, 10.0),
+		(&reverb_track, 100.0),
+	] {
+		let mut sound = manager.add_sound(StaticSound::from_frames(
+			1,
+			vec![Frame::from_mono(amplitude); 4],
+			Default::default(),
+		))?;
+		sound.play(InstanceSettings::new().track(track))?;
+		sounds.push(sound);
+	}
+	manager.backend_mut().on_start_processing(0.0);
+	assert_frame_approximate_eq(manager.backend_mut().process(), Frame::from_mono(111.0));
+	// the group track stays audible because the soloed track
+	// is routed to it, and the reverb track is solo-safe
+	soloed_track.set_soloed(true)?;
+	manager.backend_mut().on_start_processing(0.0);
+	assert_frame_approximate_eq(manager.backend_mut().process(), Frame::from_mono(101.0));
+	reverb_track.set_muted(true)?;
+	manager.backend_mut().on_start_processing(0.0);
+	assert_frame_approximate_eq(manager.backend_mut().process(), Frame::from_mono(1.0));
+	soloed_track.set_soloed(false)?;
+	manager.backend_mut().on_start_processing(0.0);
+	assert_frame_approximate_eq(manager.backend_mut().process(), Frame::from_mono(11.0));
+	Ok(())
+}
+
+#[test]
+fn vcas_scale_track_volumes() -> Result<(), Box<dyn Error>> {
+	let mut manager = AudioManager::new(
+		AudioManagerSettings {
+			main_track_limiter: None,
+			..Default::default()
+		},
+		MockBackend::new(1),
+	)
+	.unwrap();
+	let mut vca = manager.add_vca(0.5)?;
+	let first_track = manager.add_sub_track(TrackSettings::new().vca(&vca))?;
+	let mut second_track = manager.add_sub_track(TrackSettings::new().volume(0.5))?;
+	second_track.set_vca(Some(vca.id()))?;
+	let mut sounds = vec![];
+	for track in [&first_track, &second_track] {
+		let mut sound = manager.add_sound(StaticSound::from_frames(
+			1,
+			vec![Frame::from_mono(1.0); 4],
+			Default::default(),
+		))?;
+		sound.play(InstanceSettings::new().track(track))?;
+		sounds.push(sound);
+	}
+	manager.backend_mut().on_start_processing(0.0);
+	assert_frame_approximate_eq(manager.backend_mut().process(), Frame::from_mono(0.75));
+	vca.set_volume(0.0, Tween::default())?;
+	manager.backend_mut().on_start_processing(0.0);
+	assert_frame_approximate_eq(manager.backend_mut().process(), Frame::ZERO);
+	// once the VCA is removed, the tracks go back to their own volume
+	drop(vca);
+	manager.backend_mut().on_start_processing(0.0);
+	assert_frame_approximate_eq(manager.backend_mut().process(), Frame::from_mono(1.5));
+	Ok(())
+}
+
+#[test]
+fn one_pole_smoothing_approaches_new_volume_exponentially() -> Result<(), Box<dyn Error>> {
+	let mut manager = AudioManager::new(
+		AudioManagerSettings {
+			main_track_limiter: None,
+			..Default::default()
+		},
+		MockBackend::new(1),
+	)
+	.unwrap();
+	let mut track = manager.add_sub_track(
+		TrackSettings::new().smoothing(Smoothing::OnePole(Duration::from_secs(1))),
+	)?;
+	let mut sound = manager.add_sound(StaticSound::from_frames(
+		1,
+		vec![Frame::from_mono(1.0); 10],
+		Default::default(),
+	))?;
+	let _instance = sound.play(InstanceSettings::new().track(&track))?;
+	manager.backend_mut().on_start_processing(0.0);
+	assert_frame_approximate_eq(manager.backend_mut().process(), Frame::from_mono(1.0));
+	track.set_volume(0.0, None)?;
+	manager.backend_mut().on_start_processing(0.0);
+	// with a time constant of one frame, the volume should cover
+	// 1 - 1/e of the remaining distance every frame
+	for frame in 1..=4 {
+		let expected = (-(frame as f32)).exp();
+		assert_frame_approximate_eq(manager.backend_mut().process(), Frame::from_mono(expected));
+	}
+	Ok(())
+}
+
+#[test]
+fn shuts_down_effects_when_tracks_are_removed() -> Result<(), Box<dyn Error>> {
+	let mut manager = AudioManager::new(Default::default(), MockBackend::new(1)).unwrap();
+	let shut_down = Arc::new(AtomicBool::new(false));
+	let track = manager.add_sub_track(TrackSettings::new().with_effect(ShutdownFlag {
+		shut_down: shut_down.clone(),
+	}))?;
+	manager.backend_mut().on_start_processing(0.0);
+	manager.backend_mut().process();
+	assert!(!shut_down.load(Ordering::SeqCst));
+	drop(track);
+	manager.backend_mut().on_start_processing(0.0);
+	assert!(shut_down.load(Ordering::SeqCst));
+	Ok(())
+}
+
+#[test]
+fn rejects_routes_to_nonexistent_tracks() -> Result<(), Box<dyn Error>> {
+	let mut manager = AudioManager::new(Default::default(), MockBackend::new(1)).unwrap();
+	let mut track = manager.add_sub_track(TrackSettings::new())?;
+	let removed_track_id = manager.add_sub_track(TrackSettings::new())?.id();
+	assert!(matches!(
+		manager.add_sub_track(
+			TrackSettings::new().routes(TrackRoutes::new().with_route(removed_track_id, 1.0))
+		),
+		Err(AddSubTrackError::NonexistentTrack)
+	));
+	assert!(matches!(
+		track.set_route(removed_track_id, 1.0, None),
+		Err(SetRouteError::NonexistentTrack)
+	));
+	Ok(())
+}